@@ -0,0 +1,302 @@
+//! Bodies torn apart by black holes, stars and giant planets, the streams of
+//! debris they are stretched into, the glowing disks the debris settles into
+//! around black holes and the rings it settles into around planets.
+
+use ::rand::{self, Rng};
+use macroquad::prelude::*;
+use planets_core::{collision, Body, Merge};
+use std::collections::HashMap;
+use std::f32::consts::TAU;
+
+use crate::camera::View;
+use crate::planet_class::PlanetClass;
+use crate::{BodyKind, Planet};
+
+/// Share of the textbook Roche radius bodies get shredded at. Drawn radii
+/// grow with the log of the mass, so the real formula would reach several
+/// times further out than any orbit gets close.
+const ROCHE_SCALE: f32 = 0.3;
+/// The same for stars, which drawn this size would otherwise be denser than
+/// anything orbiting them, so that their Roche radius ends up just outside
+/// them, where rocky planets really do break up.
+const STAR_ROCHE_SCALE: f32 = 0.12;
+/// Planets are drawn at their real densities, so for moons the textbook
+/// formula holds.
+const PLANET_ROCHE_SCALE: f32 = 1.;
+/// Inner and outer edge of a disk, in radii of its hole.
+const DISK_EDGES: (f32, f32) = (1.5, 6.);
+/// Particles that keep circling while nothing is being fed in.
+const QUIET_PARTICLES: usize = 60;
+/// Pieces a shredded body breaks into, per square root of its mass and at
+/// least and most.
+const PIECES_PER_MASS: f32 = 8.;
+const PIECES: (usize, usize) = (40, 300);
+/// Pieces on their way in at any one time, the oldest dropped beyond that.
+const MAX_DEBRIS: usize = 3000;
+/// Days a stream lasts before what's left of it settles into the disk, or
+/// just fades around a star.
+const STREAM_DAYS: f32 = 150.;
+/// Steps per day the pieces' orbits are integrated in.
+const ORBIT_SUBSTEPS: usize = 8;
+/// Size of the patches of a ring whose pieces collide with each other, in
+/// world units across and in sectors around.
+const RING_CELL: f32 = 2.;
+const RING_SECTORS: f32 = 36.;
+/// Share per day of the difference from the mean motion of its patch that a
+/// ring piece loses in collisions. That takes out the random motions,
+/// leaving the pieces on circular orbits.
+const RING_DAMPING: f32 = 0.02;
+/// Radians per second at the inner edge, slower further out as in a
+/// Keplerian disk.
+const SPIN: f32 = 3.;
+/// Inner edge radii a particle sinks per second.
+const SINK: f32 = 0.15;
+
+/// Distance inside which `hole` tears `body` apart.
+pub fn roche_radius(hole: &Planet, body: &Planet) -> f32 {
+  let scale = match hole.kind {
+    BodyKind::Star => STAR_ROCHE_SCALE,
+    BodyKind::Planet => PLANET_ROCHE_SCALE,
+    BodyKind::BlackHole => ROCHE_SCALE,
+  };
+  scale * 2f32.cbrt() * body.radius(1.) * (hole.mass / body.mass).cbrt()
+}
+
+/// What `hole` can tear apart: anything but another hole, and for a star or
+/// a giant planet nothing but planets.
+fn tears(hole: &Planet, body: &Planet) -> bool {
+  let kind = match hole.kind {
+    BodyKind::BlackHole => body.kind != BodyKind::BlackHole,
+    BodyKind::Star => body.kind == BodyKind::Planet,
+    BodyKind::Planet => body.kind == BodyKind::Planet && giant(hole),
+  };
+  kind && body.mass < hole.mass
+}
+
+/// Feeds every body that strayed inside a black hole's or star's Roche radius
+/// to it, returning each as a merge into it.
+pub fn shred(objects: &mut Vec<Planet>) -> Vec<Merge<Planet>> {
+  let mut shredded = Vec::new();
+  while let Some((hole, body)) = first_shredded(objects) {
+    shredded.push(collision::absorb(objects, hole, body));
+  }
+  shredded
+}
+
+/// Only giants pull moons apart, which also keeps the swarms of small bodies
+/// from all being checked against each other.
+fn giant(planet: &Planet) -> bool {
+  planet.class() != PlanetClass::Rocky
+}
+
+fn first_shredded(objects: &[Planet]) -> Option<(usize, usize)> {
+  let active = || objects.iter().enumerate().filter(|(_, p)| p.active());
+  active()
+    .filter(|(_, hole)| hole.kind != BodyKind::Planet || giant(hole))
+    .find_map(|(h, hole)| {
+      active()
+        .filter(|(_, p)| tears(hole, p))
+        .find(|(_, p)| p.pos().distance(hole.pos()) < roche_radius(hole, p))
+        .map(|(b, _)| (h, b))
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Particle {
+  /// Distance from the hole in radii of it.
+  radius: f32,
+  angle: f32,
+}
+
+/// A piece of a shredded body on its own orbit around what tore it.
+#[derive(Debug, Clone, Copy)]
+struct Debris {
+  hole: u32,
+  /// Position and velocity relative to the hole.
+  pos: Vec2,
+  velocity: Vec2,
+  /// Days since the body was torn apart.
+  age: f32,
+  color: Color,
+  /// Around a planet, where it stays to become part of a ring.
+  ring: bool,
+}
+
+/// Debris disks of every black hole, by hole id, and the streams on their way
+/// into them.
+#[derive(Debug, Default)]
+pub struct Disks {
+  disks: HashMap<u32, Vec<Particle>>,
+  streams: Vec<Debris>,
+}
+
+impl Disks {
+  /// Breaks `body`, just torn apart by `hole`, into pieces spread along the
+  /// line to the hole, all moving as it was. The near pieces are more bound
+  /// than the far ones, so over an orbit they draw out into a long thin
+  /// stream.
+  pub fn feed(&mut self, hole: &Planet, body: &Planet) {
+    let (least, most) = PIECES;
+    let count = ((body.mass.sqrt() * PIECES_PER_MASS) as usize).clamp(least, most);
+    let offset = body.pos - hole.pos;
+    let velocity = body.velocity - hole.velocity;
+    let (out, across) = (offset.normalize_or_zero(), velocity.normalize_or_zero());
+    let size = body.radius(1.);
+    let mut rng = rand::thread_rng();
+    for i in 0..count {
+      let along = i as f32 / (count - 1) as f32 * 2. - 1.;
+      let spread = rng.gen_range(-0.2..0.2);
+      self.streams.push(Debris {
+        hole: hole.id,
+        pos: offset + (out * along + across * spread) * size,
+        velocity,
+        age: 0.,
+        color: body.color,
+        ring: hole.kind == BodyKind::Planet,
+      });
+    }
+    let over = self.streams.len().saturating_sub(MAX_DEBRIS);
+    self.streams.drain(..over);
+  }
+
+  /// Moves the streams on by `dt` days. Pieces falling into a star are gone,
+  /// and old enough pieces join the disk of their black hole if they are
+  /// within its edges. Pieces around planets stay, settling into a ring.
+  pub fn step(&mut self, objects: &[Planet], g: f32, dt: f32) {
+    let (inner, outer) = DISK_EDGES;
+    let dt = dt / ORBIT_SUBSTEPS as f32;
+    let disks = &mut self.disks;
+    self.streams.retain_mut(|d| {
+      let Some(hole) = objects.iter().find(|p| p.id == d.hole) else {
+        return false;
+      };
+      let unit = hole.radius(1.);
+      let surface = match hole.kind {
+        BodyKind::BlackHole => inner * unit,
+        _ => unit,
+      };
+      for _ in 0..ORBIT_SUBSTEPS {
+        let r = d.pos.length();
+        if r < surface {
+          return false;
+        }
+        d.velocity -= d.pos * g * hole.mass / (r * r * r) * dt;
+        d.pos += d.velocity * dt;
+      }
+      d.age += dt * ORBIT_SUBSTEPS as f32;
+      if d.ring || d.age < STREAM_DAYS {
+        return true;
+      }
+      let radius = d.pos.length() / unit;
+      if hole.kind == BodyKind::BlackHole && radius < outer {
+        disks.entry(hole.id).or_default().push(Particle {
+          radius,
+          angle: d.pos.y.atan2(d.pos.x),
+        });
+      }
+      false
+    });
+    self.damp_rings(dt * ORBIT_SUBSTEPS as f32);
+  }
+
+  /// Pulls the velocity of every ring piece towards the mean of those around
+  /// it, as if they were gently bumping into each other.
+  fn damp_rings(&mut self, dt: f32) {
+    let cell = |d: &Debris| {
+      let angle = d.pos.y.atan2(d.pos.x) / TAU * RING_SECTORS;
+      (
+        d.hole,
+        (d.pos.length() / RING_CELL) as i32,
+        angle.floor() as i32,
+      )
+    };
+    let mut patches: HashMap<_, (Vec2, f32)> = HashMap::new();
+    for d in self.streams.iter().filter(|d| d.ring) {
+      let patch = patches.entry(cell(d)).or_default();
+      patch.0 += d.velocity;
+      patch.1 += 1.;
+    }
+    let share = (RING_DAMPING * dt).min(1.);
+    for d in self.streams.iter_mut().filter(|d| d.ring) {
+      let (sum, count) = patches[&cell(d)];
+      d.velocity += (sum / count - d.velocity) * share;
+    }
+  }
+
+  /// Moves the particles on by `dt` real seconds. Particles past the inner
+  /// edge fall in, and quiet disks get new ones at the outer edge.
+  pub fn update(&mut self, objects: &[Planet], dt: f32) {
+    let (inner, outer) = DISK_EDGES;
+    let holes = || objects.iter().filter(|p| p.kind == BodyKind::BlackHole);
+    self.disks.retain(|id, _| holes().any(|p| p.id == *id));
+    let mut rng = rand::thread_rng();
+    for hole in holes() {
+      let disk = self.disks.entry(hole.id).or_default();
+      for p in disk.iter_mut() {
+        let kepler = (inner / p.radius).powf(1.5);
+        p.angle += SPIN * kepler * dt;
+        p.radius -= SINK * inner * kepler * dt;
+      }
+      disk.retain(|p| p.radius > inner);
+      while disk.len() < QUIET_PARTICLES {
+        disk.push(Particle {
+          radius: rng.gen_range(inner..outer),
+          angle: rng.gen_range(0. ..TAU),
+        });
+      }
+    }
+  }
+
+  /// Draws the streams, and the disks under the holes, which hide their
+  /// inner edges.
+  pub fn render(&self, objects: &[Planet], view: &View) {
+    let size = (view.scale * 0.4).max(1.);
+    for d in &self.streams {
+      let Some(hole) = objects.iter().find(|p| p.id == d.hole) else {
+        continue;
+      };
+      let s = view.world_to_screen(hole.pos + d.pos);
+      let fade = if d.ring {
+        1.
+      } else {
+        (1. - d.age / STREAM_DAYS).clamp(0., 1.).sqrt()
+      };
+      draw_circle(
+        s.x,
+        s.y,
+        size,
+        Color {
+          a: 0.9 * fade,
+          ..d.color
+        },
+      );
+    }
+
+    let (inner, _) = DISK_EDGES;
+    for hole in objects.iter().filter(|p| p.kind == BodyKind::BlackHole) {
+      let Some(disk) = self.disks.get(&hole.id) else {
+        continue;
+      };
+      let center = view.world_to_screen(hole.pos);
+      let unit = hole.radius(view.scale);
+      let size = (unit * 0.08).max(1.);
+      for p in disk {
+        let s = center + Vec2::from_angle(p.angle) * p.radius * unit;
+        // a thin disk heats up as r^-3/4 towards its inner edge
+        let heat = (inner / p.radius).powf(0.75);
+        draw_circle(s.x, s.y, size, temperature_color(heat));
+      }
+    }
+  }
+}
+
+/// Dull red when cool through orange and yellow to blue-white at `heat` 1.
+fn temperature_color(heat: f32) -> Color {
+  let t = heat.clamp(0., 1.);
+  Color::new(
+    1.,
+    (0.2 + 1.2 * t).min(1.),
+    (2. * t - 1.).max(0.) * 0.9 + 0.05,
+    0.3 + 0.6 * t,
+  )
+}
@@ -0,0 +1,127 @@
+//! Next closest approach of two measured bodies, found by running a light
+//! copy of the system ahead instead of extrapolating in a straight line.
+
+use planets_core::{integrate, Body, Vec3};
+
+use crate::{Planet, DAY};
+
+/// Days looked ahead at most.
+const HORIZON: u32 = 2000;
+/// Frames between predictions, since each runs the whole system ahead.
+const EVERY: u32 = 10;
+
+/// Just what the physics needs of a body, cheap to copy.
+#[derive(Debug, Clone, Copy)]
+struct Point {
+  id: u32,
+  mass: f32,
+  pos: Vec3,
+  velocity: Vec3,
+}
+
+impl Body for Point {
+  fn id(&self) -> u32 {
+    self.id
+  }
+
+  fn mass(&self) -> f32 {
+    self.mass
+  }
+
+  fn pos(&self) -> Vec3 {
+    self.pos
+  }
+
+  fn velocity(&self) -> Vec3 {
+    self.velocity
+  }
+
+  fn radius(&self) -> f32 {
+    0.
+  }
+
+  fn set_mass(&mut self, mass: f32) {
+    self.mass = mass;
+  }
+
+  fn set_pos(&mut self, pos: Vec3) {
+    self.pos = pos;
+  }
+
+  fn set_velocity(&mut self, velocity: Vec3) {
+    self.velocity = velocity;
+  }
+}
+
+/// Predicted closest approach.
+#[derive(Debug, Clone, Copy)]
+pub struct Approach {
+  /// Simulation day it happens on.
+  pub day: u64,
+  pub distance: f32,
+}
+
+/// Keeps the latest prediction for a pair, refreshed every few frames.
+#[derive(Debug, Default)]
+pub struct Predictor {
+  pair: Option<(u32, u32)>,
+  countdown: u32,
+  latest: Option<Approach>,
+}
+
+impl Predictor {
+  /// The prediction for `pair`, starting over when the pair changes. None
+  /// when the closest approach is further off than the look-ahead.
+  pub fn update(
+    &mut self,
+    objects: &[Planet],
+    pair: Option<(u32, u32)>,
+    g: f32,
+    day: u64,
+  ) -> Option<Approach> {
+    if pair != self.pair {
+      self.pair = pair;
+      self.countdown = 0;
+      self.latest = None;
+    }
+    let (a, b) = pair?;
+    if self.countdown == 0 {
+      self.countdown = EVERY;
+      self.latest = predict(objects, a, b, g, day);
+    }
+    self.countdown -= 1;
+    self.latest.filter(|approach| approach.day >= day)
+  }
+}
+
+/// Steps a copy of the active bodies a day at a time until `a` and `b`
+/// stop closing in, under their gravity alone.
+fn predict(objects: &[Planet], a: u32, b: u32, g: f32, day: u64) -> Option<Approach> {
+  let mut points = Vec::from_iter(objects.iter().filter(|p| p.active()).map(|p| Point {
+    id: p.id,
+    mass: p.mass,
+    pos: p.pos(),
+    velocity: p.velocity(),
+  }));
+  let ia = points.iter().position(|p| p.id == a)?;
+  let ib = points.iter().position(|p| p.id == b)?;
+  let distance = |points: &[Point]| points[ia].pos.distance(points[ib].pos);
+
+  let mut last = distance(&points);
+  let mut closing = false;
+  for n in 0..HORIZON {
+    let pairs = integrate::close_pairs(&points, g, DAY);
+    integrate::kick(&mut points, &pairs, g, DAY, None);
+    integrate::drift(&mut points, &pairs, g, DAY);
+    let d = distance(&points);
+    if closing && d > last {
+      return Some(Approach {
+        day: day + n as u64,
+        distance: last,
+      });
+    }
+    closing = d < last;
+    last = d;
+  }
+  None
+}
@@ -0,0 +1,165 @@
+//! Many random systems run without drawing, spread over every core, for
+//! the statistics of how systems end up: how many bodies collide, get torn
+//! apart or thrown out, and how many survive.
+
+use ::rand::{rngs::StdRng, SeedableRng};
+use macroquad::prelude::*;
+use planets_core::energy;
+use std::fmt::Write;
+use std::fs;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+
+use crate::profiler::Timings;
+use crate::{advance, random_setup, BodyKind, Physics, Planet, SimEvent};
+
+/// How one system ended up.
+#[derive(Debug, Clone, Copy)]
+struct Outcome {
+  seed: u64,
+  /// Planets at the start and at the end.
+  planets: usize,
+  survivors: usize,
+  collisions: u32,
+  shredded: u32,
+  ejections: u32,
+  /// Change of total energy over the run relative to where it started,
+  /// including what collisions and ejections carried off.
+  energy: f64,
+}
+
+fn planets(objects: &[Planet]) -> usize {
+  objects
+    .iter()
+    .filter(|p| p.kind == BodyKind::Planet)
+    .count()
+}
+
+fn run_one(
+  seed: u64,
+  steps: u64,
+  palette: &[Color],
+  physics: &Physics,
+  energy_correction: u32,
+) -> Outcome {
+  let mut objects = random_setup(palette, &mut StdRng::seed_from_u64(seed), physics);
+  let start = energy::total(&objects, physics.g);
+  let mut drift = energy::Drift::new(energy_correction);
+  let mut outcome = Outcome {
+    seed,
+    planets: planets(&objects),
+    survivors: 0,
+    collisions: 0,
+    shredded: 0,
+    ejections: 0,
+    energy: 0.,
+  };
+  for _ in 0..steps {
+    let (happened, _) = advance(&mut objects, physics, &mut drift, &mut Timings::default());
+    for event in happened {
+      match event {
+        SimEvent::Merge(_) => outcome.collisions += 1,
+        SimEvent::Shredded(_) => outcome.shredded += 1,
+        SimEvent::Ejection(_) => outcome.ejections += 1,
+        SimEvent::Stellar(..) => {}
+      }
+    }
+    // nothing fades out here, so ejected bodies can go at once
+    objects.retain(|p| p.departing.is_none());
+  }
+  outcome.survivors = planets(&objects);
+  if start != 0. {
+    outcome.energy = (energy::total(&objects, physics.g) - start) / start.abs();
+  }
+  outcome
+}
+
+/// Runs `systems` random systems for `steps` steps each, seeded from `seed`
+/// on, and returns the report, also written to `path` if given.
+pub fn run(
+  systems: u32,
+  steps: u64,
+  seed: u64,
+  palette: &[Color],
+  physics: &Physics,
+  energy_correction: u32,
+  path: Option<&str>,
+) -> Result<String, String> {
+  let threads = thread::available_parallelism().map_or(1, |n| n.get());
+  let next = AtomicU32::new(0);
+  let done = AtomicU32::new(0);
+  let mut outcomes = thread::scope(|scope| {
+    let workers = Vec::from_iter((0..threads).map(|_| {
+      scope.spawn(|| {
+        let mut outcomes = Vec::new();
+        loop {
+          let i = next.fetch_add(1, Ordering::Relaxed);
+          if i >= systems {
+            break outcomes;
+          }
+          let seed = seed.wrapping_add(i as u64);
+          outcomes.push(run_one(seed, steps, palette, physics, energy_correction));
+          let done = done.fetch_add(1, Ordering::Relaxed) + 1;
+          eprintln!("system {}/{} done", done, systems);
+        }
+      })
+    }));
+    Vec::from_iter(workers.into_iter().flat_map(|w| w.join().unwrap()))
+  });
+  outcomes.sort_by_key(|o| o.seed);
+
+  let report = summarize(&outcomes, steps);
+  if let Some(path) = path {
+    fs::write(path, &report).map_err(|e| format!("{}: {}", path, e))?;
+  }
+  Ok(report)
+}
+
+fn summarize(outcomes: &[Outcome], steps: u64) -> String {
+  let mut text = String::new();
+  let _ = writeln!(text, "{} systems, {} days each\n", outcomes.len(), steps);
+  let _ = writeln!(
+    text,
+    "{:>20}  {:>7}  {:>9}  {:>10}  {:>8}  {:>9}  {:>10}",
+    "seed", "planets", "survivors", "collisions", "shredded", "ejections", "energy"
+  );
+  for o in outcomes {
+    let _ = writeln!(
+      text,
+      "{:>20}  {:>7}  {:>9}  {:>10}  {:>8}  {:>9}  {:>+10.2e}",
+      o.seed, o.planets, o.survivors, o.collisions, o.shredded, o.ejections, o.energy
+    );
+  }
+
+  let count = outcomes.len().max(1) as f64;
+  let mean = |f: fn(&Outcome) -> f64| outcomes.iter().map(f).sum::<f64>() / count;
+  let survival = mean(|o| o.survivors as f64 / o.planets.max(1) as f64);
+  let _ = writeln!(text);
+  let _ = writeln!(
+    text,
+    "mean planets {:.1}, survivors {:.1} ({:.0}% survive)",
+    mean(|o| o.planets as f64),
+    mean(|o| o.survivors as f64),
+    survival * 100.
+  );
+  let _ = writeln!(
+    text,
+    "mean collisions {:.1}, shredded {:.1}, ejections {:.1}",
+    mean(|o| o.collisions as f64),
+    mean(|o| o.shredded as f64),
+    mean(|o| o.ejections as f64)
+  );
+  let stable = outcomes
+    .iter()
+    .filter(|o| o.collisions + o.shredded + o.ejections == 0)
+    .count();
+  let _ = writeln!(
+    text,
+    "{} of {} systems lost nothing",
+    stable,
+    outcomes.len()
+  );
+  let worst = outcomes.iter().map(|o| o.energy.abs()).fold(0., f64::max);
+  let _ = writeln!(text, "largest energy change {:.2e}", worst);
+  text
+}
@@ -0,0 +1,46 @@
+use macroquad::prelude::*;
+
+use crate::camera::View;
+use crate::{Planet, CULL_DISTANCE};
+
+/// Bodies beyond this share of the cull distance get a warning.
+const WARNING_DISTANCE: f32 = 0.85;
+
+/// Faint circle where bodies get culled, shown once zoomed out far enough
+/// for it to come into view.
+pub fn render(view: &View) {
+  let center = view.world_to_screen(Vec2::ZERO);
+  let radius = CULL_DISTANCE * view.scale;
+  let reach = vec2(screen_width(), screen_height()).length();
+  // fades in as the circle shrinks towards the screen size
+  let alpha = ((reach * 1.5 - radius) / reach).clamp(0., 1.) * 0.4;
+  if alpha > 0. {
+    draw_circle_lines(
+      center.x,
+      center.y,
+      radius,
+      1.,
+      Color::new(1., 0.3, 0.3, alpha),
+    );
+  }
+}
+
+/// Flashing rings around bodies about to cross the boundary.
+pub fn render_warnings(objects: &[Planet], view: &View) {
+  let flash = (get_time() as f32 * 8.).sin() * 0.5 + 0.5;
+  for p in objects.iter() {
+    let closeness = p.pos.length() / CULL_DISTANCE;
+    if closeness < WARNING_DISTANCE {
+      continue;
+    }
+    let s = view.world_to_screen(p.pos);
+    let urgency = (closeness - WARNING_DISTANCE) / (1. - WARNING_DISTANCE);
+    draw_circle_lines(
+      s.x,
+      s.y,
+      p.radius(view.scale) + 6.,
+      2.,
+      Color::new(1., 0.2, 0.2, flash * (0.3 + 0.7 * urgency)),
+    );
+  }
+}
@@ -0,0 +1,175 @@
+use macroquad::prelude::*;
+
+use crate::{VIRTUAL_HEIGHT, VIRTUAL_WIDTH};
+
+const ZOOM_STEP: f32 = 1.1;
+const MIN_ZOOM: f32 = 0.05;
+const MAX_ZOOM: f32 = 20.;
+/// Seconds a glide over to a new target takes.
+const GLIDE: f64 = 1.5;
+
+/// User pan and zoom on top of following a body, the system's central one
+/// unless told otherwise.
+#[derive(Debug)]
+pub struct Camera {
+  /// Pan relative to the followed position, in world units.
+  pub offset: Vec2,
+  pub zoom: f32,
+  /// Part of the screen the camera shows, the whole screen when None.
+  pub area: Option<Rect>,
+  drag: Option<Vec2>,
+  glide: Option<Glide>,
+}
+
+/// Where a camera was coming from when it was sent to a new target.
+#[derive(Debug, Clone, Copy)]
+struct Glide {
+  /// Difference between the old view center and the new one when it
+  /// started, shrunk to nothing along the way.
+  lag: Vec2,
+  zoom: f32,
+  started: f64,
+}
+
+impl Glide {
+  /// Eased share of the way done, 1 once there.
+  fn progress(&self) -> f32 {
+    let t = ((get_time() - self.started) / GLIDE).clamp(0., 1.) as f32;
+    t * t * (3. - 2. * t)
+  }
+}
+
+impl Default for Camera {
+  fn default() -> Self {
+    Camera {
+      offset: Vec2::ZERO,
+      zoom: 1.,
+      area: None,
+      drag: None,
+      glide: None,
+    }
+  }
+}
+
+impl Camera {
+  pub fn view(&self, follow: Vec2) -> View {
+    let (lag, zoom) = self.glided();
+    View {
+      zero: follow + self.offset + lag,
+      scale: base_scale() * zoom,
+      center: self.area.map_or_else(screen_center, |area| area.center()),
+    }
+  }
+
+  /// Eases over from following `from` to following `to`, ending at the
+  /// given pan and zoom.
+  pub fn glide_to(&mut self, from: Vec2, to: Vec2, offset: Vec2, zoom: f32) {
+    let before = self.view(from);
+    self.glide = Some(Glide {
+      lag: before.zero - (to + offset),
+      zoom: before.zoom(),
+      started: get_time(),
+    });
+    self.offset = offset;
+    self.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+  }
+
+  /// What is left of a glide in progress: the extra pan, and the zoom.
+  fn glided(&self) -> (Vec2, f32) {
+    match self.glide {
+      Some(glide) => {
+        let t = glide.progress();
+        let zoom = glide.zoom * (self.zoom / glide.zoom).powf(t);
+        (glide.lag * (1. - t), zoom)
+      }
+      None => (Vec2::ZERO, self.zoom),
+    }
+  }
+
+  /// Stops a glide where it is, so pan and zoom by hand start from what is
+  /// on screen.
+  fn settle(&mut self) {
+    let (lag, zoom) = self.glided();
+    self.offset += lag;
+    self.zoom = zoom;
+    self.glide = None;
+  }
+
+  /// Mouse wheel zooms around the cursor, right-button drag pans.
+  pub fn handle_input(&mut self, follow: Vec2) {
+    let mouse = Vec2::from(mouse_position());
+
+    let (_, wheel) = mouse_wheel();
+    if wheel != 0. {
+      self.zoom_at(follow, mouse, ZOOM_STEP.powf(wheel.signum()));
+    }
+
+    if is_mouse_button_down(MouseButton::Right) {
+      if let Some(last) = self.drag {
+        self.pan(follow, mouse - last);
+      }
+      self.drag = Some(mouse);
+    } else {
+      self.drag = None;
+    }
+  }
+
+  /// Multiplies the zoom while keeping the world point under `screen` fixed.
+  pub fn zoom_at(&mut self, follow: Vec2, screen: Vec2, factor: f32) {
+    self.settle();
+    let before = self.view(follow).screen_to_world(screen);
+    self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+    let after = self.view(follow).screen_to_world(screen);
+    self.offset += before - after;
+  }
+
+  /// Moves the view along with a drag of `delta` screen pixels.
+  pub fn pan(&mut self, follow: Vec2, delta: Vec2) {
+    self.settle();
+    self.offset -= delta / self.view(follow).scale;
+  }
+
+  /// Ends a drag in progress, for when the mouse is handled by another
+  /// camera.
+  pub fn release(&mut self) {
+    self.drag = None;
+  }
+}
+
+/// World to screen mapping for a single frame.
+#[derive(Debug, Clone, Copy)]
+pub struct View {
+  /// World position shown at the center of the screen.
+  pub zero: Vec2,
+  /// Screen pixels per world unit.
+  pub scale: f32,
+  /// Screen point `zero` is drawn at.
+  pub center: Vec2,
+}
+
+impl View {
+  pub fn world_to_screen(&self, p: Vec2) -> Vec2 {
+    self.center + (p - self.zero) * self.scale
+  }
+
+  pub fn screen_to_world(&self, p: Vec2) -> Vec2 {
+    (p - self.center) / self.scale + self.zero
+  }
+
+  /// Zoom relative to the default fit-to-window scale.
+  pub fn zoom(&self) -> f32 {
+    self.scale / base_scale()
+  }
+}
+
+/// Screen pixels per world unit at zoom 1.
+///
+/// Uniform in both axes and fitted so the whole virtual frame stays visible
+/// whatever the window aspect ratio is.
+pub fn base_scale() -> f32 {
+  (screen_width() / VIRTUAL_WIDTH).min(screen_height() / VIRTUAL_HEIGHT)
+}
+
+pub fn screen_center() -> Vec2 {
+  vec2(screen_width() / 2., screen_height() / 2.)
+}
@@ -0,0 +1,155 @@
+//! How chaotic the selected body's orbit is, from how fast a shadow copy
+//! started a hair away drifts off from it: an estimate of the largest
+//! Lyapunov exponent.
+
+use macroquad::prelude::*;
+
+use crate::Planet;
+
+/// Starting distance of the shadow in phase space, where velocities count
+/// as the distance they cover in `VELOCITY_WEIGHT` days.
+const SEPARATION: f64 = 1e-4;
+const VELOCITY_WEIGHT: f64 = 10.;
+/// Days between pulling the shadow back to `SEPARATION`, before it has
+/// drifted so far that the growth stops being exponential.
+const RENORMALIZE_DAYS: u32 = 10;
+/// Steps per day the two copies are integrated in.
+const SUBSTEPS: usize = 8;
+/// Days of averaging before an orbit is called either way, and the exponent
+/// per day above which it counts as chaotic, a Lyapunov time under 500 days.
+const SETTLE_DAYS: f64 = 1000.;
+const CHAOTIC: f64 = 2e-3;
+
+/// A massless copy of a body, in f64 so the tiny separation survives.
+#[derive(Debug, Clone, Copy)]
+struct Particle {
+  pos: DVec2,
+  velocity: DVec2,
+}
+
+impl Particle {
+  fn of(body: &Planet) -> Self {
+    Particle {
+      pos: body.pos.as_dvec2(),
+      velocity: body.velocity.as_dvec2(),
+    }
+  }
+
+  /// Distance in phase space.
+  fn distance(&self, other: &Particle) -> f64 {
+    let dx = self.pos - other.pos;
+    let dv = (self.velocity - other.velocity) * VELOCITY_WEIGHT;
+    (dx.length_squared() + dv.length_squared()).sqrt()
+  }
+}
+
+/// Lyapunov exponent of an orbit so far.
+#[derive(Debug, Clone, Copy)]
+pub struct Estimate {
+  /// Per day.
+  pub exponent: f64,
+  pub days: f64,
+}
+
+impl Estimate {
+  pub fn label(&self) -> &'static str {
+    if self.days < SETTLE_DAYS {
+      "too early to tell"
+    } else if self.exponent > CHAOTIC {
+      "chaotic"
+    } else {
+      "regular so far"
+    }
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct Chaos {
+  body: Option<u32>,
+  /// The body and its shadow, both moved here the same way so that only
+  /// their separation tells them apart.
+  copies: Option<(Particle, Particle)>,
+  /// Sum of the logs of the growth over every renormalization.
+  growth: f64,
+  days: f64,
+  countdown: u32,
+}
+
+impl Chaos {
+  /// Call once per step with the selected body, `dt` days long.
+  pub fn step(&mut self, objects: &[Planet], selected: Option<u32>, g: f32, dt: f32) {
+    if selected != self.body {
+      *self = Chaos {
+        body: selected,
+        ..Default::default()
+      };
+    }
+    let Some(body) = selected.and_then(|id| objects.iter().find(|p| p.id == id)) else {
+      return;
+    };
+    let (reference, shadow) = self.copies.get_or_insert_with(|| {
+      let reference = Particle::of(body);
+      let mut shadow = reference;
+      shadow.pos.x += SEPARATION;
+      (reference, shadow)
+    });
+
+    let pulls = Vec::from_iter(
+      objects
+        .iter()
+        .filter(|p| p.id != body.id && p.departing.is_none())
+        .map(|p| (p.pos.as_dvec2(), g as f64 * p.mass as f64)),
+    );
+    let dt = dt as f64 / SUBSTEPS as f64;
+    for _ in 0..SUBSTEPS {
+      for p in [&mut *reference, &mut *shadow] {
+        let pull = pulls.iter().fold(DVec2::ZERO, |sum, &(pos, gm)| {
+          let r = pos - p.pos;
+          let d2 = r.length_squared();
+          if d2 > 0. {
+            sum + r * gm / (d2 * d2.sqrt())
+          } else {
+            sum
+          }
+        });
+        p.velocity += pull * dt;
+        p.pos += p.velocity * dt;
+      }
+    }
+    self.days += dt * SUBSTEPS as f64;
+
+    if self.countdown > 0 {
+      self.countdown -= 1;
+      return;
+    }
+    self.countdown = RENORMALIZE_DAYS - 1;
+    let distance = reference.distance(shadow);
+    if distance > 0. {
+      self.growth += (distance / SEPARATION).ln();
+    }
+    // start the next stretch from the body as it really is, with the shadow
+    // the same way off it as it drifted
+    let fresh = Particle::of(body);
+    let scale = if distance > 0. {
+      SEPARATION / distance
+    } else {
+      1.
+    };
+    *shadow = Particle {
+      pos: fresh.pos + (shadow.pos - reference.pos) * scale,
+      velocity: fresh.velocity + (shadow.velocity - reference.velocity) * scale,
+    };
+    *reference = fresh;
+  }
+
+  /// The estimate for body `id`, if it's the one being followed.
+  pub fn estimate(&self, id: u32) -> Option<Estimate> {
+    if self.body != Some(id) || self.days <= 0. {
+      return None;
+    }
+    Some(Estimate {
+      exponent: self.growth / self.days,
+      days: self.days,
+    })
+  }
+}
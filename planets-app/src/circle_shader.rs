@@ -0,0 +1,158 @@
+use macroquad::prelude::*;
+
+use crate::hidpi;
+
+/// How a circle is lit by a star.
+#[derive(Debug, Clone, Copy)]
+pub struct Lighting {
+  /// Screen-space direction towards the light source.
+  pub dir: Vec2,
+  /// Highlight the rim on the lit side.
+  pub rim: bool,
+  /// Darken the hemisphere facing away from the light.
+  pub terminator: bool,
+  /// Share of the light blocked by bodies in between, 0 is fully lit.
+  pub eclipse: f32,
+}
+
+/// Draws filled circles as quads shaded by a signed-distance function, so
+/// edges stay smooth at any radius instead of showing polygon facets.
+pub struct CircleShader {
+  material: Material,
+  /// Only there to make the quad carry 0..1 texture coordinates.
+  quad: Texture2D,
+}
+
+impl CircleShader {
+  pub fn new() -> Self {
+    let material = load_material(
+      VERTEX_SHADER,
+      FRAGMENT_SHADER,
+      MaterialParams {
+        uniforms: vec![
+          ("Light".to_owned(), UniformType::Float2),
+          ("Rim".to_owned(), UniformType::Float1),
+          ("Night".to_owned(), UniformType::Float1),
+          ("Eclipse".to_owned(), UniformType::Float1),
+          ("Radius".to_owned(), UniformType::Float1),
+          ("Edge".to_owned(), UniformType::Float1),
+          ("Bands".to_owned(), UniformType::Float1),
+        ],
+        ..Default::default()
+      },
+    )
+    .expect("circle shader should compile");
+    CircleShader {
+      material,
+      quad: Texture2D::from_rgba8(1, 1, &[255; 4]),
+    }
+  }
+
+  /// Draws a circle in screen space, flat colored unless `lighting` is set.
+  pub fn draw(&self, center: Vec2, radius: f32, color: Color, lighting: Option<Lighting>) {
+    self.draw_banded(center, radius, color, lighting, 0.);
+  }
+
+  /// Like `draw`, with `bands` darker stripes across the disc like the
+  /// cloud belts of a giant planet.
+  pub fn draw_banded(
+    &self,
+    center: Vec2,
+    radius: f32,
+    color: Color,
+    lighting: Option<Lighting>,
+    bands: f32,
+  ) {
+    // one pixel of padding leaves room for the anti-aliased edge
+    let pixel = hidpi::pixel();
+    let half = radius + pixel;
+
+    let l = lighting.unwrap_or(Lighting {
+      dir: Vec2::ZERO,
+      rim: false,
+      terminator: false,
+      eclipse: 0.,
+    });
+    let flag = |on: bool| if on { 1f32 } else { 0. };
+    self
+      .material
+      .set_uniform("Light", l.dir.normalize_or_zero());
+    self.material.set_uniform("Rim", flag(l.rim));
+    self
+      .material
+      .set_uniform("Night", flag(l.terminator) * NIGHT_DARKNESS);
+    self.material.set_uniform("Eclipse", l.eclipse);
+    self.material.set_uniform("Radius", radius / half);
+    self.material.set_uniform("Edge", pixel / half);
+    self.material.set_uniform("Bands", bands);
+
+    gl_use_material(self.material);
+    draw_texture_ex(
+      self.quad,
+      center.x - half,
+      center.y - half,
+      color,
+      DrawTextureParams {
+        dest_size: Some(vec2(half, half) * 2.),
+        ..Default::default()
+      },
+    );
+    gl_use_default_material();
+  }
+}
+
+/// Brightness removed from the night side.
+const NIGHT_DARKNESS: f32 = 0.75;
+
+const VERTEX_SHADER: &str = "#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+
+varying mediump vec2 uv;
+varying lowp vec4 color;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+  gl_Position = Projection * Model * vec4(position, 1);
+  color = color0 / 255.0;
+  uv = texcoord;
+}
+";
+
+const FRAGMENT_SHADER: &str = "#version 100
+precision mediump float;
+
+varying vec2 uv;
+varying vec4 color;
+
+uniform vec2 Light;
+uniform float Rim;
+uniform float Night;
+uniform float Eclipse;
+uniform float Radius;
+uniform float Edge;
+uniform float Bands;
+
+void main() {
+  vec2 p = uv * 2.0 - 1.0;
+  float d = length(p);
+  float coverage = clamp((Radius - d) / Edge + 0.5, 0.0, 1.0);
+
+  // Sphere normal from the disc position, brightened where it faces away
+  // from the viewer and towards the light.
+  vec2 n = p / Radius;
+  float facing = sqrt(max(1.0 - dot(n, n), 0.0));
+  float towards = dot(n, Light);
+  float rim = Rim * pow(1.0 - facing, 3.0) * max(towards, 0.0);
+
+  // soft two-tone terminator between the day and night hemispheres
+  float day = smoothstep(-0.08, 0.08, towards);
+  float light = mix(1.0 - Night, 1.0, day) * (1.0 - Eclipse);
+  light *= 1.0 - step(0.5, Bands) * 0.2 * (0.5 + 0.5 * cos(n.y * Bands * 3.14159));
+
+  gl_FragColor = vec4(color.rgb * light + rim * 0.6 * (1.0 - Eclipse), color.a * coverage);
+}
+";
@@ -0,0 +1,135 @@
+//! Command line flags.
+
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+pub const USAGE: &str =
+  "usage: planets [--import bodies.csv] [--lockstep <seed>] [--hash-every <steps>]
+               [--record replay.bin] [--verify replay.bin]
+               [--screensaver | --kiosk]
+               [--batch <systems> [--steps <days>] [--report report.txt]]";
+
+#[derive(Debug, Default)]
+pub struct Args {
+  /// CSV of bodies to start with instead of a random system.
+  pub import: Option<String>,
+  /// Seed for a deterministic run, see `lockstep`.
+  pub lockstep: Option<u64>,
+  /// Steps between state hashes in a deterministic run.
+  pub hash_every: u64,
+  /// Where to write the seed and state hashes of a deterministic run.
+  pub record: Option<String>,
+  /// Replay a recording headless and report where it diverges.
+  pub verify: Option<String>,
+  /// Run unattended until the first input, see `screensaver`.
+  pub screensaver: bool,
+  /// Run for the public with an idle reset, see `kiosk`.
+  pub kiosk: bool,
+  /// Random systems to run headless, see `batch`, seeded from the
+  /// `lockstep` seed on.
+  pub batch: Option<u32>,
+  /// Steps each batch system runs for.
+  pub steps: u64,
+  /// Where to write the batch report as well as printing it.
+  pub report: Option<String>,
+}
+
+impl Args {
+  pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args {
+      hash_every: 1000,
+      steps: 36500,
+      ..Default::default()
+    };
+    while let Some(arg) = args.next() {
+      let mut value = |flag: &str| args.next().ok_or(format!("{} needs a value", flag));
+      match arg.as_str() {
+        "--import" => parsed.import = Some(value(&arg)?),
+        "--lockstep" => parsed.lockstep = Some(number(value(&arg)?)?),
+        "--record" => parsed.record = Some(value(&arg)?),
+        "--verify" => parsed.verify = Some(value(&arg)?),
+        "--screensaver" => parsed.screensaver = true,
+        "--kiosk" => parsed.kiosk = true,
+        "--hash-every" => parsed.hash_every = number::<u64>(value(&arg)?)?.max(1),
+        "--batch" => parsed.batch = Some(number(value(&arg)?)?),
+        "--steps" => parsed.steps = number(value(&arg)?)?,
+        "--report" => parsed.report = Some(value(&arg)?),
+        "-h" | "--help" => return Err(USAGE.to_owned()),
+        _ => return Err(format!("unknown argument `{}`\n{}", arg, USAGE)),
+      }
+    }
+    if parsed.record.is_some() && parsed.lockstep.is_none() {
+      return Err("--record needs --lockstep".to_owned());
+    }
+    if parsed.report.is_some() && parsed.batch.is_none() {
+      return Err("--report needs --batch".to_owned());
+    }
+    if parsed.screensaver && parsed.kiosk {
+      return Err("--screensaver and --kiosk don't go together".to_owned());
+    }
+    Ok(parsed)
+  }
+}
+
+/// Out of range counts are errors rather than wrapping around.
+fn number<T: FromStr<Err = ParseIntError>>(s: String) -> Result<T, String> {
+  s.parse()
+    .map_err(|e| format!("invalid number `{}`: {}", s, e))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(args: &[&str]) -> Result<Args, String> {
+    Args::parse(args.iter().map(|&a| a.to_owned()))
+  }
+
+  #[test]
+  fn defaults() {
+    let args = parse(&[]).unwrap();
+    assert_eq!(
+      (args.hash_every, args.steps, args.batch),
+      (1000, 36500, None)
+    );
+  }
+
+  #[test]
+  fn flags() {
+    let args = parse(&[
+      "--lockstep",
+      "7",
+      "--record",
+      "run.bin",
+      "--hash-every",
+      "0",
+    ])
+    .unwrap();
+    assert_eq!(args.lockstep, Some(7));
+    assert_eq!(args.record.as_deref(), Some("run.bin"));
+    assert_eq!(args.hash_every, 1);
+    let args = parse(&["--batch", "20", "--steps", "500", "--report", "r.txt"]).unwrap();
+    assert_eq!((args.batch, args.steps), (Some(20), 500));
+  }
+
+  #[test]
+  fn unknown_flag() {
+    assert!(parse(&["--fast"]).is_err());
+    assert!(parse(&["seed"]).is_err());
+  }
+
+  #[test]
+  fn bad_values() {
+    assert!(parse(&["--batch", "4294967296"]).is_err());
+    assert!(parse(&["--lockstep"]).is_err());
+    assert!(parse(&["--lockstep", "-1"]).is_err());
+    assert!(parse(&["--steps", "many"]).is_err());
+  }
+
+  #[test]
+  fn conflicts() {
+    assert!(parse(&["--record", "run.bin"]).is_err());
+    assert!(parse(&["--report", "r.txt"]).is_err());
+    assert!(parse(&["--screensaver", "--kiosk"]).is_err());
+  }
+}
@@ -0,0 +1,388 @@
+use macroquad::prelude::{vec2, Color, KeyCode, Vec2};
+use planets_core::Potential;
+use std::{fmt::Display, fs, str::FromStr};
+
+use crate::frame::Frame;
+use crate::input::{self, Action};
+use crate::theme::{parse_hex_color, TrailStyle};
+
+/// Read from the working directory at startup when present.
+pub const CONFIG_PATH: &str = "planets.conf";
+
+/// User settings loaded from a `key = value` file.
+///
+/// Keys are dotted by section (`stars.count = 800`). A `#` at the start of a
+/// line or surrounded by whitespace starts a comment, so `#rrggbb` colors
+/// still work as values.
+/// Missing keys keep their defaults; malformed lines are reported and skipped.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+  pub stars: StarConfig,
+  pub meteors: MeteorConfig,
+  pub detail: DetailConfig,
+  pub measure: MeasureConfig,
+  pub tour: TourConfig,
+  pub screensaver: ScreensaverConfig,
+  pub kiosk: KioskConfig,
+  pub stream: StreamConfig,
+  pub theme: ThemeConfig,
+  pub planets: PlanetConfig,
+  pub physics: PhysicsConfig,
+  pub remote: RemoteConfig,
+  /// Rebound actions, `keys.reset = N`.
+  pub keys: Vec<(Action, KeyCode)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RemoteConfig {
+  /// Address the servers listen on, loopback unless opened up on purpose.
+  pub host: String,
+  /// UDP port to take OSC commands on, 0 for none.
+  pub osc_port: u16,
+  /// TCP port for the HTTP endpoints, 0 for none.
+  pub http_port: u16,
+  /// TCP port to broadcast snapshots to spectators on, 0 for none.
+  pub broadcast_port: u16,
+  /// `host:port` of a broadcasting instance to watch instead of simulating.
+  pub spectate: Option<String>,
+}
+
+impl Default for RemoteConfig {
+  fn default() -> Self {
+    RemoteConfig {
+      host: "127.0.0.1".to_owned(),
+      osc_port: 0,
+      http_port: 0,
+      broadcast_port: 0,
+      spectate: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlanetConfig {
+  /// Named palette for random planets, defaults to the theme's.
+  pub palette: Option<String>,
+  /// Trail color of captured bodies, or their own color when unset.
+  pub capture_color: Option<Color>,
+}
+
+impl Default for PlanetConfig {
+  fn default() -> Self {
+    PlanetConfig {
+      palette: None,
+      capture_color: Some(Color::new(1., 0.82, 0.3, 1.)),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct PhysicsConfig {
+  /// How new random systems balance their momentum.
+  pub frame: Frame,
+  /// Fastest circular orbit random planets are put on; closer ones are
+  /// moved out until their orbit is this slow.
+  pub orbit_speed_limit: Option<f32>,
+  /// Hard cap on every body's speed, for stability rather than realism.
+  pub speed_limit: Option<f32>,
+  /// Steps between rescaling velocities to hold total energy, 0 for never.
+  pub energy_correction: u32,
+  /// Trail points shared out between all bodies.
+  pub trail_budget: usize,
+  /// Largest tilt of random planets' orbits in degrees, 0 for flat systems.
+  pub inclination: f32,
+  /// Days a star of the sun's mass spends on the main sequence, 0 for
+  /// stars that never age.
+  pub star_lifetime: f32,
+  /// Bodies lighter than this are dust that starlight pushes on, 0 for
+  /// none.
+  pub dust_mass: f32,
+  /// Fixed background field around the origin, for the whole session.
+  pub potential: Option<Potential>,
+  /// Uniform acceleration on every planet at startup, see `Physics::field`.
+  pub field: Vec2,
+}
+
+impl Default for PhysicsConfig {
+  fn default() -> Self {
+    PhysicsConfig {
+      frame: Frame::Sun,
+      orbit_speed_limit: Some(crate::MAX_SPEED),
+      speed_limit: None,
+      energy_correction: 0,
+      trail_budget: 60_000,
+      inclination: 0.,
+      star_lifetime: 0.,
+      dust_mass: 0.,
+      potential: None,
+      field: Vec2::ZERO,
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct StarConfig {
+  pub count: usize,
+  /// Tint stars by a spectral class distribution instead of plain white.
+  pub spectral: bool,
+  /// Power-law index of the size distribution, larger means fewer big stars.
+  pub magnitude_exponent: f32,
+}
+
+impl Default for StarConfig {
+  fn default() -> Self {
+    StarConfig {
+      count: 500,
+      spectral: true,
+      magnitude_exponent: 2.5,
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct MeteorConfig {
+  /// Average shooting stars per minute, zero disables them.
+  pub per_minute: f32,
+}
+
+impl Default for MeteorConfig {
+  fn default() -> Self {
+    MeteorConfig { per_minute: 6. }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct DetailConfig {
+  /// Milliseconds of work per frame before cosmetic detail is scaled
+  /// down, zero disables scaling.
+  pub frame_budget: f32,
+}
+
+impl Default for DetailConfig {
+  fn default() -> Self {
+    DetailConfig { frame_budget: 14. }
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MeasureConfig {
+  /// Lower the time scale as a predicted approach of two measured bodies
+  /// nears, so it isn't over in a single frame.
+  pub slow_approach: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct TourConfig {
+  /// Seconds the tour stays at each stop.
+  pub seconds: f32,
+}
+
+impl Default for TourConfig {
+  fn default() -> Self {
+    TourConfig { seconds: 12. }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScreensaverConfig {
+  /// Minutes before the screensaver moves on to a new system.
+  pub minutes: f32,
+}
+
+impl Default for ScreensaverConfig {
+  fn default() -> Self {
+    ScreensaverConfig { minutes: 5. }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct KioskConfig {
+  /// Seconds without input before a kiosk starts over with a new system.
+  pub idle_seconds: f32,
+}
+
+impl Default for KioskConfig {
+  fn default() -> Self {
+    KioskConfig { idle_seconds: 120. }
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StreamConfig {
+  /// Flat background for a chroma or luma key to cut out, with the
+  /// backdrop left out.
+  pub key: Option<Color>,
+}
+
+/// A named theme plus optional overrides of its parts.
+#[derive(Debug, Clone)]
+pub struct ThemeConfig {
+  pub name: String,
+  pub background: Option<Color>,
+  pub stars: Option<Color>,
+  pub trail: Option<TrailStyle>,
+}
+
+impl Default for ThemeConfig {
+  fn default() -> Self {
+    ThemeConfig {
+      name: "dark".to_owned(),
+      background: None,
+      stars: None,
+      trail: None,
+    }
+  }
+}
+
+impl Config {
+  pub fn load() -> Self {
+    match fs::read_to_string(CONFIG_PATH) {
+      Ok(text) => Config::parse(&text),
+      Err(_) => Config::default(),
+    }
+  }
+
+  pub fn parse(text: &str) -> Self {
+    let mut config = Config::default();
+    for (n, line) in text.lines().enumerate() {
+      let line = strip_comment(line).trim();
+      if line.is_empty() {
+        continue;
+      }
+      let result = match line.split_once('=') {
+        Some((key, value)) => config.set(key.trim(), value.trim()),
+        None => Err("expected `key = value`".to_owned()),
+      };
+      if let Err(e) = result {
+        eprintln!("{}:{}: {}", CONFIG_PATH, n + 1, e);
+      }
+    }
+    config
+  }
+
+  fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+    match key {
+      "stars.count" => parse_into(&mut self.stars.count, value),
+      "stars.spectral" => parse_into(&mut self.stars.spectral, value),
+      "stars.magnitude_exponent" => parse_into(&mut self.stars.magnitude_exponent, value),
+      "meteors.per_minute" => parse_into(&mut self.meteors.per_minute, value),
+      "detail.frame_budget" => parse_into(&mut self.detail.frame_budget, value),
+      "measure.slow_approach" => parse_into(&mut self.measure.slow_approach, value),
+      "tour.seconds" => parse_into(&mut self.tour.seconds, value),
+      "screensaver.minutes" => parse_into(&mut self.screensaver.minutes, value),
+      "kiosk.idle_seconds" => parse_into(&mut self.kiosk.idle_seconds, value),
+      "stream.key" if value == "none" => {
+        self.stream.key = None;
+        Ok(())
+      }
+      "stream.key" => parse_color(&mut self.stream.key, value),
+      "theme.name" => parse_into(&mut self.theme.name, value),
+      "theme.background" => parse_color(&mut self.theme.background, value),
+      "theme.stars" => parse_color(&mut self.theme.stars, value),
+      "theme.trail" => value.parse().map(|t| self.theme.trail = Some(t)),
+      "physics.frame" => value.parse().map(|f| self.physics.frame = f),
+      "physics.orbit_speed_limit" => parse_limit(&mut self.physics.orbit_speed_limit, value),
+      "physics.speed_limit" => parse_limit(&mut self.physics.speed_limit, value),
+      "physics.energy_correction" => parse_into(&mut self.physics.energy_correction, value),
+      "physics.trail_budget" => parse_into(&mut self.physics.trail_budget, value),
+      "physics.inclination" => parse_into(&mut self.physics.inclination, value),
+      "physics.star_lifetime" => parse_into(&mut self.physics.star_lifetime, value),
+      "physics.dust_mass" => parse_into(&mut self.physics.dust_mass, value),
+      "physics.potential" => parse_potential(&mut self.physics.potential, value),
+      "physics.field" => parse_vector(&mut self.physics.field, value),
+      "remote.host" => {
+        self.remote.host = value.to_owned();
+        Ok(())
+      }
+      "remote.osc_port" => parse_into(&mut self.remote.osc_port, value),
+      "remote.http_port" => parse_into(&mut self.remote.http_port, value),
+      "remote.broadcast_port" => parse_into(&mut self.remote.broadcast_port, value),
+      "remote.spectate" => {
+        self.remote.spectate = Some(value.to_owned());
+        Ok(())
+      }
+      "planets.palette" => {
+        self.planets.palette = Some(value.to_owned());
+        Ok(())
+      }
+      "planets.capture_color" if value == "none" => {
+        self.planets.capture_color = None;
+        Ok(())
+      }
+      "planets.capture_color" => parse_color(&mut self.planets.capture_color, value),
+      _ if key.starts_with("keys.") => {
+        let action = Action::from_name(&key["keys.".len()..])
+          .ok_or_else(|| format!("unknown action `{}`", key))?;
+        self.keys.push((action, input::parse_key(value)?));
+        Ok(())
+      }
+      _ => Err(format!("unknown key `{}`", key)),
+    }
+  }
+}
+
+fn strip_comment(line: &str) -> &str {
+  let bytes = line.as_bytes();
+  let comment = (0..bytes.len()).find(|&i| {
+    bytes[i] == b'#'
+      && (i == 0 || bytes[i - 1].is_ascii_whitespace())
+      && bytes.get(i + 1).is_none_or(|b| b.is_ascii_whitespace())
+  });
+  match comment {
+    Some(i) => &line[..i],
+    None if line.trim_start().starts_with('#') => "",
+    None => line,
+  }
+}
+
+fn parse_color(field: &mut Option<Color>, value: &str) -> Result<(), String> {
+  *field = Some(parse_hex_color(value)?);
+  Ok(())
+}
+
+/// A positive number, or `none` for no limit.
+fn parse_limit(field: &mut Option<f32>, value: &str) -> Result<(), String> {
+  if value == "none" {
+    *field = None;
+    return Ok(());
+  }
+  match value.parse::<f32>() {
+    Ok(limit) if limit > 0. => {
+      *field = Some(limit);
+      Ok(())
+    }
+    _ => Err(format!(
+      "expected a positive number or `none`, got `{}`",
+      value
+    )),
+  }
+}
+
+fn parse_vector(field: &mut Vec2, value: &str) -> Result<(), String> {
+  let numbers = Vec::from_iter(value.split_whitespace().map(str::parse::<f32>));
+  match numbers[..] {
+    [Ok(x), Ok(y)] => {
+      *field = vec2(x, y);
+      Ok(())
+    }
+    _ => Err(format!("expected two numbers `x y`, got `{}`", value)),
+  }
+}
+
+fn parse_potential(field: &mut Option<Potential>, value: &str) -> Result<(), String> {
+  *field = match value {
+    "none" => None,
+    _ => Some(value.parse()?),
+  };
+  Ok(())
+}
+
+pub fn parse_into<T: FromStr>(field: &mut T, value: &str) -> Result<(), String>
+where
+  T::Err: Display,
+{
+  *field = value
+    .parse()
+    .map_err(|e| format!("invalid value `{}`: {}", value, e))?;
+  Ok(())
+}
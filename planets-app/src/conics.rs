@@ -0,0 +1,166 @@
+//! What shape of path each body is on around whatever dominates it, and
+//! when a body crosses between bound and unbound.
+
+use macroquad::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use crate::{orbits, Planet};
+
+/// How far the eccentricity may be from 1 and still count as parabolic.
+const PARABOLIC_BAND: f32 = 0.02;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conic {
+  Elliptic,
+  Parabolic,
+  Hyperbolic,
+}
+
+impl Conic {
+  pub fn label(self) -> &'static str {
+    match self {
+      Conic::Elliptic => "elliptic",
+      Conic::Parabolic => "parabolic",
+      Conic::Hyperbolic => "hyperbolic",
+    }
+  }
+}
+
+/// Two-body orbit of a body around the one dominating it.
+#[derive(Debug, Clone, Copy)]
+pub struct Orbit {
+  pub center: u32,
+  pub conic: Conic,
+  pub eccentricity: f32,
+  /// Negative for open orbits.
+  pub semi_major_axis: f32,
+}
+
+impl Orbit {
+  pub fn of(body: &Planet, center: &Planet, g: f32) -> Orbit {
+    let mu = g * (body.mass + center.mass);
+    let r = (body.pos - center.pos).extend(body.z - center.z);
+    let v = (body.velocity - center.velocity).extend(body.vz - center.vz);
+    let e = ((v.length_squared() - mu / r.length()) * r - r.dot(v) * v) / mu;
+    let eccentricity = e.length();
+    let energy = v.length_squared() / 2. - mu / r.length();
+    let conic = if eccentricity < 1. - PARABOLIC_BAND {
+      Conic::Elliptic
+    } else if eccentricity <= 1. + PARABOLIC_BAND {
+      Conic::Parabolic
+    } else {
+      Conic::Hyperbolic
+    };
+    Orbit {
+      center: center.id,
+      conic,
+      eccentricity,
+      semi_major_axis: -mu / (2. * energy),
+    }
+  }
+}
+
+/// A body becoming bound or unbound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+  /// Bound for a year to a center it wasn't bound to before, whether a
+  /// rogue caught by the sun or a moon taken from it by a planet.
+  Captured,
+  /// No longer bound to the center it orbited.
+  Escaping,
+}
+
+/// Days a new bound orbit has to last before it counts as a capture, so
+/// bodies merely slowed down during a close pass aren't announced.
+const CAPTURE_DAYS: u64 = 365;
+
+#[derive(Debug, Clone, Copy)]
+struct Track {
+  orbit: Orbit,
+  /// Whether it was last seen bound; a near parabolic orbit doesn't change
+  /// that, so a body grazing the line isn't reported over and over.
+  bound: bool,
+  /// Day it got bound to this center, until that counts as a capture.
+  pending: Option<u64>,
+}
+
+/// Latest orbit of every body and what it was doing before.
+#[derive(Debug, Default)]
+pub struct Conics {
+  tracks: HashMap<u32, Track>,
+  /// The last track of every body around each center it has had, so one
+  /// that falls to another center for a while comes back as it left.
+  around: HashMap<(u32, u32), Track>,
+}
+
+impl Conics {
+  pub fn get(&self, id: u32) -> Option<&Orbit> {
+    self.tracks.get(&id).map(|t| &t.orbit)
+  }
+
+  pub fn clear(&mut self) {
+    self.tracks.clear();
+    self.around.clear();
+  }
+
+  /// Classifies every body again, returning who changed around whom.
+  /// Bodies seen for the first time are never captures: they belong to
+  /// the system as it was set up.
+  pub fn update(&mut self, objects: &[Planet], g: f32, day: u64) -> Vec<(u32, u32, Change)> {
+    // `orbits::dominant_attractor` with the Hill radii worked out once
+    let hills = Vec::from_iter(objects.iter().map(|p| orbits::hill_radius(objects, p)));
+    let mut changes = Vec::new();
+    let mut tracks = HashMap::new();
+    for body in objects.iter().filter(|p| p.departing.is_none()) {
+      let center = objects
+        .iter()
+        .zip(&hills)
+        .filter(|(p, _)| p.id != body.id && p.departing.is_none())
+        .filter(|(p, &hill)| p.pos.distance(body.pos) < hill)
+        .map(|(p, _)| p)
+        .min_by(|a, b| a.mass.total_cmp(&b.mass));
+      let Some(center) = center else {
+        continue;
+      };
+      let orbit = Orbit::of(body, center, g);
+      let before = self.tracks.get(&body.id);
+      let same = self.around.get(&(body.id, center.id));
+      let bound = match (orbit.conic, same) {
+        (Conic::Parabolic, Some(t)) => t.bound,
+        (conic, _) => conic == Conic::Elliptic,
+      };
+      let pending = match (before, same) {
+        (None, _) => None,
+        (Some(_), Some(t)) if t.bound && !bound => {
+          // a pending capture that didn't hold was never announced
+          if t.pending.is_none() {
+            changes.push((body.id, center.id, Change::Escaping));
+          }
+          None
+        }
+        (Some(_), Some(t)) if t.bound => t.pending,
+        (Some(_), _) => bound.then_some(day),
+      };
+      let pending = match pending {
+        Some(since) if day >= since + CAPTURE_DAYS => {
+          changes.push((body.id, center.id, Change::Captured));
+          None
+        }
+        pending => pending,
+      };
+      let track = Track {
+        orbit,
+        bound,
+        pending,
+      };
+      self.around.insert((body.id, center.id), track);
+      tracks.insert(body.id, track);
+    }
+    self.tracks = tracks;
+    let ids = HashSet::<u32>::from_iter(objects.iter().map(|p| p.id));
+    self
+      .around
+      .retain(|(id, center), _| ids.contains(id) && ids.contains(center));
+    changes
+  }
+}
@@ -0,0 +1,339 @@
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+
+use planets_core::Potential;
+
+use crate::hidpi;
+use crate::theme::Theme;
+
+/// Output lines kept for scrollback.
+const SCROLLBACK: usize = 200;
+/// Share of the screen height the console covers.
+const HEIGHT: f32 = 0.4;
+
+/// A console line, parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+  /// Any field left out takes the default for hand-spawned planets.
+  Spawn {
+    mass: Option<f32>,
+    pos: Vec2,
+    velocity: Vec2,
+  },
+  Set(String, f32),
+  Save(String),
+  Load(String),
+  /// Real bodies from state vector files, see `ephemeris`.
+  Import(Vec<String>),
+  /// Saves (true) or loads initial conditions in the REBOUND format.
+  Rebound(bool, String),
+  /// New random system from a fixed seed.
+  Seed(u64),
+  /// Runs every line of a file as a command.
+  Exec(String),
+  /// Trail length for the selected body regardless of the budget, or back
+  /// to its share of it.
+  Trail(Option<usize>),
+  /// Zeroes total momentum and moves the barycenter to the origin.
+  Recenter,
+  /// Replaces the background potential, or removes it.
+  Potential(Option<Potential>),
+  /// Switches to a named theme, keeping the config's overrides.
+  Theme(String),
+  /// Holds the simulation still until entered again.
+  Pause,
+  Clear,
+  Help,
+}
+
+pub const HELP: [&str; 19] = [
+  "spawn [mass=M] [x=X] [y=Y] [vx=VX] [vy=VY]   add a planet",
+  "set G <value>                                change the gravitational constant",
+  "set speed_limit <value>                      cap every body's speed, 0 for none",
+  "set trail_budget <points>                    trail points shared by all bodies",
+  "set star_lifetime <days>                     main sequence of a sun, 0 stops aging",
+  "save <name>                                  save the system to <name>.system",
+  "load <name>                                  load <name>.system",
+  "import <file>...                             load Horizons vectors or a CSV of bodies",
+  "rebound save <file>                          save initial conditions for REBOUND",
+  "rebound load <file>                          load REBOUND initial conditions",
+  "seed <n>                                     new random system from seed n",
+  "exec <file>                                  run each line of file as a command",
+  "trail <points>|auto                          trail length of the selected body",
+  "recenter                                     stop the drift, barycenter to origin",
+  "potential halo <v> <core>|disk <m> <scale>   background field, or none",
+  "theme dark|paper|colorblind                  change the colors and trail style",
+  "pause                                        pause or resume",
+  "clear                                        clear this console",
+  "help                                         list commands",
+];
+
+/// Whether `line` reads or writes files, which only the local console may
+/// do, never a remote sender.
+#[cfg(feature = "net")]
+pub fn local_only(line: &str) -> bool {
+  matches!(
+    Command::parse(line),
+    Ok(
+      Command::Save(_)
+        | Command::Load(_)
+        | Command::Import(_)
+        | Command::Rebound(..)
+        | Command::Exec(_)
+    )
+  )
+}
+
+impl Command {
+  pub fn parse(line: &str) -> Result<Command, String> {
+    let mut words = line.split_whitespace();
+    let name = words.next().unwrap_or_default();
+    let args = Vec::from_iter(words);
+    let number = |s: &str| {
+      s.parse::<f32>()
+        .map_err(|e| format!("invalid number `{}`: {}", s, e))
+    };
+    let single = || match args[..] {
+      [arg] => Ok(arg.to_owned()),
+      _ => Err(format!("usage: {} <arg>", name)),
+    };
+
+    match name {
+      "spawn" => {
+        let (mut mass, mut pos, mut velocity) = (None, Vec2::ZERO, Vec2::ZERO);
+        for arg in args {
+          let (key, value) = arg
+            .split_once('=')
+            .ok_or_else(|| format!("expected key=value, got `{}`", arg))?;
+          let value = number(value)?;
+          match key {
+            "mass" => mass = Some(value),
+            "x" => pos.x = value,
+            "y" => pos.y = value,
+            "vx" => velocity.x = value,
+            "vy" => velocity.y = value,
+            _ => return Err(format!("unknown spawn field `{}`", key)),
+          }
+        }
+        Ok(Command::Spawn {
+          mass,
+          pos,
+          velocity,
+        })
+      }
+      "set" => match args[..] {
+        [param, value] => Ok(Command::Set(param.to_owned(), number(value)?)),
+        _ => Err("usage: set <param> <value>".to_owned()),
+      },
+      "save" => single().map(Command::Save),
+      "load" => single().map(Command::Load),
+      "exec" => single().map(Command::Exec),
+      "import" if args.is_empty() => Err("usage: import <file>...".to_owned()),
+      "import" => Ok(Command::Import(Vec::from_iter(
+        args.iter().map(|&a| a.to_owned()),
+      ))),
+      "rebound" => match args[..] {
+        ["save", path] => Ok(Command::Rebound(true, path.to_owned())),
+        ["load", path] => Ok(Command::Rebound(false, path.to_owned())),
+        _ => Err("usage: rebound save|load <file>".to_owned()),
+      },
+      "seed" => single()?
+        .parse()
+        .map(Command::Seed)
+        .map_err(|e| format!("invalid seed: {}", e)),
+      "trail" => match single()?.as_str() {
+        "auto" => Ok(Command::Trail(None)),
+        points => points
+          .parse()
+          .map(|n| Command::Trail(Some(n)))
+          .map_err(|e| format!("invalid trail length: {}", e)),
+      },
+      "recenter" => Ok(Command::Recenter),
+      "potential" => match args[..] {
+        ["none"] => Ok(Command::Potential(None)),
+        _ => args.join(" ").parse().map(|p| Command::Potential(Some(p))),
+      },
+      "theme" => {
+        let name = single()?;
+        match Theme::named(&name) {
+          Some(_) => Ok(Command::Theme(name)),
+          None => Err(format!(
+            "unknown theme `{}`, expected dark, paper or colorblind",
+            name
+          )),
+        }
+      }
+      "pause" => Ok(Command::Pause),
+      "clear" => Ok(Command::Clear),
+      "help" => Ok(Command::Help),
+      "" => Err("empty command".to_owned()),
+      _ => Err(format!("unknown command `{}`, try `help`", name)),
+    }
+  }
+}
+
+/// Drop-down command line over the top of the screen.
+#[derive(Debug, Default)]
+pub struct Console {
+  open: bool,
+  line: String,
+  output: VecDeque<(String, Color)>,
+  /// Entered lines, oldest first, and how far back `Up` has gone.
+  history: Vec<String>,
+  recall: usize,
+}
+
+impl Console {
+  pub fn is_open(&self) -> bool {
+    self.open
+  }
+
+  /// Toggles on `toggle`, edits the line while open and returns it once
+  /// entered.
+  pub fn update(&mut self, toggle: KeyCode) -> Option<String> {
+    if is_key_pressed(toggle) || (self.open && is_key_pressed(KeyCode::Escape)) {
+      self.open = !self.open;
+    }
+
+    // drained while closed too, or everything typed meanwhile would pour in
+    // on opening
+    while let Some(c) = get_char_pressed() {
+      if self.open && !c.is_control() && c != '`' && c != '~' {
+        self.line.push(c);
+      }
+    }
+    if !self.open {
+      return None;
+    }
+
+    if is_key_pressed(KeyCode::Backspace) {
+      self.line.pop();
+    }
+    if is_key_pressed(KeyCode::Up) && self.recall < self.history.len() {
+      self.recall += 1;
+      self.line = self.history[self.history.len() - self.recall].clone();
+    }
+    if is_key_pressed(KeyCode::Down) {
+      self.recall = self.recall.saturating_sub(1);
+      self.line = match self.recall {
+        0 => String::new(),
+        n => self.history[self.history.len() - n].clone(),
+      };
+    }
+    if !is_key_pressed(KeyCode::Enter) || self.line.trim().is_empty() {
+      return None;
+    }
+
+    let line = std::mem::take(&mut self.line);
+    self.print(format!("> {}", line), GRAY);
+    self.history.push(line.clone());
+    self.recall = 0;
+    Some(line)
+  }
+
+  pub fn print(&mut self, text: impl Into<String>, color: Color) {
+    self.output.push_back((text.into(), color));
+    if self.output.len() > SCROLLBACK {
+      self.output.pop_front();
+    }
+  }
+
+  pub fn clear(&mut self) {
+    self.output.clear();
+  }
+
+  pub fn render(&self) {
+    const SIZE: f32 = 20.;
+    if !self.open {
+      return;
+    }
+
+    let height = screen_height() * HEIGHT;
+    draw_rectangle(0., 0., screen_width(), height, Color::new(0., 0., 0., 0.85));
+    draw_line(0., height, screen_width(), height, 1., DARKGRAY);
+
+    let prompt = height - 10.;
+    let cursor = if get_time().fract() < 0.5 { "_" } else { "" };
+    hidpi::draw_text_sharp(
+      &format!("> {}{}", self.line, cursor),
+      10.,
+      prompt,
+      SIZE,
+      WHITE,
+    );
+    for (i, (text, color)) in self.output.iter().rev().enumerate() {
+      let y = prompt - (i + 1) as f32 * SIZE;
+      if y < SIZE {
+        break;
+      }
+      hidpi::draw_text_sharp(text, 10., y, SIZE, *color);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn spawn() {
+    assert_eq!(
+      Command::parse("spawn  mass=2 x=1.5\ty=-3 vx=0.1"),
+      Ok(Command::Spawn {
+        mass: Some(2.),
+        pos: vec2(1.5, -3.),
+        velocity: vec2(0.1, 0.),
+      })
+    );
+    assert!(Command::parse("spawn mass").is_err());
+    assert!(Command::parse("spawn spin=3").is_err());
+    assert!(Command::parse("spawn x=far").is_err());
+  }
+
+  #[test]
+  fn arguments() {
+    assert_eq!(
+      Command::parse("set G 2e-4"),
+      Ok(Command::Set("G".to_owned(), 2e-4))
+    );
+    assert_eq!(
+      Command::parse("rebound save a.txt"),
+      Ok(Command::Rebound(true, "a.txt".to_owned()))
+    );
+    assert_eq!(Command::parse("trail auto"), Ok(Command::Trail(None)));
+    assert_eq!(
+      Command::parse("theme paper"),
+      Ok(Command::Theme("paper".to_owned()))
+    );
+    assert!(Command::parse("set G").is_err());
+    assert!(Command::parse("save a b").is_err());
+    assert!(Command::parse("seed -1").is_err());
+    assert!(Command::parse("import").is_err());
+    assert!(Command::parse("rebound a.txt").is_err());
+    assert!(Command::parse("theme neon").is_err());
+  }
+
+  #[test]
+  fn unknown() {
+    assert!(Command::parse("").is_err());
+    assert!(Command::parse("   ").is_err());
+    assert!(Command::parse("launch").is_err());
+  }
+
+  #[cfg(feature = "net")]
+  #[test]
+  fn files_are_local_only() {
+    for line in [
+      "save x",
+      "load ../x",
+      "exec run.txt",
+      "import a.csv",
+      "rebound save x",
+    ] {
+      assert!(local_only(line), "{}", line);
+    }
+    for line in ["seed 7", "pause", "save"] {
+      assert!(!local_only(line), "{}", line);
+    }
+  }
+}
@@ -0,0 +1,82 @@
+//! Automatic detail scaling. When frames take longer than the budget,
+//! cosmetic work is cut back a level at a time: fewer trail points drawn
+//! and no meteors or merge flashes, then fewer background stars as well.
+//! Detail comes back once there's headroom again. The physics is never
+//! touched.
+
+/// Deepest level. Each level halves the trail points drawn, and from the
+/// second one on the stars too.
+const MAX_LEVEL: u32 = 3;
+/// Seconds frames have to stay over budget before detail drops.
+const OVER_PATIENCE: f32 = 0.5;
+/// Seconds frames have to stay under the headroom before it comes back.
+const UNDER_PATIENCE: f32 = 3.;
+/// Share of the budget frames have to stay under to restore a level, low
+/// enough that the restored work still fits.
+const HEADROOM: f32 = 0.5;
+
+#[derive(Debug, Default)]
+pub struct Detail {
+  /// Work per frame allowed, in seconds, zero disables scaling.
+  budget: f32,
+  level: u32,
+  over: f32,
+  under: f32,
+}
+
+impl Detail {
+  pub fn new(budget_ms: f32) -> Detail {
+    Detail {
+      budget: budget_ms / 1e3,
+      ..Default::default()
+    }
+  }
+
+  /// Takes the measured work of a frame that lasted `dt`, returning the
+  /// new level when it changes.
+  pub fn update(&mut self, work: f32, dt: f32) -> Option<u32> {
+    if self.budget <= 0. {
+      return None;
+    }
+    if work > self.budget {
+      self.over += dt;
+      self.under = 0.;
+    } else if work < self.budget * HEADROOM {
+      self.under += dt;
+      self.over = 0.;
+    } else {
+      self.over = 0.;
+      self.under = 0.;
+    }
+
+    let level = if self.over > OVER_PATIENCE {
+      (self.level + 1).min(MAX_LEVEL)
+    } else if self.under > UNDER_PATIENCE {
+      self.level.saturating_sub(1)
+    } else {
+      self.level
+    };
+    if level == self.level {
+      return None;
+    }
+    self.level = level;
+    self.over = 0.;
+    self.under = 0.;
+    Some(level)
+  }
+
+  /// Every how many recorded points a trail segment is drawn.
+  pub fn trail_stride(&self) -> usize {
+    2 << self.level
+  }
+
+  /// Every how many background stars one is drawn.
+  pub fn star_stride(&self) -> usize {
+    1 << self.level.saturating_sub(1)
+  }
+
+  /// Whether meteors and merge flashes are drawn.
+  pub fn effects(&self) -> bool {
+    self.level == 0
+  }
+}
@@ -0,0 +1,125 @@
+//! Eccentricity and semi-major axis of the selected body over time, where
+//! secular perturbations and resonances pumping an orbit show up long
+//! before they change what its trail looks like.
+
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+
+use crate::camera::base_scale;
+use crate::conics::{Conic, Conics, Orbit};
+use crate::{hidpi, Planet};
+
+/// Samples kept, one every few days.
+const HISTORY: usize = 300;
+const SAMPLE_EVERY: u32 = 10;
+const MARGIN: f32 = 10.;
+
+#[derive(Debug, Default)]
+pub struct Elements {
+  pub visible: bool,
+  /// Body the samples are of; a new selection starts over.
+  body: Option<u32>,
+  /// Eccentricity and semi-major axis, bound orbits only.
+  samples: VecDeque<(f32, f32)>,
+  countdown: u32,
+}
+
+impl Elements {
+  /// Call once per step with the selected body.
+  pub fn sample(&mut self, objects: &[Planet], conics: &Conics, selected: Option<u32>, g: f32) {
+    if !self.visible {
+      return;
+    }
+    if selected != self.body {
+      self.body = selected;
+      self.samples.clear();
+      self.countdown = 0;
+    }
+    if self.countdown > 0 {
+      self.countdown -= 1;
+      return;
+    }
+    self.countdown = SAMPLE_EVERY - 1;
+    let find = |id: u32| objects.iter().find(|p| p.id == id);
+    let Some(body) = selected.and_then(find) else {
+      return;
+    };
+    // the center as of the last classification, the orbit as of now
+    let Some(center) = conics.get(body.id).and_then(|o| find(o.center)) else {
+      return;
+    };
+    let orbit = Orbit::of(body, center, g);
+    if orbit.conic != Conic::Elliptic {
+      return;
+    }
+    if self.samples.len() == HISTORY {
+      self.samples.pop_front();
+    }
+    self
+      .samples
+      .push_back((orbit.eccentricity, orbit.semi_major_axis));
+  }
+
+  /// Right edge, vertically centered.
+  pub fn render(&self, objects: &[Planet]) {
+    let Some(body) = self.body.and_then(|id| objects.iter().find(|p| p.id == id)) else {
+      return;
+    };
+    if !self.visible || self.samples.is_empty() {
+      return;
+    }
+    let scale = base_scale();
+    let line = 16. * scale;
+    let (width, graph) = (HISTORY as f32 * scale, 40. * scale);
+    let height = line * 1.5 + 2. * (line + graph + 8.);
+    let corner = vec2(
+      screen_width() - width - MARGIN,
+      screen_height() / 2. - height / 2.,
+    );
+    draw_rectangle(
+      corner.x - 6.,
+      corner.y,
+      width + 12.,
+      height,
+      Color::new(0., 0., 0., 0.6),
+    );
+    let days = self.samples.len() as u32 * SAMPLE_EVERY;
+    let title = format!("{}, last {} days", body.name, days);
+    hidpi::draw_text_sharp(&title, corner.x, corner.y + line, line, body.color);
+
+    let charts = [
+      (
+        "e",
+        self.samples.iter().map(|s| s.0).collect::<Vec<_>>(),
+        ORANGE,
+      ),
+      ("a", self.samples.iter().map(|s| s.1).collect(), SKYBLUE),
+    ];
+    let mut y = corner.y + line * 1.5;
+    for (label, values, color) in charts {
+      y += line;
+      let (low, high) = values
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &v| {
+          (lo.min(v), hi.max(v))
+        });
+      let last = values[values.len() - 1];
+      let text = format!("{} = {:.4}  ({:.4} to {:.4})", label, last, low, high);
+      hidpi::draw_text_sharp(&text, corner.x, y, line, color);
+
+      let span = (high - low).max(f32::EPSILON * high.abs().max(1.));
+      let top = y + 4.;
+      let point = |i: usize, v: f32| {
+        vec2(
+          corner.x + i as f32 * scale,
+          top + graph * (1. - (v - low) / span),
+        )
+      };
+      for (i, (&a, &b)) in values.iter().zip(values.iter().skip(1)).enumerate() {
+        let (a, b) = (point(i, a), point(i + 1, b));
+        draw_line(a.x, a.y, b.x, b.y, 1., color);
+      }
+      y = top + graph + 4.;
+    }
+  }
+}
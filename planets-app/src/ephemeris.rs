@@ -0,0 +1,159 @@
+//! Real bodies from state vectors, either JPL Horizons vector tables or a
+//! simpler CSV of `name,mass_kg,x_km,y_km,vx_km_s,vy_km_s`, converted to
+//! simulation units. The CSV bodies are in the x-y plane.
+
+use macroquad::prelude::*;
+use std::fs;
+
+use crate::{units, BodyKind, Planet, SUN_COLOR, SUN_MASS};
+
+const SECONDS_PER_DAY: f64 = 86400.;
+const KM_PER_AU: f64 = 1.495978707e8;
+/// Lightest body counted as a star, in solar masses.
+const STAR_MASS: f64 = 0.07;
+
+/// Bodies from every file together, heaviest last as the sun. A sun at
+/// rest at the origin is added when none of the files has a star.
+pub fn load(paths: &[String]) -> Result<Vec<Planet>, String> {
+  let mut bodies = Vec::new();
+  for path in paths {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let parsed = if text.contains("$$SOE") {
+      parse_horizons(&text).map(|b| vec![b])
+    } else {
+      parse_csv(&text)
+    };
+    bodies.extend(parsed.map_err(|e| format!("{}: {}", path, e))?);
+  }
+  if bodies.is_empty() {
+    return Err("no bodies to import".to_owned());
+  }
+  arrange(&mut bodies);
+  Ok(bodies)
+}
+
+/// Sorts imported bodies so the heaviest is last as the sun, making every
+/// star one and adding a sun at the origin if there is none.
+pub fn arrange(bodies: &mut Vec<Planet>) {
+  let star = |p: &Planet| p.mass >= (STAR_MASS * SUN_MASS as f64) as f32;
+  if !bodies.iter().any(star) {
+    bodies.push(Planet {
+      id: Planet::next_id(),
+      name: "Sun".to_owned(),
+      mass: SUN_MASS,
+      ..Default::default()
+    });
+  }
+  bodies.sort_by(|a, b| a.mass.total_cmp(&b.mass));
+  for body in bodies.iter_mut().filter(|p| star(p)) {
+    body.kind = BodyKind::Star;
+    if body.color == Color::default() {
+      body.color = SUN_COLOR;
+    }
+  }
+}
+
+/// A body from SI-ish units: kilograms, kilometres and km/s.
+fn body(name: &str, kg: f64, pos_km: [f64; 3], velocity_km_s: [f64; 3]) -> Planet {
+  let km = units::km_per_unit();
+  let [x, y, z] = pos_km.map(|p| (p / km) as f32);
+  let [vx, vy, vz] = velocity_km_s.map(|v| (v * SECONDS_PER_DAY / km) as f32);
+  Planet {
+    id: Planet::next_id(),
+    name: name.to_owned(),
+    mass: (kg / units::kg_per_unit()) as f32,
+    pos: vec2(x, y),
+    velocity: vec2(vx, vy),
+    z,
+    vz,
+    ..Default::default()
+  }
+}
+
+fn parse_csv(text: &str) -> Result<Vec<Planet>, String> {
+  let mut bodies = Vec::new();
+  for (n, line) in text.lines().enumerate() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || line.starts_with("name") {
+      continue;
+    }
+    let fields = Vec::from_iter(line.split(',').map(str::trim));
+    let [name, numbers @ ..] = &fields[..] else {
+      continue;
+    };
+    let numbers = numbers
+      .iter()
+      .map(|s| s.parse::<f64>())
+      .collect::<Result<Vec<_>, _>>();
+    let Ok(&[kg, x, y, vx, vy]) = numbers.as_deref() else {
+      return Err(format!(
+        "line {}: expected `name,mass_kg,x_km,y_km,vx_km_s,vy_km_s`",
+        n + 1
+      ));
+    };
+    bodies.push(body(name, kg, [x, y, 0.], [vx, vy, 0.]));
+  }
+  Ok(bodies)
+}
+
+/// The first record of a Horizons vector table; the mass has to be in the
+/// header, as it is when the target's physical data is included.
+fn parse_horizons(text: &str) -> Result<Planet, String> {
+  let name = text
+    .lines()
+    .find_map(|l| l.trim().strip_prefix("Target body name:"))
+    .map(|rest| rest.split('(').next().unwrap_or(rest).trim())
+    .unwrap_or("body");
+  let kg = text
+    .lines()
+    .find_map(horizons_mass)
+    .ok_or("no mass in the header, include the target's physical data or use CSV")?;
+  let au = text
+    .lines()
+    .any(|l| l.starts_with("Output units") && l.contains("AU"));
+
+  let records = text
+    .split("$$SOE")
+    .nth(1)
+    .and_then(|t| t.split("$$EOE").next())
+    .ok_or("no $$SOE ... $$EOE records")?;
+  let value = |key: &str| {
+    let start = records
+      .find(&format!("{}=", key))
+      .or_else(|| records.find(&format!("{} =", key)))?;
+    let rest = records[start + key.len()..].trim_start_matches([' ', '=']);
+    let end = rest.find(|c: char| c.is_whitespace()).unwrap_or(rest.len());
+    rest[..end].parse::<f64>().ok()
+  };
+  let field = |key: &str| value(key).ok_or(format!("missing {} in the first record", key));
+  let pos = [field(" X")?, field(" Y")?, field(" Z")?];
+  let velocity = [field("VX")?, field("VY")?, field("VZ")?];
+  let (pos, velocity) = if au {
+    // AU and AU per day
+    (
+      pos.map(|x| x * KM_PER_AU),
+      velocity.map(|v| v * KM_PER_AU / SECONDS_PER_DAY),
+    )
+  } else {
+    (pos, velocity)
+  };
+  Ok(body(name, kg, pos, velocity))
+}
+
+/// Mass from a physical data line like `Mass x10^24 (kg)= 5.97219` or
+/// `Mass, 10^24 kg = ~5.97`.
+fn horizons_mass(line: &str) -> Option<f64> {
+  let start = line.find("Mass")?;
+  let line = &line[start..];
+  let exponent = line.split("10^").nth(1)?;
+  let exponent: i32 = exponent
+    .split(|c: char| !c.is_ascii_digit() && c != '-')
+    .next()?
+    .parse()
+    .ok()?;
+  let value = line.split('=').nth(1)?.trim().trim_start_matches('~');
+  let end = value
+    .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+    .unwrap_or(value.len());
+  Some(value[..end].parse::<f64>().ok()? * 10f64.powi(exponent))
+}
@@ -0,0 +1,72 @@
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+
+use crate::hidpi;
+
+/// Entries kept on screen at once.
+const MAX_EVENTS: usize = 6;
+/// Seconds an entry stays fully visible before fading out.
+const EVENT_TTL: f64 = 6.;
+const FADE: f64 = 1.5;
+
+pub struct Event {
+  /// Simulated day the event happened on.
+  pub day: u64,
+  pub text: String,
+  pub color: Color,
+  posted: f64,
+}
+
+/// Notable simulation events, shown in the top right corner.
+#[derive(Default)]
+pub struct EventLog {
+  events: VecDeque<Event>,
+}
+
+impl EventLog {
+  pub fn post(&mut self, day: u64, text: impl Into<String>, color: Color) {
+    self.events.push_front(Event {
+      day,
+      text: text.into(),
+      color,
+      posted: get_time(),
+    });
+    self.events.truncate(MAX_EVENTS);
+  }
+
+  /// Newest first.
+  #[cfg(feature = "net")]
+  pub fn iter(&self) -> impl Iterator<Item = &Event> {
+    self.events.iter()
+  }
+
+  pub fn clear(&mut self) {
+    self.events.clear();
+  }
+
+  pub fn render(&self) {
+    const SIZE: f32 = 22.;
+    let now = get_time();
+
+    for (i, e) in self.events.iter().enumerate() {
+      let age = now - e.posted;
+      if age > EVENT_TTL + FADE {
+        continue;
+      }
+      let alpha = (1. - (age - EVENT_TTL).max(0.) / FADE) as f32;
+
+      let text = format!("{}  {}", crate::format_day(e.day), e.text);
+      let width = measure_text(&text, None, SIZE as u16, 1.).width;
+      hidpi::draw_text_sharp(
+        &text,
+        screen_width() - width - 10.,
+        30. + i as f32 * SIZE,
+        SIZE,
+        Color {
+          a: alpha,
+          ..e.color
+        },
+      );
+    }
+  }
+}
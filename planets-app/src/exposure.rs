@@ -0,0 +1,117 @@
+use macroquad::prelude::*;
+use std::collections::HashMap;
+
+use crate::camera::View;
+use crate::{hidpi, timestamped_path, Planet};
+
+/// Opacity each frame's strokes add to the accumulated image.
+const STROKE_ALPHA: f32 = 0.35;
+
+/// Offscreen image that is never cleared, so planet paths build up into
+/// long-exposure orbit art.
+pub struct Exposure {
+  target: RenderTarget,
+  size: (u32, u32),
+  /// Where each body was drawn last frame, to join strokes into lines.
+  last: HashMap<u32, Vec2>,
+}
+
+impl Exposure {
+  pub fn new() -> Self {
+    let size = hidpi::physical_screen_size();
+    let exposure = Exposure {
+      target: new_target(size),
+      size,
+      last: HashMap::new(),
+    };
+    exposure.clear();
+    exposure
+  }
+
+  pub fn clear(&self) {
+    self.with_target(|| clear_background(BLANK));
+  }
+
+  /// Adds the movement of every body since the previous call.
+  ///
+  /// Strokes land in screen space, so moving the camera smears the image
+  /// like it would on a real long exposure.
+  pub fn accumulate(&mut self, objects: &[Planet], view: &View) {
+    let size = hidpi::physical_screen_size();
+    if size != self.size {
+      self.target.delete();
+      self.target = new_target(size);
+      self.size = size;
+      self.last.clear();
+      self.clear();
+    }
+
+    let last = &self.last;
+    self.with_target(|| {
+      for p in objects.iter() {
+        let b = view.world_to_screen(p.pos);
+        let a = last.get(&p.id).copied().unwrap_or(b);
+        let color = Color {
+          a: STROKE_ALPHA,
+          ..p.color
+        };
+        draw_line(a.x, a.y, b.x, b.y, 1.5, color);
+      }
+    });
+    self.last = HashMap::from_iter(objects.iter().map(|p| (p.id, view.world_to_screen(p.pos))));
+  }
+
+  pub fn render(&self) {
+    draw_texture_ex(
+      self.target.texture,
+      0.,
+      0.,
+      WHITE,
+      DrawTextureParams {
+        dest_size: Some(vec2(screen_width(), screen_height())),
+        flip_y: true,
+        ..Default::default()
+      },
+    );
+  }
+
+  /// Writes the accumulated image to a timestamped PNG in the working
+  /// directory and returns its name.
+  pub fn export(&self) -> String {
+    save_png(self.target.texture, "exposure")
+  }
+
+  fn with_target(&self, draw: impl FnOnce()) {
+    let mut camera =
+      Camera2D::from_display_rect(Rect::new(0., 0., screen_width(), screen_height()));
+    camera.render_target = Some(self.target);
+    set_camera(&camera);
+    draw();
+    set_default_camera();
+  }
+}
+
+/// Writes a render target's texture to a timestamped PNG in the working
+/// directory and returns its name.
+pub fn save_png(texture: Texture2D, prefix: &str) -> String {
+  let mut image = texture.get_texture_data();
+  // render targets are stored bottom-up
+  let width = image.width();
+  let rows = image.get_image_data_mut();
+  let height = rows.len() / width;
+  for y in 0..height / 2 {
+    for x in 0..width {
+      rows.swap(y * width + x, (height - 1 - y) * width + x);
+    }
+  }
+
+  let path = timestamped_path(prefix, "png");
+  image.export_png(&path);
+  path
+}
+
+fn new_target((width, height): (u32, u32)) -> RenderTarget {
+  let target = render_target(width.max(1), height.max(1));
+  target.texture.set_filter(FilterMode::Linear);
+  target
+}
@@ -0,0 +1,89 @@
+//! Reference frame adjustments, so systems don't drift off as a whole.
+
+use macroquad::prelude::*;
+use planets_core::Body;
+use std::str::FromStr;
+
+use crate::Planet;
+
+/// How a new random system's momentum is balanced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frame {
+  /// Sun at rest while the planets pull it along, so the system drifts.
+  Drifting,
+  /// The sun gets the momentum that cancels the planets'.
+  Sun,
+  /// Everything moves relative to the center of mass, which sits still at
+  /// the origin.
+  Barycentric,
+}
+
+impl FromStr for Frame {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "drifting" => Ok(Frame::Drifting),
+      "sun" => Ok(Frame::Sun),
+      "barycentric" => Ok(Frame::Barycentric),
+      _ => Err("expected drifting, sun or barycentric".to_owned()),
+    }
+  }
+}
+
+impl Frame {
+  /// Balances a fresh system with the sun last.
+  pub fn apply(self, objects: &mut [Planet]) {
+    match self {
+      Frame::Drifting => {}
+      Frame::Sun => {
+        let Some((sun, planets)) = objects.split_last_mut() else {
+          return;
+        };
+        let momentum = planets
+          .iter()
+          .fold(Vec3::ZERO, |sum, p| sum + p.velocity() * p.mass);
+        sun.set_velocity(-momentum / sun.mass);
+      }
+      Frame::Barycentric => {
+        zero_momentum(objects);
+        recenter(objects);
+      }
+    }
+  }
+}
+
+/// Center of mass position and velocity.
+pub fn barycenter(objects: &[Planet]) -> (Vec3, Vec3) {
+  let mass: f32 = objects.iter().map(|p| p.mass).sum();
+  if mass <= 0. {
+    return (Vec3::ZERO, Vec3::ZERO);
+  }
+  let pos = objects
+    .iter()
+    .fold(Vec3::ZERO, |sum, p| sum + p.pos() * p.mass);
+  let velocity = objects
+    .iter()
+    .fold(Vec3::ZERO, |sum, p| sum + p.velocity() * p.mass);
+  (pos / mass, velocity / mass)
+}
+
+/// Subtracts the center of mass velocity, leaving zero total momentum.
+pub fn zero_momentum(objects: &mut [Planet]) {
+  let (_, velocity) = barycenter(objects);
+  for p in objects.iter_mut() {
+    p.set_velocity(p.velocity() - velocity);
+  }
+}
+
+/// Moves everything so the center of mass is at the origin.
+pub fn recenter(objects: &mut [Planet]) {
+  let (pos, _) = barycenter(objects);
+  for p in objects.iter_mut() {
+    p.set_pos(p.pos() - pos);
+    for t in p.trail.iter_mut() {
+      t.pos -= pos.truncate();
+      t.z -= pos.z;
+    }
+  }
+}
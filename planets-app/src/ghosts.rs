@@ -0,0 +1,36 @@
+use macroquad::prelude::*;
+
+use crate::camera::View;
+use crate::Planet;
+
+/// Faded monochrome trails left over from the previous system, so
+/// consecutive runs can be compared in the same frame.
+#[derive(Default)]
+pub struct Ghosts {
+  trails: Vec<Vec<Vec2>>,
+  pub visible: bool,
+}
+
+impl Ghosts {
+  /// Keeps the trails of a system that is about to be replaced.
+  pub fn capture(&mut self, objects: &[Planet]) {
+    self.trails = Vec::from_iter(
+      objects
+        .iter()
+        .map(|p| Vec::from_iter(p.trail.iter().step_by(2).map(|t| t.pos))),
+    );
+  }
+
+  pub fn render(&self, view: &View) {
+    if !self.visible {
+      return;
+    }
+    let color = Color::new(0.7, 0.7, 0.7, 0.25);
+    for trail in self.trails.iter() {
+      for (a, b) in trail.iter().zip(trail.iter().skip(1)) {
+        let (a, b) = (view.world_to_screen(*a), view.world_to_screen(*b));
+        draw_line(a.x, a.y, b.x, b.y, 1.5, color);
+      }
+    }
+  }
+}
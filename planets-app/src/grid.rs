@@ -0,0 +1,81 @@
+use macroquad::prelude::*;
+
+use crate::camera::View;
+use crate::{hidpi, units};
+
+/// Grid lines are spaced to land near this many pixels apart.
+const TARGET_SPACING: f32 = 120.;
+
+/// How the world-space grid is labeled, if shown at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grid {
+  SimUnits,
+  Kilometres,
+}
+
+impl Grid {
+  /// Off, then simulation units, then kilometres, then off again.
+  pub fn cycle(grid: Option<Grid>) -> Option<Grid> {
+    match grid {
+      None => Some(Grid::SimUnits),
+      Some(Grid::SimUnits) => Some(Grid::Kilometres),
+      Some(Grid::Kilometres) => None,
+    }
+  }
+
+  pub fn label(self) -> &'static str {
+    match self {
+      Grid::SimUnits => "units",
+      Grid::Kilometres => "km",
+    }
+  }
+
+  pub fn from_label(label: &str) -> Option<Grid> {
+    [Grid::SimUnits, Grid::Kilometres]
+      .into_iter()
+      .find(|g| g.label() == label)
+  }
+
+  pub fn render(self, view: &View) {
+    let spacing = nice_spacing(TARGET_SPACING / view.scale);
+    let color = Color::new(0.4, 0.5, 0.6, 0.25);
+    let label = Color::new(0.5, 0.6, 0.7, 0.7);
+
+    let top_left = view.screen_to_world(Vec2::ZERO);
+    let bottom_right = view.screen_to_world(vec2(screen_width(), screen_height()));
+
+    let mut x = (top_left.x / spacing).floor() * spacing;
+    while x <= bottom_right.x {
+      let sx = view.world_to_screen(vec2(x, 0.)).x;
+      draw_line(sx, 0., sx, screen_height(), 1., color);
+      x += spacing;
+    }
+
+    let mut y = (top_left.y / spacing).floor() * spacing;
+    while y <= bottom_right.y {
+      let sy = view.world_to_screen(vec2(0., y)).y;
+      draw_line(0., sy, screen_width(), sy, 1., color);
+      y += spacing;
+    }
+
+    let km = self == Grid::Kilometres;
+    let text = format!("grid {}", units::format_distance(spacing, km));
+    hidpi::draw_text_sharp(&text, 10., screen_height() - 70., 20., label);
+  }
+}
+
+/// Rounds up to the nearest 1, 2 or 5 times a power of ten.
+fn nice_spacing(raw: f32) -> f32 {
+  let magnitude = 10f32.powf(raw.log10().floor());
+  let mantissa = raw / magnitude;
+  let nice = if mantissa <= 1. {
+    1.
+  } else if mantissa <= 2. {
+    2.
+  } else if mantissa <= 5. {
+    5.
+  } else {
+    10.
+  };
+  nice * magnitude
+}
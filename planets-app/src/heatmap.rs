@@ -0,0 +1,88 @@
+use macroquad::prelude::*;
+
+use crate::{hidpi, Planet};
+
+/// Samples of the viridis colormap, evenly spaced from low to high.
+const VIRIDIS: [(u8, u8, u8); 9] = [
+  (68, 1, 84),
+  (71, 44, 122),
+  (59, 81, 139),
+  (44, 113, 142),
+  (33, 144, 141),
+  (39, 173, 129),
+  (92, 200, 99),
+  (170, 220, 50),
+  (253, 231, 37),
+];
+
+/// Quantity planets are colored by in heatmap mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heatmap {
+  Mass,
+  Speed,
+}
+
+impl Heatmap {
+  /// Off, then mass, then speed, then off again.
+  pub fn cycle(mode: Option<Heatmap>) -> Option<Heatmap> {
+    match mode {
+      None => Some(Heatmap::Mass),
+      Some(Heatmap::Mass) => Some(Heatmap::Speed),
+      Some(Heatmap::Speed) => None,
+    }
+  }
+
+  pub fn label(self) -> &'static str {
+    match self {
+      Heatmap::Mass => "mass",
+      Heatmap::Speed => "speed",
+    }
+  }
+
+  pub fn from_label(label: &str) -> Option<Heatmap> {
+    [Heatmap::Mass, Heatmap::Speed]
+      .into_iter()
+      .find(|h| h.label() == label)
+  }
+
+  fn value(self, p: &Planet) -> f32 {
+    match self {
+      // masses span orders of magnitude
+      Heatmap::Mass => p.mass.ln(),
+      Heatmap::Speed => p.velocity.length(),
+    }
+  }
+
+  /// Fill color of every body, normalized over the bodies present.
+  pub fn colors(self, objects: &[Planet]) -> Vec<Color> {
+    let values = Vec::from_iter(objects.iter().map(|p| self.value(p)));
+    let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let range = (max - min).max(f32::EPSILON);
+    Vec::from_iter(values.iter().map(|v| viridis((v - min) / range)))
+  }
+
+  /// Low to high gradient with its label, bottom left above the clock.
+  pub fn render_legend(self) {
+    const WIDTH: f32 = 160.;
+    const STEPS: usize = 32;
+    let y = screen_height() - 50.;
+    for i in 0..STEPS {
+      let t = i as f32 / (STEPS - 1) as f32;
+      let w = WIDTH / STEPS as f32;
+      draw_rectangle(10. + i as f32 * w, y, w + 0.5, 10., viridis(t));
+    }
+    let text = format!("{}: low to high", self.label());
+    hidpi::draw_text_sharp(&text, 20. + WIDTH, y + 10., 20., GRAY);
+  }
+}
+
+/// Maps 0..1 to the viridis colormap.
+pub fn viridis(t: f32) -> Color {
+  let x = t.clamp(0., 1.) * (VIRIDIS.len() - 1) as f32;
+  let i = (x.floor() as usize).min(VIRIDIS.len() - 2);
+  let f = x - i as f32;
+  let (a, b) = (VIRIDIS[i], VIRIDIS[i + 1]);
+  let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * f) / 255.;
+  Color::new(mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2), 1.)
+}
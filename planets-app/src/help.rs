@@ -0,0 +1,47 @@
+use macroquad::prelude::*;
+
+use crate::hidpi;
+use crate::input::{self, InputMap};
+
+/// Controls that aren't keys, so aren't in the input map.
+const MOUSE: [(&str, &str); 3] = [
+  ("Wheel", "zoom at cursor"),
+  ("Right drag", "pan"),
+  ("Left click", "place measurement anchors"),
+];
+
+/// Centered panel listing every binding from `input`, followed by the
+/// current state of each mode.
+pub fn render(input: &InputMap, modes: &[(&str, String)]) {
+  const SIZE: f32 = 20.;
+  const WIDTH: f32 = 640.;
+  const COLUMN: f32 = 150.;
+
+  let keys = Vec::from_iter(
+    input
+      .bindings()
+      .map(|(action, key)| (input::key_name(key), action.description().to_owned())),
+  );
+  let mouse = MOUSE.map(|(k, d)| (k.to_owned(), d.to_owned()));
+  let controls = keys.len() + mouse.len();
+  let height = (controls + modes.len() + 3) as f32 * SIZE + 20.;
+
+  let x = ((screen_width() - WIDTH) / 2.).max(0.);
+  let mut y = ((screen_height() - height) / 2.).max(0.);
+  draw_rectangle(x, y, WIDTH, height, Color::new(0., 0., 0., 0.8));
+
+  let mut line = |left: &str, right: &str, color: Color| {
+    y += SIZE;
+    hidpi::draw_text_sharp(left, x + 15., y, SIZE, color);
+    hidpi::draw_text_sharp(right, x + 15. + COLUMN, y, SIZE, LIGHTGRAY);
+  };
+  line("Controls", "", WHITE);
+  for (key, description) in keys.iter().chain(&mouse) {
+    line(key, description, YELLOW);
+  }
+  line("", "", WHITE);
+  line("Settings", "", WHITE);
+  for (name, value) in modes {
+    line(name, value, GRAY);
+  }
+}
@@ -0,0 +1,49 @@
+//! Helpers for drawing at native pixel density on high-DPI displays.
+//!
+//! Drawing coordinates stay in logical pixels; these only matter where a
+//! logical pixel would otherwise be rasterized or snapped too coarsely.
+
+use macroquad::prelude::*;
+
+/// Physical pixels per logical pixel.
+pub fn dpi_scale() -> f32 {
+  // SAFETY: only reads the scale factor, no GL state is touched.
+  unsafe { get_internal_gl().quad_context.dpi_scale() }
+}
+
+/// Size of one physical pixel in logical units.
+pub fn pixel() -> f32 {
+  1. / dpi_scale()
+}
+
+/// Screen size in physical pixels, for offscreen targets that should be as
+/// sharp as drawing directly.
+pub fn physical_screen_size() -> (u32, u32) {
+  let dpi = dpi_scale();
+  (
+    (screen_width() * dpi) as u32,
+    (screen_height() * dpi) as u32,
+  )
+}
+
+/// Snaps a logical coordinate to the physical pixel grid.
+pub fn snap(v: f32) -> f32 {
+  let dpi = dpi_scale();
+  (v * dpi).floor() / dpi
+}
+
+/// Like `draw_text`, but rasterizes the glyphs at physical resolution.
+pub fn draw_text_sharp(text: &str, x: f32, y: f32, font_size: f32, color: Color) {
+  let dpi = dpi_scale();
+  draw_text_ex(
+    text,
+    x,
+    y,
+    TextParams {
+      font_size: (font_size * dpi).round() as u16,
+      font_scale: 1. / dpi,
+      color,
+      ..Default::default()
+    },
+  );
+}
@@ -0,0 +1,356 @@
+//! Tiny embedded HTTP server for dashboards and scripts:
+//! `GET /state` and `GET /events` answer with JSON, and `POST /control`
+//! runs its body as console commands, one per line.
+
+use std::fmt::Write as _;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+use crate::events::Event;
+use crate::{scenario, Planet};
+
+/// Largest request accepted, headers and body together.
+const MAX_REQUEST: usize = 64 * 1024;
+/// How long a client gets to send its request and take the answer.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct Request {
+  pub method: String,
+  pub path: String,
+  pub body: String,
+  /// The `Origin` header browsers add, which says what page sent it.
+  pub origin: Option<String>,
+  /// The connection to answer on.
+  id: u64,
+}
+
+impl Request {
+  /// Whether a web page other than one served from this machine sent the
+  /// request, which any site can do with a plain form post. Requests
+  /// without an `Origin` come from scripts and `curl`, not pages.
+  pub fn cross_site(&self) -> bool {
+    let Some(origin) = self.origin.as_deref().filter(|o| !o.is_empty()) else {
+      return false;
+    };
+    let Some(host) = origin
+      .strip_prefix("http://")
+      .or_else(|| origin.strip_prefix("https://"))
+    else {
+      return true;
+    };
+    let name = match host.rsplit_once(':') {
+      Some((name, port)) if !name.ends_with(':') && port.parse::<u16>().is_ok() => name,
+      _ => host,
+    };
+    !matches!(name, "localhost" | "127.0.0.1" | "[::1]")
+  }
+}
+
+enum State {
+  Reading(Vec<u8>),
+  /// Handed out by `poll`, waiting for `respond`.
+  Answering,
+  /// Whatever part of the response hasn't gone out, after which the
+  /// connection closes.
+  Sending(Vec<u8>),
+}
+
+struct Connection {
+  id: u64,
+  stream: TcpStream,
+  state: State,
+  opened: Instant,
+}
+
+/// Reads and answers without blocking, a slow client's request is
+/// gathered over as many frames as it takes.
+pub struct HttpServer {
+  listener: TcpListener,
+  connections: Vec<Connection>,
+  next_id: u64,
+}
+
+impl HttpServer {
+  pub fn bind(host: &str, port: u16) -> io::Result<HttpServer> {
+    let listener = TcpListener::bind((host, port))?;
+    listener.set_nonblocking(true)?;
+    Ok(HttpServer {
+      listener,
+      connections: Vec::new(),
+      next_id: 0,
+    })
+  }
+
+  /// Requests that arrived complete since the last call. Malformed ones are
+  /// answered and dropped here.
+  pub fn poll(&mut self) -> Vec<Request> {
+    while let Ok((stream, _)) = self.listener.accept() {
+      if stream.set_nonblocking(true).is_ok() {
+        self.next_id += 1;
+        self.connections.push(Connection {
+          id: self.next_id,
+          stream,
+          state: State::Reading(Vec::new()),
+          opened: Instant::now(),
+        });
+      }
+    }
+
+    let mut requests = Vec::new();
+    self.connections.retain_mut(|c| {
+      if c.opened.elapsed() > TIMEOUT {
+        return false;
+      }
+      if let State::Reading(received) = &mut c.state {
+        let mut buf = [0; 4096];
+        loop {
+          match c.stream.read(&mut buf) {
+            Ok(0) => return false,
+            Ok(n) => received.extend(&buf[..n]),
+            Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+            Err(_) => return false,
+          }
+        }
+        match parse(received) {
+          Ok(None) => {}
+          Ok(Some(mut request)) => {
+            request.id = c.id;
+            requests.push(request);
+            c.state = State::Answering;
+          }
+          Err(status) => {
+            let body = format!("{}\n", status);
+            c.state = State::Sending(response(status, "text/plain", &body));
+          }
+        }
+      }
+      match &mut c.state {
+        State::Sending(pending) => send(&mut c.stream, pending),
+        _ => true,
+      }
+    });
+    requests
+  }
+
+  /// Queues the answer to `request`, sent over the next frames.
+  pub fn respond(&mut self, request: Request, status: &str, content_type: &str, body: &str) {
+    if let Some(c) = self.connections.iter_mut().find(|c| c.id == request.id) {
+      let mut pending = response(status, content_type, body);
+      send(&mut c.stream, &mut pending);
+      c.state = State::Sending(pending);
+    }
+  }
+}
+
+/// Writes what the socket takes, false once there is nothing left to keep
+/// the connection for.
+fn send(stream: &mut TcpStream, pending: &mut Vec<u8>) -> bool {
+  while !pending.is_empty() {
+    match stream.write(pending) {
+      Ok(0) => return false,
+      Ok(n) => {
+        pending.drain(..n);
+      }
+      Err(e) => return e.kind() == ErrorKind::WouldBlock,
+    }
+  }
+  false
+}
+
+fn response(status: &str, content_type: &str, body: &str) -> Vec<u8> {
+  format!(
+    "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+     Connection: close\r\n\r\n{}",
+    status,
+    content_type,
+    body.len(),
+    body
+  )
+  .into_bytes()
+}
+
+/// The request in `data` once all of it is there, `None` while more is to
+/// come, or the status to turn it away with.
+fn parse(data: &[u8]) -> Result<Option<Request>, &'static str> {
+  const TOO_LARGE: &str = "413 Payload Too Large";
+  const BAD: &str = "400 Bad Request";
+  let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") else {
+    return if data.len() > MAX_REQUEST {
+      Err(TOO_LARGE)
+    } else {
+      Ok(None)
+    };
+  };
+  let head_len = end + 4;
+  let head = String::from_utf8_lossy(&data[..end]);
+  let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+  let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+    return Err(BAD);
+  };
+  let header = |wanted: &str| {
+    head
+      .lines()
+      .filter_map(|l| l.split_once(':'))
+      .find(|(name, _)| name.eq_ignore_ascii_case(wanted))
+      .map(|(_, value)| value.trim())
+  };
+  let body_len = match header("content-length") {
+    Some(value) => value.parse::<usize>().map_err(|_| BAD)?,
+    None => 0,
+  };
+  if head_len.saturating_add(body_len) > MAX_REQUEST {
+    return Err(TOO_LARGE);
+  }
+  let Some(body) = data.get(head_len..head_len + body_len) else {
+    return Ok(None);
+  };
+  Ok(Some(Request {
+    method: method.to_owned(),
+    path: path.to_owned(),
+    body: String::from_utf8_lossy(body).into_owned(),
+    origin: header("origin").map(str::to_owned),
+    id: 0,
+  }))
+}
+
+/// Every body, sun last, as one JSON object.
+pub fn state_json(objects: &[Planet], day: u64) -> String {
+  let mut json = format!("{{\"day\":{},\"bodies\":[", day);
+  for (i, p) in objects.iter().enumerate() {
+    let [r, g, b, _]: [u8; 4] = p.color.into();
+    let _ = write!(
+      json,
+      "{}{{\"id\":{},\"name\":{},\"kind\":\"{}\",\"mass\":{},\"x\":{},\"y\":{},\"z\":{},\
+       \"vx\":{},\"vy\":{},\"vz\":{},\"color\":\"#{:02x}{:02x}{:02x}\"}}",
+      if i == 0 { "" } else { "," },
+      p.id,
+      string(&p.name),
+      scenario::kind_name(p.kind),
+      number(p.mass),
+      number(p.pos.x),
+      number(p.pos.y),
+      number(p.z),
+      number(p.velocity.x),
+      number(p.velocity.y),
+      number(p.vz),
+      r,
+      g,
+      b
+    );
+  }
+  json.push_str("]}");
+  json
+}
+
+/// Recent entries of the event log, newest first.
+pub fn events_json<'a>(events: impl Iterator<Item = &'a Event>) -> String {
+  let entries = events.map(|e| format!("{{\"day\":{},\"text\":{}}}", e.day, string(&e.text)));
+  format!("[{}]", Vec::from_iter(entries).join(","))
+}
+
+fn string(s: &str) -> String {
+  let mut json = String::from("\"");
+  for c in s.chars() {
+    match c {
+      '"' => json.push_str("\\\""),
+      '\\' => json.push_str("\\\\"),
+      c if c.is_control() => {
+        let _ = write!(json, "\\u{:04x}", c as u32);
+      }
+      c => json.push(c),
+    }
+  }
+  json.push('"');
+  json
+}
+
+/// JSON has no infinities or NaN.
+fn number(x: f32) -> String {
+  if x.is_finite() {
+    x.to_string()
+  } else {
+    "null".to_owned()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn complete_request() {
+    let request = parse(b"POST /control HTTP/1.1\r\nContent-Length: 6\r\n\r\nseed 7")
+      .unwrap()
+      .unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/control");
+    assert_eq!(request.body, "seed 7");
+  }
+
+  #[test]
+  fn waits_for_the_rest() {
+    assert!(matches!(parse(b"GET /state HTTP/1.1\r\nHost: x"), Ok(None)));
+    assert!(matches!(
+      parse(b"POST /control HTTP/1.1\r\ncontent-length: 6\r\n\r\nseed"),
+      Ok(None)
+    ));
+  }
+
+  #[test]
+  fn oversized_content_length() {
+    let head = format!(
+      "POST /control HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+      MAX_REQUEST
+    );
+    assert!(parse(head.as_bytes()).is_err());
+    let head = format!(
+      "POST /control HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+      usize::MAX
+    );
+    assert!(parse(head.as_bytes()).is_err());
+  }
+
+  #[test]
+  fn endless_headers() {
+    assert!(parse(&vec![b'a'; MAX_REQUEST + 1]).is_err());
+  }
+
+  #[test]
+  fn origin() {
+    let request = |origin: &str| {
+      let head = format!("POST /control HTTP/1.1\r\nOrigin: {}\r\n\r\n", origin);
+      parse(head.as_bytes()).unwrap().unwrap()
+    };
+    assert_eq!(
+      request("http://a.test").origin.as_deref(),
+      Some("http://a.test")
+    );
+    for local in [
+      "",
+      "http://localhost",
+      "http://127.0.0.1:8080",
+      "https://[::1]:3000",
+    ] {
+      assert!(!request(local).cross_site(), "{}", local);
+    }
+    for other in [
+      "null",
+      "http://evil.test",
+      "http://localhost.evil.test",
+      "http://127.0.0.1.evil.test:80",
+      "file://localhost",
+    ] {
+      assert!(request(other).cross_site(), "{}", other);
+    }
+    let plain = parse(b"POST /control HTTP/1.1\r\n\r\n").unwrap().unwrap();
+    assert!(!plain.cross_site());
+  }
+
+  #[test]
+  fn malformed() {
+    assert!(parse(b"GET\r\n\r\n").is_err());
+    assert!(parse(b"\r\n\r\n").is_err());
+    assert!(parse(b"POST /control HTTP/1.1\r\nContent-Length: six\r\n\r\n").is_err());
+  }
+}
@@ -0,0 +1,48 @@
+use macroquad::prelude::*;
+
+use crate::hidpi;
+
+/// Values shown in the top left overlay.
+pub struct HudStats {
+  pub bodies: usize,
+  pub sim_days: u64,
+  pub time_scale: u32,
+  pub paused: bool,
+  pub integrator: &'static str,
+  /// Uniform field, shown only when there is one.
+  pub field: Vec2,
+}
+
+pub fn render(stats: &HudStats) {
+  const SIZE: f32 = 22.;
+
+  let speed = if stats.paused {
+    format!("x{} (paused)", stats.time_scale)
+  } else {
+    format!("x{}", stats.time_scale)
+  };
+  let mut lines = vec![
+    format!("FPS         {}", get_fps()),
+    format!("Bodies      {}", stats.bodies),
+    format!("Time        {}", crate::format_day(stats.sim_days)),
+    format!("Time scale  {}", speed),
+    format!("Integrator  {}", stats.integrator),
+  ];
+  if stats.field != Vec2::ZERO {
+    lines.push(format!(
+      "Field       {:.5} {:.5}",
+      stats.field.x, stats.field.y
+    ));
+  }
+
+  draw_rectangle(
+    5.,
+    5.,
+    300.,
+    lines.len() as f32 * SIZE + 10.,
+    Color::new(0., 0., 0., 0.5),
+  );
+  for (i, line) in lines.iter().enumerate() {
+    hidpi::draw_text_sharp(line, 12., 25. + i as f32 * SIZE, SIZE, LIGHTGRAY);
+  }
+}
@@ -0,0 +1,415 @@
+use macroquad::prelude::*;
+use std::collections::HashMap;
+
+/// Everything the keyboard can trigger, rebindable from the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+  Reset,
+  /// Held rather than toggled.
+  Pause,
+  TimeScale,
+  SpeedTrails,
+  RimLight,
+  Terminator,
+  Shadows,
+  Heatmap,
+  Hud,
+  Minimap,
+  Grid,
+  Measure,
+  Ghosts,
+  Exposure,
+  ClearExposure,
+  SaveExposure,
+  ExportSvg,
+  ResetCamera,
+  Help,
+  Fullscreen,
+  Menu,
+  Console,
+  OtherTrails,
+  SpawnMoonlet,
+  SpawnRocky,
+  SpawnGasGiant,
+  SpawnStar,
+  SpawnBlackHole,
+  Insertion,
+  Recenter,
+  Profiler,
+  View3d,
+  FieldLeft,
+  FieldRight,
+  FieldUp,
+  FieldDown,
+  FieldReset,
+  SlowMotion,
+  Inset,
+  Split,
+  Follow,
+  Tour,
+  Snapshot,
+  Population,
+  Sparklines,
+  Elements,
+  Poincare,
+}
+
+impl Action {
+  pub const ALL: [Action; 47] = [
+    Action::Reset,
+    Action::Pause,
+    Action::TimeScale,
+    Action::SpeedTrails,
+    Action::RimLight,
+    Action::Terminator,
+    Action::Shadows,
+    Action::Heatmap,
+    Action::Hud,
+    Action::Minimap,
+    Action::Grid,
+    Action::Measure,
+    Action::Ghosts,
+    Action::Exposure,
+    Action::ClearExposure,
+    Action::SaveExposure,
+    Action::ExportSvg,
+    Action::ResetCamera,
+    Action::Help,
+    Action::Fullscreen,
+    Action::Menu,
+    Action::Console,
+    Action::OtherTrails,
+    Action::SpawnMoonlet,
+    Action::SpawnRocky,
+    Action::SpawnGasGiant,
+    Action::SpawnStar,
+    Action::SpawnBlackHole,
+    Action::Insertion,
+    Action::Recenter,
+    Action::Profiler,
+    Action::View3d,
+    Action::FieldLeft,
+    Action::FieldRight,
+    Action::FieldUp,
+    Action::FieldDown,
+    Action::FieldReset,
+    Action::SlowMotion,
+    Action::Inset,
+    Action::Split,
+    Action::Follow,
+    Action::Tour,
+    Action::Snapshot,
+    Action::Population,
+    Action::Sparklines,
+    Action::Elements,
+    Action::Poincare,
+  ];
+
+  /// Spawn actions in hotbar order, matching `presets::PRESETS`.
+  pub const SPAWN: [Action; 5] = [
+    Action::SpawnMoonlet,
+    Action::SpawnRocky,
+    Action::SpawnGasGiant,
+    Action::SpawnStar,
+    Action::SpawnBlackHole,
+  ];
+
+  /// Name used for `keys.<name>` in the config.
+  pub fn name(self) -> &'static str {
+    match self {
+      Action::Reset => "reset",
+      Action::Pause => "pause",
+      Action::TimeScale => "time_scale",
+      Action::SpeedTrails => "speed_trails",
+      Action::RimLight => "rim_light",
+      Action::Terminator => "terminator",
+      Action::Shadows => "shadows",
+      Action::Heatmap => "heatmap",
+      Action::Hud => "hud",
+      Action::Minimap => "minimap",
+      Action::Grid => "grid",
+      Action::Measure => "measure",
+      Action::Ghosts => "ghosts",
+      Action::Exposure => "exposure",
+      Action::ClearExposure => "clear_exposure",
+      Action::SaveExposure => "save_exposure",
+      Action::ExportSvg => "export_svg",
+      Action::ResetCamera => "reset_camera",
+      Action::Help => "help",
+      Action::Fullscreen => "fullscreen",
+      Action::Menu => "menu",
+      Action::Console => "console",
+      Action::OtherTrails => "other_trails",
+      Action::SpawnMoonlet => "spawn_moonlet",
+      Action::SpawnRocky => "spawn_rocky",
+      Action::SpawnGasGiant => "spawn_gas_giant",
+      Action::SpawnStar => "spawn_star",
+      Action::SpawnBlackHole => "spawn_black_hole",
+      Action::Insertion => "insertion",
+      Action::Recenter => "recenter",
+      Action::Profiler => "profiler",
+      Action::View3d => "view_3d",
+      Action::FieldLeft => "field_left",
+      Action::FieldRight => "field_right",
+      Action::FieldUp => "field_up",
+      Action::FieldDown => "field_down",
+      Action::FieldReset => "field_reset",
+      Action::SlowMotion => "slow_motion",
+      Action::Inset => "inset",
+      Action::Split => "split",
+      Action::Follow => "follow",
+      Action::Tour => "tour",
+      Action::Snapshot => "snapshot",
+      Action::Population => "population",
+      Action::Sparklines => "sparklines",
+      Action::Elements => "elements",
+      Action::Poincare => "poincare",
+    }
+  }
+
+  pub fn description(self) -> &'static str {
+    match self {
+      Action::Reset => "new system",
+      Action::Pause => "pause while held",
+      Action::TimeScale => "cycle fast-forward",
+      Action::SpeedTrails => "color trails by speed",
+      Action::RimLight => "rim lighting",
+      Action::Terminator => "night-side shading",
+      Action::Shadows => "shadows",
+      Action::Heatmap => "cycle heatmap",
+      Action::Hud => "stats",
+      Action::Minimap => "minimap",
+      Action::Grid => "cycle distance grid",
+      Action::Measure => "measure tool",
+      Action::Ghosts => "previous system's trails",
+      Action::Exposure => "long exposure",
+      Action::ClearExposure => "clear long exposure",
+      Action::SaveExposure => "save long exposure as PNG",
+      Action::ExportSvg => "save trails as SVG",
+      Action::ResetCamera => "reset view",
+      Action::Help => "this help",
+      Action::Fullscreen => "fullscreen",
+      Action::Menu => "pause menu",
+      Action::Console => "command console",
+      Action::OtherTrails => "dim or hide unselected trails",
+      Action::SpawnMoonlet => "spawn a moonlet at the cursor",
+      Action::SpawnRocky => "spawn a rocky planet",
+      Action::SpawnGasGiant => "spawn a gas giant",
+      Action::SpawnStar => "spawn a star",
+      Action::SpawnBlackHole => "spawn a black hole",
+      Action::Insertion => "cycle spawn orbit",
+      Action::Recenter => "stop drift, center on barycenter",
+      Action::Profiler => "frame time breakdown",
+      Action::View3d => "3D view",
+      Action::FieldLeft => "field towards the left",
+      Action::FieldRight => "field towards the right",
+      Action::FieldUp => "field towards the top",
+      Action::FieldDown => "field towards the bottom",
+      Action::FieldReset => "switch the field off",
+      Action::SlowMotion => "slow down for close encounters",
+      Action::Inset => "close-up of the selected body",
+      Action::Split => "split the screen with a second camera",
+      Action::Follow => "follow the selected body",
+      Action::Tour => "tour the system",
+      Action::Snapshot => "save a transparent PNG",
+      Action::Population => "population statistics",
+      Action::Sparklines => "energy and angular momentum charts",
+      Action::Elements => "orbit of the selected body over time",
+      Action::Poincare => "surface of section of the selected body",
+    }
+  }
+
+  pub fn from_name(name: &str) -> Option<Action> {
+    Action::ALL.into_iter().find(|a| a.name() == name)
+  }
+
+  fn default_key(self) -> KeyCode {
+    match self {
+      Action::Reset => KeyCode::R,
+      Action::Pause => KeyCode::Space,
+      Action::TimeScale => KeyCode::F,
+      Action::SpeedTrails => KeyCode::C,
+      Action::RimLight => KeyCode::L,
+      Action::Terminator => KeyCode::N,
+      Action::Shadows => KeyCode::K,
+      Action::Heatmap => KeyCode::M,
+      Action::Hud => KeyCode::Tab,
+      Action::Minimap => KeyCode::O,
+      Action::Grid => KeyCode::G,
+      Action::Measure => KeyCode::T,
+      Action::Ghosts => KeyCode::V,
+      Action::Exposure => KeyCode::E,
+      Action::ClearExposure => KeyCode::X,
+      Action::SaveExposure => KeyCode::P,
+      Action::ExportSvg => KeyCode::J,
+      Action::ResetCamera => KeyCode::Z,
+      Action::Help => KeyCode::H,
+      Action::Fullscreen => KeyCode::F11,
+      Action::Menu => KeyCode::Escape,
+      Action::Console => KeyCode::GraveAccent,
+      Action::OtherTrails => KeyCode::D,
+      Action::SpawnMoonlet => KeyCode::Key1,
+      Action::SpawnRocky => KeyCode::Key2,
+      Action::SpawnGasGiant => KeyCode::Key3,
+      Action::SpawnStar => KeyCode::Key4,
+      Action::SpawnBlackHole => KeyCode::Key5,
+      Action::Insertion => KeyCode::I,
+      Action::Recenter => KeyCode::B,
+      Action::Profiler => KeyCode::F3,
+      Action::View3d => KeyCode::Y,
+      Action::FieldLeft => KeyCode::Left,
+      Action::FieldRight => KeyCode::Right,
+      Action::FieldUp => KeyCode::Up,
+      Action::FieldDown => KeyCode::Down,
+      Action::FieldReset => KeyCode::Backspace,
+      Action::SlowMotion => KeyCode::S,
+      Action::Inset => KeyCode::W,
+      Action::Split => KeyCode::Q,
+      Action::Follow => KeyCode::A,
+      Action::Tour => KeyCode::U,
+      Action::Snapshot => KeyCode::F2,
+      Action::Population => KeyCode::F4,
+      Action::Sparklines => KeyCode::F5,
+      Action::Elements => KeyCode::F6,
+      Action::Poincare => KeyCode::F7,
+    }
+  }
+}
+
+/// Keys bound to each action, the defaults with config overrides on top.
+#[derive(Debug, Clone)]
+pub struct InputMap {
+  keys: HashMap<Action, KeyCode>,
+}
+
+impl InputMap {
+  pub fn new(overrides: &[(Action, KeyCode)]) -> Self {
+    let mut keys = HashMap::from_iter(Action::ALL.map(|a| (a, a.default_key())));
+    keys.extend(overrides.iter().copied());
+
+    let map = InputMap { keys };
+    for (i, (a, key)) in map.bindings().enumerate() {
+      for (b, _) in map.bindings().skip(i + 1).filter(|&(_, k)| k == key) {
+        eprintln!(
+          "`{}` and `{}` are both bound to {}",
+          a.name(),
+          b.name(),
+          key_name(key)
+        );
+      }
+    }
+    map
+  }
+
+  pub fn key(&self, action: Action) -> KeyCode {
+    self.keys[&action]
+  }
+
+  pub fn pressed(&self, action: Action) -> bool {
+    is_key_pressed(self.key(action))
+  }
+
+  pub fn down(&self, action: Action) -> bool {
+    is_key_down(self.key(action))
+  }
+
+  /// Every action with its key, in a stable order.
+  pub fn bindings(&self) -> impl Iterator<Item = (Action, KeyCode)> + '_ {
+    Action::ALL.into_iter().map(|a| (a, self.key(a)))
+  }
+}
+
+/// Whether any key, mouse button, wheel or touch was used this frame,
+/// bound to something or not. Mouse movement isn't included.
+pub fn any_pressed() -> bool {
+  let (wheel_x, wheel_y) = mouse_wheel();
+  wheel_x != 0.
+    || wheel_y != 0.
+    || get_last_key_pressed().is_some()
+    || [MouseButton::Left, MouseButton::Right, MouseButton::Middle]
+      .into_iter()
+      .any(is_mouse_button_pressed)
+    || !touches().is_empty()
+}
+
+/// Keys that can be bound, matched by their name ignoring case.
+const BINDABLE: [KeyCode; 63] = [
+  KeyCode::A,
+  KeyCode::B,
+  KeyCode::C,
+  KeyCode::D,
+  KeyCode::E,
+  KeyCode::F,
+  KeyCode::G,
+  KeyCode::H,
+  KeyCode::I,
+  KeyCode::J,
+  KeyCode::K,
+  KeyCode::L,
+  KeyCode::M,
+  KeyCode::N,
+  KeyCode::O,
+  KeyCode::P,
+  KeyCode::Q,
+  KeyCode::R,
+  KeyCode::S,
+  KeyCode::T,
+  KeyCode::U,
+  KeyCode::V,
+  KeyCode::W,
+  KeyCode::X,
+  KeyCode::Y,
+  KeyCode::Z,
+  KeyCode::Key0,
+  KeyCode::Key1,
+  KeyCode::Key2,
+  KeyCode::Key3,
+  KeyCode::Key4,
+  KeyCode::Key5,
+  KeyCode::Key6,
+  KeyCode::Key7,
+  KeyCode::Key8,
+  KeyCode::Key9,
+  KeyCode::F1,
+  KeyCode::F2,
+  KeyCode::F3,
+  KeyCode::F4,
+  KeyCode::F5,
+  KeyCode::F6,
+  KeyCode::F7,
+  KeyCode::F8,
+  KeyCode::F9,
+  KeyCode::F10,
+  KeyCode::F11,
+  KeyCode::F12,
+  KeyCode::Space,
+  KeyCode::Tab,
+  KeyCode::Enter,
+  KeyCode::Escape,
+  KeyCode::Backspace,
+  KeyCode::Up,
+  KeyCode::Down,
+  KeyCode::Left,
+  KeyCode::Right,
+  KeyCode::Minus,
+  KeyCode::Equal,
+  KeyCode::Comma,
+  KeyCode::Period,
+  KeyCode::Slash,
+  KeyCode::GraveAccent,
+];
+
+/// Parses key names like `R`, `space`, `f5` or `1`.
+pub fn parse_key(name: &str) -> Result<KeyCode, String> {
+  BINDABLE
+    .into_iter()
+    .find(|&k| {
+      let n = key_name(k);
+      n.eq_ignore_ascii_case(name) || n.strip_prefix("Key") == Some(name)
+    })
+    .ok_or_else(|| format!("unknown key `{}`", name))
+}
+
+pub fn key_name(key: KeyCode) -> String {
+  format!("{:?}", key)
+}
@@ -0,0 +1,128 @@
+//! Picture-in-picture close-up of the selected body, or of wherever the
+//! latest collision or supernova happened, while the main view shows the
+//! whole system.
+
+use macroquad::prelude::*;
+
+use crate::camera::{base_scale, screen_center, View};
+use crate::hidpi::{self, physical_screen_size};
+use crate::Planet;
+
+/// Width of the inset as a share of the screen; it keeps the screen's
+/// aspect ratio.
+const SHARE: f32 = 0.28;
+/// Zoom of the close-up before it gets shrunk into the inset.
+const ZOOM: f32 = 20.;
+const MARGIN: f32 = 10.;
+
+/// The close-up is drawn like the main view, into an offscreen target
+/// between [`Inset::begin`] and [`Inset::finish`], then shown shrunk down on
+/// the right edge of the screen.
+pub struct Inset {
+  pub visible: bool,
+  target: RenderTarget,
+  size: (u32, u32),
+  /// Where the latest event happened, shown when nothing is selected.
+  event: Option<Vec2>,
+  /// Name or description shown under the inset.
+  label: String,
+}
+
+impl Inset {
+  pub fn new() -> Self {
+    let size = target_size();
+    Inset {
+      visible: false,
+      target: new_target(size),
+      size,
+      event: None,
+      label: String::new(),
+    }
+  }
+
+  /// Remembers an event location to look at while nothing is selected.
+  pub fn event(&mut self, pos: Vec2) {
+    self.event = Some(pos);
+  }
+
+  pub fn clear(&mut self) {
+    self.event = None;
+  }
+
+  /// Redirects drawing into the offscreen target, cleared to `background`,
+  /// and returns the view to draw with. None when hidden or with nothing to
+  /// look at, in which case there's no need to call [`Inset::finish`].
+  pub fn begin(
+    &mut self,
+    objects: &[Planet],
+    selected: Option<u32>,
+    background: Color,
+  ) -> Option<View> {
+    if !self.visible {
+      return None;
+    }
+    let (zero, label) = match objects.iter().find(|p| Some(p.id) == selected) {
+      Some(body) => (body.pos, body.name.clone()),
+      None => (self.event?, "latest event".to_owned()),
+    };
+    self.label = label;
+
+    let size = target_size();
+    if size != self.size {
+      self.target.delete();
+      self.target = new_target(size);
+      self.size = size;
+    }
+    let mut camera =
+      Camera2D::from_display_rect(Rect::new(0., 0., screen_width(), screen_height()));
+    camera.render_target = Some(self.target);
+    set_camera(&camera);
+    clear_background(background);
+    Some(View {
+      zero,
+      scale: base_scale() * ZOOM,
+      center: screen_center(),
+    })
+  }
+
+  /// Goes back to drawing on the screen and shows the captured close-up.
+  pub fn finish(&self) {
+    set_default_camera();
+
+    let (w, h) = (screen_width() * SHARE, screen_height() * SHARE);
+    let corner = vec2(screen_width() - w - MARGIN, (screen_height() - h) / 2.);
+    draw_texture_ex(
+      self.target.texture,
+      corner.x,
+      corner.y,
+      WHITE,
+      DrawTextureParams {
+        dest_size: Some(vec2(w, h)),
+        flip_y: true,
+        ..Default::default()
+      },
+    );
+    draw_rectangle_lines(corner.x, corner.y, w, h, 1., GRAY);
+    hidpi::draw_text_sharp(
+      &self.label,
+      corner.x + 6.,
+      corner.y + h - 8.,
+      18.,
+      LIGHTGRAY,
+    );
+  }
+}
+
+fn target_size() -> (u32, u32) {
+  let (width, height) = physical_screen_size();
+  (
+    (width as f32 * SHARE) as u32,
+    (height as f32 * SHARE) as u32,
+  )
+}
+
+fn new_target((width, height): (u32, u32)) -> RenderTarget {
+  let target = render_target(width.max(1), height.max(1));
+  target.texture.set_filter(FilterMode::Linear);
+  target
+}
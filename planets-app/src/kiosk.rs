@@ -0,0 +1,64 @@
+//! Running unattended for the public, as in a museum: visitors can look
+//! around, select and spawn bodies by touch or mouse, but nothing that quits,
+//! writes files or opens the menu or console, and a fresh system replaces
+//! theirs once nobody has touched anything for a while.
+
+use macroquad::prelude::*;
+
+use crate::input::{self, Action};
+use crate::state;
+
+/// Actions visitors don't get.
+const BLOCKED: [Action; 6] = [
+  Action::Menu,
+  Action::Fullscreen,
+  Action::ExportSvg,
+  Action::SaveExposure,
+  Action::Snapshot,
+  Action::Profiler,
+];
+
+#[derive(Debug)]
+pub struct Kiosk {
+  /// Seconds without input before starting over.
+  idle: f64,
+  last_input: f64,
+  mouse: Vec2,
+  /// Whether anyone used it since the last reset, so an idle system isn't
+  /// replaced over and over.
+  used: bool,
+}
+
+impl Kiosk {
+  /// Goes fullscreen.
+  pub fn start(idle_seconds: f32) -> Self {
+    state::set_fullscreen(true);
+    Kiosk {
+      idle: idle_seconds.max(1.) as f64,
+      last_input: get_time(),
+      mouse: Vec2::from(mouse_position()),
+      used: false,
+    }
+  }
+
+  pub fn allows(action: Action) -> bool {
+    !BLOCKED.contains(&action)
+  }
+
+  /// Call every frame: true once input has stopped for long enough and
+  /// it's time to start over.
+  pub fn idle(&mut self) -> bool {
+    let now = get_time();
+    let mouse = Vec2::from(mouse_position());
+    if mouse != self.mouse || input::any_pressed() {
+      self.mouse = mouse;
+      self.last_input = now;
+      self.used = true;
+    }
+    if self.used && now - self.last_input >= self.idle {
+      self.used = false;
+      return true;
+    }
+    false
+  }
+}
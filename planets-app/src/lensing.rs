@@ -0,0 +1,131 @@
+use macroquad::prelude::*;
+
+use crate::hidpi::physical_screen_size;
+
+/// Screen-space gravitational lensing around a single point mass.
+///
+/// The scene is drawn into an offscreen target between [`Lensing::begin`]
+/// and [`Lensing::finish`], then composited through a shader that bends the
+/// sampling rays towards the lens.
+pub struct Lensing {
+  material: Material,
+  target: RenderTarget,
+  size: (u32, u32),
+}
+
+impl Lensing {
+  pub fn new() -> Self {
+    let material = load_material(
+      VERTEX_SHADER,
+      FRAGMENT_SHADER,
+      MaterialParams {
+        uniforms: vec![
+          ("Center".to_owned(), UniformType::Float2),
+          ("Resolution".to_owned(), UniformType::Float2),
+          ("Radius".to_owned(), UniformType::Float1),
+        ],
+        ..Default::default()
+      },
+    )
+    .expect("lensing shader should compile");
+    let size = physical_screen_size();
+    Lensing {
+      material,
+      target: new_target(size),
+      size,
+    }
+  }
+
+  /// Redirects subsequent drawing into the offscreen target, cleared to
+  /// `background`.
+  pub fn begin(&mut self, background: Color) {
+    let size = physical_screen_size();
+    if size != self.size {
+      self.target.delete();
+      self.target = new_target(size);
+      self.size = size;
+    }
+
+    let mut camera =
+      Camera2D::from_display_rect(Rect::new(0., 0., screen_width(), screen_height()));
+    camera.render_target = Some(self.target);
+    set_camera(&camera);
+    clear_background(background);
+  }
+
+  /// Draws the captured scene to the screen, warped around `center` (screen
+  /// pixels) with the given Einstein radius.
+  pub fn finish(&self, center: Vec2, radius: f32) {
+    set_default_camera();
+
+    let (w, h) = (screen_width(), screen_height());
+    // The target is stored bottom-up, so flip the lens center to match.
+    self
+      .material
+      .set_uniform("Center", vec2(center.x / w, 1. - center.y / h));
+    self.material.set_uniform("Resolution", vec2(w, h));
+    self.material.set_uniform("Radius", radius);
+
+    gl_use_material(self.material);
+    draw_texture_ex(
+      self.target.texture,
+      0.,
+      0.,
+      WHITE,
+      DrawTextureParams {
+        dest_size: Some(vec2(w, h)),
+        flip_y: true,
+        ..Default::default()
+      },
+    );
+    gl_use_default_material();
+  }
+}
+
+fn new_target((width, height): (u32, u32)) -> RenderTarget {
+  let target = render_target(width.max(1), height.max(1));
+  target.texture.set_filter(FilterMode::Linear);
+  target
+}
+
+const VERTEX_SHADER: &str = "#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+
+varying mediump vec2 uv;
+varying lowp vec4 color;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+  gl_Position = Projection * Model * vec4(position, 1);
+  color = color0 / 255.0;
+  uv = texcoord;
+}
+";
+
+const FRAGMENT_SHADER: &str = "#version 100
+precision mediump float;
+
+varying vec2 uv;
+varying vec4 color;
+
+uniform sampler2D Texture;
+uniform vec2 Center;
+uniform vec2 Resolution;
+uniform float Radius;
+
+void main() {
+  vec2 d = (uv - Center) * Resolution;
+  float r = max(length(d), 1.0);
+
+  // Point-lens equation: light seen at r comes from r - Radius^2 / r.
+  // Faded out with distance so the rest of the screen stays untouched.
+  float falloff = 1.0 - smoothstep(Radius * 3.0, Radius * 8.0, r);
+  vec2 source = d * (1.0 - falloff * Radius * Radius / (r * r));
+
+  gl_FragColor = color * texture2D(Texture, Center + source / Resolution);
+}
+";
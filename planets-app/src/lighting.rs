@@ -0,0 +1,63 @@
+use macroquad::prelude::*;
+
+use crate::camera::View;
+use crate::{BodyKind, Planet};
+
+/// Darkest an eclipse can make a body, as a share of blocked light.
+const MAX_ECLIPSE: f32 = 0.85;
+
+/// Position of the star closest to `pos`, the light source for shading.
+pub fn nearest_star(objects: &[Planet], pos: Vec2) -> Option<Vec2> {
+  objects
+    .iter()
+    .filter(|p| p.kind == BodyKind::Star)
+    .map(|p| p.pos)
+    .min_by(|a, b| a.distance_squared(pos).total_cmp(&b.distance_squared(pos)))
+}
+
+/// Share of the light from `star` blocked before it reaches `objects[i]`.
+///
+/// Shadows are treated as cylinders as wide as the body casting them, which
+/// is close enough at the distances involved.
+pub fn eclipse(objects: &[Planet], i: usize, star: Vec2) -> f32 {
+  let target = &objects[i];
+  let to_target = target.pos - star;
+  let dist = to_target.length();
+  let dir = to_target / dist;
+  let r = target.radius(1.);
+
+  objects
+    .iter()
+    .enumerate()
+    .filter(|&(j, p)| j != i && p.pos != star)
+    .map(|(_, p)| {
+      let along = (p.pos - star).dot(dir);
+      if along <= 0. || along >= dist {
+        return 0.;
+      }
+      let across = (p.pos - star - dir * along).length();
+      let overlap = (p.radius(1.) + r - across) / (2. * r.min(p.radius(1.)));
+      overlap.clamp(0., 1.)
+    })
+    .fold(0., f32::max)
+    * MAX_ECLIPSE
+}
+
+/// Casts a fading band from `body` directly away from `star`.
+pub fn draw_shadow(view: &View, body: &Planet, star: Vec2) {
+  const LENGTH: f32 = 250.;
+  const BANDS: usize = 8;
+  const ALPHA: f32 = 0.35;
+
+  let dir = (body.pos - star).normalize_or_zero();
+  let side = dir.perp() * body.radius(1.);
+  for band in 0..BANDS {
+    let t0 = band as f32 / BANDS as f32;
+    let t1 = (band + 1) as f32 / BANDS as f32;
+    let (a, b) = (body.pos + dir * LENGTH * t0, body.pos + dir * LENGTH * t1);
+    let corners = [a + side, a - side, b - side, b + side].map(|p| view.world_to_screen(p));
+    let color = Color::new(0., 0., 0., ALPHA * (1. - t0));
+    draw_triangle(corners[0], corners[1], corners[2], color);
+    draw_triangle(corners[0], corners[2], corners[3], color);
+  }
+}
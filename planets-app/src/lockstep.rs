@@ -0,0 +1,115 @@
+//! Strict determinism for comparing runs: the same seed gives bit-identical
+//! states step for step, which a short hash of the state makes easy to
+//! check across runs and machines. A run can be recorded as its seed plus
+//! the hash at every checkpoint, and verified later by replaying it.
+
+use ::rand::{rngs::StdRng, SeedableRng};
+use macroquad::prelude::*;
+use planets_core::energy;
+use std::fs::{self, File};
+use std::io::{self, Write};
+
+use crate::profiler::Timings;
+use crate::{advance, random_setup, Physics, Planet};
+
+const MAGIC: &[u8; 4] = b"PLRP";
+
+/// FNV-1a over the exact bits of every body's identity and state, in list
+/// order. Departing bodies are skipped, as they leave the list at a time
+/// that depends on the frame rate.
+pub fn state_hash(objects: &[Planet]) -> u64 {
+  const OFFSET: u64 = 0xcbf29ce484222325;
+  const PRIME: u64 = 0x100000001b3;
+  let mut hash = OFFSET;
+  let mut feed = |word: u32| {
+    for byte in word.to_le_bytes() {
+      hash ^= byte as u64;
+      hash = hash.wrapping_mul(PRIME);
+    }
+  };
+  for p in objects.iter().filter(|p| p.departing.is_none()) {
+    feed(p.id);
+    let state = [
+      p.mass,
+      p.pos.x,
+      p.pos.y,
+      p.z,
+      p.velocity.x,
+      p.velocity.y,
+      p.vz,
+    ];
+    for x in state {
+      feed(x.to_bits());
+    }
+  }
+  hash
+}
+
+/// Writes a replay: the seed and checkpoint interval, then a `day hash`
+/// pair per checkpoint, all little-endian `u64`s.
+pub struct Recorder {
+  file: File,
+}
+
+impl Recorder {
+  pub fn create(path: &str, seed: u64, every: u64) -> io::Result<Recorder> {
+    let mut file = File::create(path)?;
+    file.write_all(MAGIC)?;
+    file.write_all(&seed.to_le_bytes())?;
+    file.write_all(&every.to_le_bytes())?;
+    Ok(Recorder { file })
+  }
+
+  pub fn checkpoint(&mut self, day: u64, hash: u64) -> io::Result<()> {
+    self.file.write_all(&day.to_le_bytes())?;
+    self.file.write_all(&hash.to_le_bytes())
+  }
+}
+
+/// Replays a recorded run without drawing anything, returning a summary,
+/// or the first checkpoint whose state differs. Needs the same
+/// configuration as the recording, which decides the system and physics.
+pub fn verify(
+  path: &str,
+  palette: &[Color],
+  physics: &Physics,
+  energy_correction: u32,
+) -> Result<String, String> {
+  let data = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+  let words = match data.strip_prefix(MAGIC) {
+    Some(rest) if rest.len() % 8 == 0 => Vec::from_iter(
+      rest
+        .chunks_exact(8)
+        .map(|w| u64::from_le_bytes(w.try_into().unwrap())),
+    ),
+    _ => return Err(format!("{}: not a replay", path)),
+  };
+  let [seed, _every, ref checkpoints @ ..] = words[..] else {
+    return Err(format!("{}: not a replay", path));
+  };
+
+  let mut objects = random_setup(palette, &mut StdRng::seed_from_u64(seed), physics);
+  let mut drift = energy::Drift::new(energy_correction);
+  let mut day = 0;
+  for pair in checkpoints.chunks_exact(2) {
+    let matched = day;
+    let (target, expected) = (pair[0], pair[1]);
+    while day < target {
+      advance(&mut objects, physics, &mut drift, &mut Timings::default());
+      day += 1;
+    }
+    let hash = state_hash(&objects);
+    if hash != expected {
+      return Err(format!(
+        "{}: diverged between day {} and day {}, expected {:016x}, got {:016x}",
+        path, matched, day, expected, hash
+      ));
+    }
+  }
+  Ok(format!(
+    "{}: all {} checkpoints match, {} days",
+    path,
+    checkpoints.len() / 2,
+    day
+  ))
+}
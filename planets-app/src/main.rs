@@ -0,0 +1,2307 @@
+use ::rand::{self, rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use itertools::Itertools;
+use macroquad::prelude::*;
+use planets_core::radiation::{self, Radiation};
+use planets_core::{collision, energy, integrate, potential, Impact, Potential};
+use std::collections::VecDeque;
+use std::fs;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+mod accretion;
+mod approach;
+mod batch;
+mod boundary;
+mod camera;
+mod chaos;
+mod circle_shader;
+mod cli;
+mod config;
+mod conics;
+mod console;
+mod detail;
+mod elements;
+mod ephemeris;
+mod events;
+mod exposure;
+mod frame;
+mod ghosts;
+mod grid;
+mod heatmap;
+mod help;
+mod hidpi;
+#[cfg(feature = "net")]
+mod http;
+mod hud;
+mod input;
+mod inset;
+mod kiosk;
+mod lensing;
+mod lighting;
+mod lockstep;
+mod measure;
+mod menu;
+mod meteors;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod minimap;
+mod names;
+mod nebula;
+mod orbits;
+mod palette;
+mod planet_class;
+mod poincare;
+mod population;
+mod precession;
+mod presets;
+mod profiler;
+mod rebound;
+#[cfg(feature = "net")]
+mod remote;
+mod ripple;
+mod scars;
+mod scenario;
+mod screensaver;
+mod slowmo;
+mod sparklines;
+#[cfg(feature = "net")]
+mod spectate;
+mod split;
+mod stars;
+mod state;
+mod stellar;
+mod stream;
+mod svg;
+mod theme;
+mod tooltip;
+mod touch;
+mod tour;
+mod units;
+mod view3d;
+
+use accretion::Disks;
+use approach::Predictor;
+use camera::{Camera, View};
+use chaos::Chaos;
+use circle_shader::{CircleShader, Lighting};
+use cli::Args;
+use config::{Config, PhysicsConfig};
+use conics::Conics;
+use console::{Command, Console};
+use detail::Detail;
+use elements::Elements;
+use events::EventLog;
+use exposure::Exposure;
+use frame::Frame;
+use ghosts::Ghosts;
+use grid::Grid;
+use heatmap::Heatmap;
+#[cfg(feature = "net")]
+use http::HttpServer;
+use input::{Action, InputMap};
+use inset::Inset;
+use kiosk::Kiosk;
+use lensing::Lensing;
+use lockstep::Recorder;
+use measure::{Anchor, Measurement};
+use menu::{Choice, Menu};
+use meteors::Meteors;
+use nebula::Nebula;
+use orbits::Insertion;
+use planet_class::PlanetClass;
+use poincare::Poincare;
+use precession::Precession;
+use profiler::{Profiler, Timings};
+#[cfg(feature = "net")]
+use remote::Remote;
+use ripple::Ripple;
+use scars::Scar;
+use screensaver::Screensaver;
+use slowmo::SlowMotion;
+use sparklines::Sparklines;
+#[cfg(feature = "net")]
+use spectate::{Broadcaster, Spectator};
+use split::Split;
+use stars::Star;
+use state::State;
+use theme::{Theme, TrailStyle};
+use touch::{Gesture, TouchInput};
+use tour::Tour;
+use view3d::View3d;
+
+const VIRTUAL_WIDTH: f32 = 1920.;
+const VIRTUAL_HEIGHT: f32 = 1080.;
+
+const SCALE_FACTOR: f32 = 10e6;
+const G: f32 = 6.674e-11 * SCALE_FACTOR;
+
+/// Default fastest circular orbit for random planets, see
+/// `PhysicsConfig::orbit_speed_limit`.
+const MAX_SPEED: f32 = 2.;
+
+const SUN_MASS: f32 = 1500000.;
+const SUN_COLOR: Color = Color::new(0.976, 0.714, 0.067, 1.);
+
+const MAX_ORBIT_RADIUS: f32 = 400.;
+/// Outermost random moon orbit as a share of the planet's Hill radius.
+const MOON_ORBIT_HILL: f32 = 0.4;
+const ORBIT_ELLIPTICITY: f32 = 0.8;
+
+const CULL_DISTANCE: f32 = 1500.;
+/// Seconds a culled body takes to fade out before it is removed.
+const DEPART_TIME: f32 = 1.;
+
+/// Diameter of the glow sprite relative to the body radius.
+const GLOW_SIZE: f32 = 8.;
+
+/// Impact energy a supernova's flash is drawn for, far past any merge.
+const SUPERNOVA_FLASH: f32 = 1e12;
+
+/// Einstein radius of a black hole relative to its horizon radius.
+const LENS_STRENGTH: f32 = 4.;
+/// Probability that a random system is centered on a black hole.
+const BLACK_HOLE_CHANCE: f64 = 0.15;
+
+/// Speed mapped to the red end of the speed-colored trails.
+const TRAIL_FAST_SPEED: f32 = 3.;
+
+/// Height above or below the plane where depth cueing is at its strongest.
+const DEPTH_RANGE: f32 = 300.;
+/// Most a body is drawn larger above the plane, or smaller below it.
+const DEPTH_SIZE: f32 = 0.3;
+/// Most a body below the plane is darkened.
+const DEPTH_DIM: f32 = 0.5;
+
+/// Drag length in world units for each unit of launch speed when spawning.
+const SPAWN_DRAG_PER_SPEED: f32 = 100.;
+/// Mass of planets spawned by hand.
+const SPAWN_MASS: f32 = 500.;
+
+/// Real seconds an approach of measured bodies may be away before the
+/// time scale is lowered, with `measure.slow_approach`.
+const SLOW_APPROACH_SECONDS: f32 = 3.;
+
+/// Physics steps per sixtieth of a second, cycled with `F`.
+const TIME_SCALES: [u32; 4] = [1, 100, 300, 1000];
+/// Real-time rate of steps at `x1`, what the scales are counted against.
+const STEPS_PER_SECOND: f32 = 60.;
+/// Frames' worth of steps at most run at once, so a stall such as a window
+/// drag doesn't freeze the app catching up.
+const MAX_CATCH_UP: f32 = 4.;
+/// Length of one physics step in simulation time units.
+const DAY: f32 = 1.;
+/// Far slower than real light compared to orbital speeds, so that
+/// Poynting–Robertson drag spirals dust in over minutes instead of ages.
+const LIGHT_SPEED: f32 = 1000.;
+/// Growth per second of the uniform field while an arrow key is held.
+const FIELD_RATE: f32 = 0.002;
+/// Shown in the HUD, matches what `step` does.
+const INTEGRATOR: &str = "semi-implicit Euler, Kepler close pairs";
+/// Scripts a script may run in turn, which stops one that runs itself
+/// from freezing the frame.
+const MAX_SCRIPT_DEPTH: u32 = 8;
+/// Clock display treats one physics step as one simulated day.
+const DAYS_PER_YEAR: u64 = 365;
+
+/// What a step reports, and the callbacks that react to it.
+type SimEvent = planets_core::SimEvent<Planet, stellar::Transition>;
+type Hooks = planets_core::Hooks<Planet, stellar::Transition>;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum BodyKind {
+  #[default]
+  Planet,
+  Star,
+  BlackHole,
+}
+
+#[derive(Debug, Default, Clone)]
+struct Planet {
+  /// Stable identity, unlike the index which shifts as bodies are culled.
+  id: u32,
+  name: String,
+  kind: BodyKind,
+  pos: Vec2,
+  mass: f32,
+  velocity: Vec2,
+  /// Height above the plane and speed across it, zero in flat systems.
+  z: f32,
+  vz: f32,
+  color: Color,
+  /// Whether the body was in another's shadow last frame.
+  eclipsed: bool,
+  /// Seconds left to fade out after crossing the cull distance.
+  departing: Option<f32>,
+  /// Tilt of the ring plane, for planets that have rings.
+  rings: Option<f32>,
+  /// Trail points kept whatever the budget, set from the console.
+  trail_length: Option<usize>,
+  /// How far through its life a star is.
+  evolution: stellar::Evolution,
+  /// Caught by the body it orbits since the system was set up.
+  captured: bool,
+  /// What crashed into it, oldest first.
+  scars: Vec<Scar>,
+
+  trail: VecDeque<TrailPoint>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TrailPoint {
+  pos: Vec2,
+  z: f32,
+  speed: f32,
+  /// Recorded after a capture, so the new orbit can be told apart.
+  captured: bool,
+}
+
+/// Simulation constants that can be changed at runtime.
+#[derive(Debug, Clone)]
+struct Physics {
+  g: f32,
+  /// Applied to new random systems.
+  frame: Frame,
+  orbit_speed_limit: Option<f32>,
+  speed_limit: Option<f32>,
+  /// Trail points shared out between all bodies, recorded with each step.
+  trail_budget: usize,
+  /// Largest tilt of random planets' orbits, in degrees.
+  inclination: f32,
+  /// Days a sun spends on the main sequence, 0 when stars don't age.
+  star_lifetime: f32,
+  radiation: Radiation,
+  /// Background field all bodies move through, on top of each other's pull.
+  potential: Option<Potential>,
+  /// Uniform acceleration on every planet, steered with the arrow keys.
+  /// Stars ignore it like pegs in a tilted table: a field pulling
+  /// on everything alike would carry whole systems off unchanged.
+  field: Vec2,
+}
+
+impl Physics {
+  fn new(config: &PhysicsConfig) -> Self {
+    Physics {
+      g: G,
+      frame: config.frame,
+      orbit_speed_limit: config.orbit_speed_limit,
+      speed_limit: config.speed_limit,
+      trail_budget: config.trail_budget,
+      inclination: config.inclination,
+      star_lifetime: config.star_lifetime,
+      radiation: Radiation {
+        dust_mass: config.dust_mass,
+        light_speed: LIGHT_SPEED,
+      },
+      potential: config.potential,
+      field: config.field,
+    }
+  }
+}
+
+/// Runtime toggles affecting how the scene is drawn.
+#[derive(Debug)]
+struct RenderOptions {
+  trail_style: TrailStyle,
+  trail_by_speed: bool,
+  /// Highlight the rim of planets on the side facing the nearest star.
+  rim_light: bool,
+  /// Shade the night side of planets.
+  terminator: bool,
+  /// Cast shadows away from the nearest star.
+  shadows: bool,
+  /// Replace planet colors with a heatmap of this quantity.
+  heatmap: Option<Heatmap>,
+  grid: Option<Grid>,
+  /// Only applies while a body is selected.
+  other_trails: OtherTrails,
+  /// Every how many trail points a segment is drawn, set by [`Detail`].
+  trail_stride: usize,
+  /// Trail color of captured bodies, so a capture stands out.
+  capture_color: Option<Color>,
+}
+
+impl Default for RenderOptions {
+  fn default() -> Self {
+    RenderOptions {
+      trail_style: TrailStyle::Fading,
+      trail_by_speed: false,
+      rim_light: true,
+      terminator: true,
+      shadows: true,
+      heatmap: None,
+      grid: None,
+      other_trails: OtherTrails::Shown,
+      trail_stride: 2,
+      capture_color: None,
+    }
+  }
+}
+
+/// Per-frame inputs to drawing a single body.
+struct BodyStyle {
+  fill: Color,
+  /// World position of the star lighting the body and how much of its light
+  /// is eclipsed.
+  light: Option<(Vec2, f32)>,
+  trail: TrailEmphasis,
+}
+
+/// How a body's trail stands out against the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TrailEmphasis {
+  Normal,
+  /// Brighter and thicker, for the selected body.
+  Highlighted,
+  Dimmed,
+  Hidden,
+}
+
+/// What happens to the trails of bodies that aren't selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OtherTrails {
+  Shown,
+  Dimmed,
+  Hidden,
+}
+
+impl OtherTrails {
+  fn cycle(self) -> OtherTrails {
+    match self {
+      OtherTrails::Shown => OtherTrails::Dimmed,
+      OtherTrails::Dimmed => OtherTrails::Hidden,
+      OtherTrails::Hidden => OtherTrails::Shown,
+    }
+  }
+
+  fn label(self) -> &'static str {
+    match self {
+      OtherTrails::Shown => "shown",
+      OtherTrails::Dimmed => "dimmed",
+      OtherTrails::Hidden => "hidden",
+    }
+  }
+
+  fn from_label(label: &str) -> Option<OtherTrails> {
+    [OtherTrails::Shown, OtherTrails::Dimmed, OtherTrails::Hidden]
+      .into_iter()
+      .find(|t| t.label() == label)
+  }
+}
+
+/// Textures and shaders created once at startup.
+struct Assets {
+  glow: Texture2D,
+  circle: CircleShader,
+}
+
+impl Assets {
+  fn new() -> Self {
+    Assets {
+      glow: glow_texture(),
+      circle: CircleShader::new(),
+    }
+  }
+}
+
+/// White radial gradient, tinted per body when drawn.
+fn glow_texture() -> Texture2D {
+  const SIZE: u16 = 128;
+  let half = SIZE as f32 / 2.;
+  let mut image = Image::gen_image_color(SIZE, SIZE, BLANK);
+  for y in 0..SIZE {
+    for x in 0..SIZE {
+      let d = vec2(x as f32 + 0.5 - half, y as f32 + 0.5 - half).length() / half;
+      let alpha = (1. - d).max(0.).powi(3);
+      image.set_pixel(x as u32, y as u32, Color::new(1., 1., 1., alpha));
+    }
+  }
+  let texture = Texture2D::from_image(&image);
+  texture.set_filter(FilterMode::Linear);
+  texture
+}
+
+impl planets_core::Body for Planet {
+  fn id(&self) -> u32 {
+    self.id
+  }
+
+  fn mass(&self) -> f32 {
+    self.mass
+  }
+
+  fn pos(&self) -> Vec3 {
+    self.pos.extend(self.z)
+  }
+
+  fn velocity(&self) -> Vec3 {
+    self.velocity.extend(self.vz)
+  }
+
+  fn radius(&self) -> f32 {
+    Planet::radius(self, 1.)
+  }
+
+  /// Departing bodies coast out of the physics, so when they are finally
+  /// removed, which depends on the frame rate, nothing else notices.
+  fn active(&self) -> bool {
+    self.departing.is_none()
+  }
+
+  fn luminous(&self) -> bool {
+    self.kind == BodyKind::Star
+  }
+
+  fn set_mass(&mut self, mass: f32) {
+    self.mass = mass;
+  }
+
+  fn set_pos(&mut self, pos: Vec3) {
+    (self.pos, self.z) = (pos.truncate(), pos.z);
+  }
+
+  fn set_velocity(&mut self, velocity: Vec3) {
+    (self.velocity, self.vz) = (velocity.truncate(), velocity.z);
+  }
+}
+
+impl Planet {
+  fn next_id() -> u32 {
+    static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+  }
+
+  fn radius(&self, scale: f32) -> f32 {
+    match self.kind {
+      // compact enough to only be seen through the lensing around it
+      BodyKind::BlackHole => self.mass.ln() * scale * 0.5,
+      BodyKind::Planet => self.mass.ln() * scale * self.class().size(),
+      BodyKind::Star => self.mass.ln() * scale * self.evolution.size(),
+    }
+  }
+
+  fn class(&self) -> PlanetClass {
+    PlanetClass::of(self.mass)
+  }
+
+  /// 1 normally, falling to 0 while a culled body fades out.
+  fn opacity(&self) -> f32 {
+    self
+      .departing
+      .map_or(1., |t| (t / DEPART_TIME).clamp(0., 1.))
+  }
+
+  fn render(&self, view: &View, options: &RenderOptions, assets: &Assets, style: &BodyStyle) {
+    let scale = view.scale;
+    let opacity = self.opacity();
+
+    let depth = depth_cue(self.z);
+    let radius = self.radius(scale) * depth.size;
+    let Vec2 { x, y } = view.world_to_screen(self.pos);
+
+    let glow = match self.kind {
+      BodyKind::Star => Some(Color {
+        a: 0.8,
+        ..self.color
+      }),
+      BodyKind::Planet => self.class().glow(),
+      BodyKind::BlackHole => None,
+    };
+    if let Some(mut tint) = glow {
+      let size = radius * GLOW_SIZE;
+      tint.a *= opacity;
+      draw_texture_ex(
+        assets.glow,
+        x - size / 2.,
+        y - size / 2.,
+        tint,
+        DrawTextureParams {
+          dest_size: Some(vec2(size, size)),
+          ..Default::default()
+        },
+      );
+    }
+
+    let lighting = style
+      .light
+      .filter(|_| self.kind == BodyKind::Planet)
+      .map(|(star, eclipse)| Lighting {
+        dir: star - self.pos,
+        rim: options.rim_light,
+        terminator: options.terminator,
+        eclipse,
+      });
+    let fill = Color {
+      a: style.fill.a * opacity,
+      ..depth.shade(style.fill)
+    };
+    let ring = Color {
+      a: 0.6 * fill.a,
+      ..fill
+    };
+    if let Some(tilt) = self.rings {
+      planet_class::draw_ring(vec2(x, y), radius, tilt, ring, false);
+    }
+    let bands = match self.kind {
+      BodyKind::Planet => self.class().bands(),
+      _ => 0.,
+    };
+    assets
+      .circle
+      .draw_banded(vec2(x, y), radius, fill, lighting, bands);
+    scars::render(self, vec2(x, y), radius, opacity);
+    if let Some(tilt) = self.rings {
+      planet_class::draw_ring(vec2(x, y), radius, tilt, ring, true);
+    }
+
+    match self.kind {
+      BodyKind::Star => {
+        // white-hot core
+        let core = Color::new(1., 0.97, 0.85, opacity);
+        assets.circle.draw(vec2(x, y), radius * 0.6, core, None);
+      }
+      BodyKind::BlackHole => {
+        // photon ring
+        let ring = Color {
+          a: opacity,
+          ..ORANGE
+        };
+        draw_circle_lines(x, y, radius * 1.1, 1.5 * scale, ring);
+      }
+      BodyKind::Planet => {}
+    }
+
+    let (alpha, width) = match style.trail {
+      TrailEmphasis::Normal => (1., 1.),
+      TrailEmphasis::Highlighted => (1., 2.),
+      TrailEmphasis::Dimmed => (0.2, 1.),
+      TrailEmphasis::Hidden => return,
+    };
+    let segments = Vec::from_iter(
+      self
+        .trail
+        .iter()
+        .step_by(options.trail_stride)
+        .tuple_windows(),
+    );
+    let len = segments.len();
+    for (i, (a, b)) in segments.iter().enumerate() {
+      let mut c = match options.capture_color {
+        _ if options.trail_by_speed => speed_color(a.speed),
+        Some(color) if a.captured => color,
+        _ => self.color,
+      };
+      if style.trail == TrailEmphasis::Highlighted {
+        // towards white, so even dark planets get a bright trail
+        c = Color::new(
+          c.r + (1. - c.r) * 0.4,
+          c.g + (1. - c.g) * 0.4,
+          c.b + (1. - c.b) * 0.4,
+          1.,
+        );
+      }
+      c = depth_cue(a.z).shade(c);
+      let fade = (len - i) as f32 / len as f32;
+      let (a, b) = (view.world_to_screen(a.pos), view.world_to_screen(b.pos));
+      match options.trail_style {
+        TrailStyle::Fading => {
+          c.a = fade * opacity * alpha;
+          draw_line(a.x, a.y, b.x, b.y, 3.0 * fade * width, c);
+        }
+        TrailStyle::Solid => {
+          c.a = 0.8 * opacity * alpha;
+          draw_line(a.x, a.y, b.x, b.y, 2. * width, c);
+        }
+        TrailStyle::Dotted => {
+          if i % 4 == 0 {
+            c.a = fade * opacity * alpha;
+            draw_circle(a.x, a.y, 1.5 * width, c);
+          }
+        }
+      }
+    }
+  }
+
+  /// Adds the current position to the trail, which is kept to `share`
+  /// points unless the body has a length of its own.
+  fn record_trail(&mut self, sun_pos: &Vec2, share: usize) {
+    const MAX_TRAIL_LENGTH: f32 = 1200.0;
+    const MIN_DIST: f32 = 100.;
+    const MAX_DIST: f32 = 1000.;
+    let dist = sun_pos.distance(self.pos).clamp(MIN_DIST, MAX_DIST);
+    let adjusted_len = self
+      .trail_length
+      .unwrap_or(((MAX_TRAIL_LENGTH * dist / MAX_DIST) as usize).min(share));
+
+    self.trail.push_front(TrailPoint {
+      pos: self.pos,
+      z: self.z,
+      speed: self.velocity.length(),
+      captured: self.captured,
+    });
+    self.trail.truncate(adjusted_len);
+  }
+}
+
+/// Expanding ring left where two bodies merged.
+struct Flash {
+  pos: Vec2,
+  size: f32,
+  color: Color,
+  started: f64,
+}
+
+impl Flash {
+  /// Seconds a flash takes to fade.
+  const DURATION: f64 = 0.8;
+
+  fn new(impact: Impact, color: Color) -> Self {
+    Flash {
+      pos: impact.pos.truncate(),
+      size: impact.energy.max(1.).ln() * 2.,
+      color,
+      started: get_time(),
+    }
+  }
+
+  /// Draws the flash, false once it has faded.
+  fn render(&self, view: &View) -> bool {
+    let t = ((get_time() - self.started) / Self::DURATION) as f32;
+    if t >= 1. {
+      return false;
+    }
+    let s = view.world_to_screen(self.pos);
+    let color = Color {
+      a: 1. - t,
+      ..self.color
+    };
+    draw_circle_lines(s.x, s.y, self.size * (1. + 2. * t) * view.scale, 2., color);
+    true
+  }
+}
+
+/// Blue for slow bodies fading to red for fast ones.
+fn speed_color(speed: f32) -> Color {
+  let t = (speed / TRAIL_FAST_SPEED).clamp(0., 1.);
+  Color::new(t, 0.2, 1. - t, 1.)
+}
+
+/// How a height off the plane shows in the top-down view, which looks down
+/// from above: higher bodies are drawn larger, lower ones smaller and darker.
+#[derive(Debug, Clone, Copy)]
+struct DepthCue {
+  size: f32,
+  brightness: f32,
+}
+
+impl DepthCue {
+  fn shade(&self, c: Color) -> Color {
+    Color::new(
+      c.r * self.brightness,
+      c.g * self.brightness,
+      c.b * self.brightness,
+      c.a,
+    )
+  }
+}
+
+fn depth_cue(z: f32) -> DepthCue {
+  let t = (z / DEPTH_RANGE).clamp(-1., 1.);
+  DepthCue {
+    size: 1. + DEPTH_SIZE * t,
+    brightness: 1. + DEPTH_DIM * t.min(0.),
+  }
+}
+
+/// Body under a screen position, with a few pixels of slack for tiny ones.
+fn body_at<'a>(objects: &'a [Planet], view: &View, screen: Vec2) -> Option<&'a Planet> {
+  let world = view.screen_to_world(screen);
+  objects
+    .iter()
+    .filter(|p| {
+      p.pos.distance(world) * view.scale <= p.radius(view.scale) * depth_cue(p.z).size + 6.
+    })
+    .min_by(|a, b| a.pos.distance(world).total_cmp(&b.pos.distance(world)))
+}
+
+/// Ring around the selected body.
+fn draw_selection(view: &View, body: &Planet) {
+  let s = view.world_to_screen(body.pos);
+  let radius = body.radius(view.scale) * depth_cue(body.z).size;
+  draw_circle_lines(s.x, s.y, radius + 6., 1.5, WHITE);
+}
+
+fn window_conf() -> Conf {
+  let state = State::load();
+  Conf {
+    window_title: "Planets".to_owned(),
+    window_width: state.window_width,
+    window_height: state.window_height,
+    fullscreen: state.fullscreen,
+    high_dpi: true,
+    ..Default::default()
+  }
+}
+
+fn main() {
+  let args = match Args::parse(std::env::args().skip(1)) {
+    Ok(args) => args,
+    Err(e) => {
+      eprintln!("{}", e);
+      return;
+    }
+  };
+  if args.verify.is_some() || args.batch.is_some() {
+    headless(&args);
+  } else {
+    macroquad::Window::from_config(window_conf(), run(args));
+  }
+}
+
+/// The palette named in the config, or the theme's.
+fn palette(config: &Config, theme: &Theme) -> Vec<Color> {
+  config
+    .planets
+    .palette
+    .as_ref()
+    .and_then(|name| {
+      let palette = palette::named(name);
+      if palette.is_none() {
+        eprintln!("unknown palette `{}`, using the theme's", name);
+      }
+      palette
+    })
+    .unwrap_or_else(|| theme.palette.clone())
+}
+
+/// `--verify` and `--batch`, which never open a window.
+fn headless(args: &Args) {
+  let config = Config::load();
+  let palette = palette(&config, &Theme::from_config(&config.theme));
+  let physics = Physics::new(&config.physics);
+  let energy_correction = config.physics.energy_correction;
+  let result = match (&args.verify, args.batch) {
+    (Some(path), _) => lockstep::verify(path, &palette, &physics, energy_correction)
+      .map(|summary| format!("{}\n", summary)),
+    (None, Some(systems)) => batch::run(
+      systems,
+      args.steps,
+      args.lockstep.unwrap_or(0),
+      &palette,
+      &physics,
+      energy_correction,
+      args.report.as_deref(),
+    ),
+    (None, None) => Ok(String::new()),
+  };
+  match result {
+    Ok(summary) => print!("{}", summary),
+    Err(e) => {
+      eprintln!("{}", e);
+      std::process::exit(1);
+    }
+  }
+}
+
+async fn run(args: Args) {
+  let mut config = Config::load();
+  if let Some(key) = config.stream.key {
+    // nothing behind the bodies but the key color, so all of it cuts out
+    config.theme.background = Some(key);
+    config.stars.count = 0;
+    config.meteors.per_minute = 0.;
+  }
+  let mut state = State::load();
+  if let Some(name) = &state.theme {
+    config.theme.name = name.clone();
+  }
+  prevent_quit();
+  let mut theme = Theme::from_config(&config.theme);
+  theme.nebula &= config.stream.key.is_none();
+  let input = InputMap::new(&config.keys);
+  let mut palette = palette(&config, &theme);
+  let mut physics = Physics::new(&config.physics);
+  if let Some(budget) = state.trail_budget {
+    physics.trail_budget = budget;
+  }
+  let mut drift = energy::Drift::new(config.physics.energy_correction);
+
+  let mut recorder = match (&args.record, args.lockstep) {
+    (Some(path), Some(seed)) => Recorder::create(path, seed, args.hash_every)
+      .inspect_err(|e| eprintln!("{}: {}", path, e))
+      .ok(),
+    _ => None,
+  };
+
+  // the event log is one more listener, fed through a channel since it is
+  // borrowed all over the loop
+  let mut hooks = Hooks::default();
+  let (notify, notices) = mpsc::channel();
+  let sender = notify.clone();
+  hooks.on_ejection(move |body| {
+    let text = format!("ejected at {:.2} u/day", body.velocity.length());
+    let _ = sender.send((text, body.color));
+  });
+  let sender = notify.clone();
+  hooks.on_merge(move |survivor, absorbed| {
+    let text = format!("{} crashed into {}", absorbed.name, survivor.name);
+    let _ = sender.send((text, absorbed.color));
+  });
+  let sender = notify.clone();
+  hooks.on_stellar(move |star, transition| {
+    let _ = sender.send((transition.describe(&star.name), star.color));
+  });
+  hooks.on_shred(move |hole, body| {
+    let text = format!("{} torn apart by {}", body.name, hole.name);
+    let _ = notify.send((text, body.color));
+  });
+  let (tour_stop, tour_stops) = mpsc::channel();
+  let sender = tour_stop.clone();
+  hooks.on_merge(move |survivor, _| {
+    let _ = sender.send(survivor.id);
+  });
+  let sender = tour_stop.clone();
+  hooks.on_stellar(move |star, _| {
+    let _ = sender.send(star.id);
+  });
+  hooks.on_shred(move |hole, _| {
+    let _ = tour_stop.send(hole.id);
+  });
+  let mut disks = Disks::default();
+  let (feed, feeds) = mpsc::channel();
+  hooks.on_shred(move |hole, body| {
+    let _ = feed.send((hole.id, body.clone()));
+  });
+  #[cfg(feature = "metrics")]
+  let mut metrics = metrics::Metrics::default();
+  let mut flashes = Vec::new();
+  let (flash, new_flashes) = mpsc::channel();
+  let sender = flash.clone();
+  hooks.on_collision(move |_, absorbed, impact| {
+    let _ = sender.send(Flash::new(impact, absorbed.color));
+  });
+  hooks.on_stellar(move |star, transition| {
+    if transition == stellar::Transition::Supernova {
+      let impact = Impact {
+        pos: star.pos.extend(star.z),
+        energy: SUPERNOVA_FLASH,
+      };
+      let _ = flash.send(Flash::new(impact, WHITE));
+    }
+  });
+  let (scar, new_scars) = mpsc::channel();
+  hooks.on_collision(move |survivor, absorbed, impact| {
+    let _ = scar.send((survivor.id, Scar::new(survivor, absorbed, impact)));
+  });
+  let mut ripples = Vec::new();
+  let (ripple, new_ripples) = mpsc::channel();
+  hooks.on_merge(move |survivor, absorbed| {
+    if let Some(r) = Ripple::of(survivor, absorbed) {
+      let _ = ripple.send(r);
+    }
+  });
+  let mut objects = match args.import.as_deref().map(scenario::load_csv) {
+    Some(Ok(mut system)) => {
+      paint_planets(&mut system, &palette);
+      system
+    }
+    Some(Err(e)) => {
+      eprintln!("{}", e);
+      return;
+    }
+    None => match (args.lockstep, state.scenario.as_deref()) {
+      (Some(seed), _) => random_setup(&palette, &mut StdRng::seed_from_u64(seed), &physics),
+      // a screensaver or kiosk shows fresh systems, not the last one loaded
+      (None, Some(path)) if !args.screensaver && !args.kiosk => match scenario::load(path) {
+        Ok((system, potential)) => {
+          physics.potential = potential;
+          system
+        }
+        Err(e) => {
+          eprintln!("{}", e);
+          random_setup(&palette, &mut rand::thread_rng(), &physics)
+        }
+      },
+      _ => random_setup(&palette, &mut rand::thread_rng(), &physics),
+    },
+  };
+  // kept to restart the current system from the menu
+  let mut initial = objects.clone();
+
+  let assets = Assets::new();
+  let mut lensing = Lensing::new();
+  let mut inset = Inset::new();
+  let mut split: Option<Split> = None;
+  // body the main camera follows, the sun when None
+  let mut followed: Option<u32> = None;
+  let mut last_follow = Vec2::ZERO;
+  let mut screensaver = args
+    .screensaver
+    .then(|| Screensaver::start(config.screensaver.minutes));
+  let mut kiosk = args.kiosk.then(|| Kiosk::start(config.kiosk.idle_seconds));
+  let mut tour = screensaver.as_ref().map(|_| Tour::new(config.tour.seconds));
+  let nebula = Nebula::new(rand::thread_rng().gen());
+  let mut meteors = Meteors::new(config.meteors.per_minute);
+
+  let mut stars = (0..config.stars.count)
+    .map(|_| Star::new(&config.stars, theme.star_color))
+    .collect::<Vec<Star>>();
+
+  let mut camera = Camera::default();
+  camera.offset = state.camera_offset;
+  camera.zoom = state.camera_zoom;
+  let mut touch = TouchInput::default();
+  // gestures are handled separately, clicks would otherwise fire twice
+  simulate_mouse_with_touch(false);
+  let mut options = RenderOptions {
+    trail_style: theme.trail,
+    trail_by_speed: state.trail_by_speed,
+    rim_light: state.rim_light,
+    terminator: state.terminator,
+    shadows: state.shadows,
+    heatmap: state.heatmap,
+    grid: state.grid,
+    other_trails: state.other_trails,
+    trail_stride: 2,
+    capture_color: config.planets.capture_color,
+  };
+  let mut events = EventLog::default();
+  let mut ghosts = Ghosts::default();
+  ghosts.visible = state.show_ghosts;
+  let mut exposure: Option<Exposure> = None;
+  let mut show_hud = state.show_hud && !args.screensaver;
+  let mut show_minimap = state.show_minimap && !args.screensaver;
+  let mut show_population = false;
+  let mut sparklines = Sparklines::default();
+  let mut elements = Elements::default();
+  let mut poincare = Poincare::default();
+  let mut chaos = Chaos::default();
+  let mut show_help = false;
+  let mut profiler = Profiler::default();
+  let mut view3d = View3d::default();
+  let mut conics = Conics::default();
+  let mut precession: Option<Precession> = None;
+  let mut detail = Detail::new(config.detail.frame_budget);
+  let mut measurement: Option<Measurement> = None;
+  let mut predictor = Predictor::default();
+  let mut slowmo = SlowMotion::default();
+  let mut selected: Option<u32> = None;
+  let mut time_scale = state.time_scale.min(TIME_SCALES.len() - 1);
+  let mut sim_days: u64 = 0;
+  // steps owed to real time, so the speed of the simulation doesn't depend
+  // on the frame rate
+  let mut pending_steps = 0.;
+  let mut menu: Option<Menu> = None;
+  let mut console = Console::default();
+  let mut insertion = Insertion::Circular;
+  // paused from the console or remotely, as opposed to holding the key
+  let mut held = false;
+  #[cfg(feature = "net")]
+  let remote = match config.remote.osc_port {
+    0 => None,
+    port => Remote::bind(&config.remote.host, port)
+      .inspect_err(|e| eprintln!("OSC port {}: {}", port, e))
+      .ok(),
+  };
+  #[cfg(feature = "net")]
+  let mut http = match config.remote.http_port {
+    0 => None,
+    port => HttpServer::bind(&config.remote.host, port)
+      .inspect_err(|e| eprintln!("HTTP port {}: {}", port, e))
+      .ok(),
+  };
+  #[cfg(feature = "net")]
+  let mut broadcaster = match config.remote.broadcast_port {
+    0 => None,
+    port => Broadcaster::bind(&config.remote.host, port)
+      .inspect_err(|e| eprintln!("broadcast port {}: {}", port, e))
+      .ok(),
+  };
+  #[cfg(feature = "net")]
+  let mut spectator = config.remote.spectate.as_deref().and_then(|host| {
+    Spectator::connect(host)
+      .inspect_err(|e| eprintln!("spectating {}: {}", host, e))
+      .ok()
+  });
+  #[cfg(not(feature = "net"))]
+  if config.remote.osc_port != 0
+    || config.remote.http_port != 0
+    || config.remote.broadcast_port != 0
+    || config.remote.spectate.is_some()
+  {
+    eprintln!("remote.* settings need a build with the `net` feature");
+  }
+
+  loop {
+    let mut timings = Timings::default();
+    clear_background(theme.background);
+
+    // Hotkeys are ignored while the menu is open, apart from the toggles
+    // it triggers itself.
+    let settings = menu::SETTINGS.map(|action| match action {
+      Action::SpeedTrails => options.trail_by_speed,
+      Action::RimLight => options.rim_light,
+      Action::Terminator => options.terminator,
+      Action::Shadows => options.shadows,
+      Action::Hud => show_hud,
+      Action::Minimap => show_minimap,
+      Action::Ghosts => ghosts.visible,
+      _ => false,
+    });
+    let mut actions = Vec::new();
+    let mut replacement = None;
+    let mut quit = false;
+    if let Some(screensaver) = screensaver.as_mut() {
+      quit = screensaver.woken();
+      if screensaver.regenerate() {
+        replacement = Some(random_setup(&palette, &mut rand::thread_rng(), &physics));
+      }
+    }
+    if kiosk.as_mut().is_some_and(Kiosk::idle) {
+      replacement = Some(random_setup(&palette, &mut rand::thread_rng(), &physics));
+      actions.push(Action::ResetCamera);
+    }
+    let typing = console.is_open();
+    // still updated in a kiosk, just never opened, so typing is drained
+    let console_key = match kiosk {
+      Some(_) => KeyCode::Unknown,
+      None => input.key(Action::Console),
+    };
+    // each line with how deep in nested scripts it came from
+    let mut commands = VecDeque::from_iter(console.update(console_key).map(|l| (l, 0)));
+    #[cfg(feature = "net")]
+    for (message, from) in remote.iter().flat_map(Remote::poll) {
+      match message {
+        Ok(m) if m.address == "/planets/state" => {
+          remote.iter().for_each(|r| r.send_state(from, &objects))
+        }
+        Ok(m) => match m.command() {
+          // a kiosk takes no commands at all, wherever they come from
+          Some(line) if kiosk.is_some() => {
+            console.print(format!("{}: refused `{}`, kiosk mode", from, line), RED);
+          }
+          Some(line) if console::local_only(&line) => {
+            let text = format!("{}: refused `{}`, files are local only", from, line);
+            console.print(text, RED);
+          }
+          Some(line) => {
+            console.print(format!("{} > {}", from, line), GRAY);
+            commands.push_back((line, 0));
+          }
+          None => console.print(format!("{}: unknown address {}", from, m.address), RED),
+        },
+        Err(e) => console.print(format!("{}: {}", from, e), RED),
+      }
+    }
+    #[cfg(feature = "net")]
+    if let Some(server) = http.as_mut() {
+      for request in server.poll() {
+        const JSON: &str = "application/json";
+        match (request.method.as_str(), request.path.as_str()) {
+          ("GET", "/state") => {
+            let body = http::state_json(&objects, sim_days);
+            server.respond(request, "200 OK", JSON, &body);
+          }
+          ("GET", "/events") => {
+            let body = http::events_json(events.iter());
+            server.respond(request, "200 OK", JSON, &body);
+          }
+          ("POST", "/control") => {
+            let lines = Vec::from_iter(
+              request
+                .body
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(str::to_owned),
+            );
+            if request.cross_site() {
+              let body = "commands can't be sent from other web pages\n";
+              server.respond(request, "403 Forbidden", "text/plain", body);
+            } else if kiosk.is_some() {
+              let body = "no commands are taken in kiosk mode\n";
+              server.respond(request, "403 Forbidden", "text/plain", body);
+            } else if lines.iter().any(|l| console::local_only(l)) {
+              let body = "files can only be read or written from the console\n";
+              server.respond(request, "403 Forbidden", "text/plain", body);
+            } else {
+              commands.extend(lines.into_iter().map(|l| (l, 0)));
+              server.respond(request, "202 Accepted", "text/plain", "queued\n");
+            }
+          }
+          #[cfg(feature = "metrics")]
+          ("GET", "/metrics") => {
+            let body = metrics.render(&objects, physics.g);
+            server.respond(request, "200 OK", "text/plain; version=0.0.4", &body);
+          }
+          _ => server.respond(request, "404 Not Found", "text/plain", "not found\n"),
+        }
+      }
+    }
+    match menu.as_mut() {
+      None if typing => {}
+      None => {
+        let allowed = |a: Action| kiosk.is_none() || Kiosk::allows(a);
+        actions.extend(
+          Action::ALL
+            .into_iter()
+            .filter(|&a| input.pressed(a) && allowed(a)),
+        );
+        if actions.contains(&Action::Menu) {
+          menu = Some(Menu::new());
+        }
+      }
+      Some(open) => match open.update(&settings) {
+        Some(Choice::Resume) => menu = None,
+        Some(Choice::Restart) => replacement = Some(initial.clone()),
+        Some(Choice::NewSystem) => {
+          replacement = Some(random_setup(&palette, &mut rand::thread_rng(), &physics))
+        }
+        Some(Choice::Save) => {
+          let path = timestamped_path("system", scenario::EXTENSION);
+          match scenario::save(&objects, physics.potential, &path) {
+            Ok(()) => events.post(sim_days, format!("saved {}", path), WHITE),
+            Err(e) => events.post(sim_days, format!("saving failed: {}", e), RED),
+          }
+        }
+        Some(Choice::Load(path)) => match scenario::load(&path) {
+          Ok((system, potential)) => {
+            physics.potential = potential;
+            replacement = Some(system);
+            state.scenario = Some(path);
+          }
+          Err(e) => events.post(sim_days, e, RED),
+        },
+        Some(Choice::Toggle(action)) => actions.push(action),
+        Some(Choice::Quit) => quit = true,
+        None => {}
+      },
+    }
+
+    while let Some((line, depth)) = commands.pop_front() {
+      match Command::parse(&line) {
+        Ok(Command::Spawn {
+          mass,
+          pos,
+          velocity,
+        }) => {
+          let planet = Planet {
+            id: Planet::next_id(),
+            name: next_planet_name(&objects),
+            pos,
+            mass: mass.unwrap_or(SPAWN_MASS),
+            velocity,
+            color: random_color(&palette, &mut rand::thread_rng()),
+            ..Default::default()
+          };
+          console.print(format!("spawned {}", planet.name), planet.color);
+          add_body(&mut objects, planet);
+        }
+        Ok(Command::Set(param, value)) => match param.as_str() {
+          "G" => {
+            physics.g = value;
+            console.print(format!("G = {}", value), WHITE);
+          }
+          "trail_budget" => {
+            physics.trail_budget = value.max(0.) as usize;
+            state.trail_budget = Some(physics.trail_budget);
+            console.print(format!("trail_budget = {}", physics.trail_budget), WHITE);
+          }
+          "star_lifetime" => {
+            physics.star_lifetime = value.max(0.);
+            console.print(format!("star_lifetime = {}", physics.star_lifetime), WHITE);
+          }
+          "speed_limit" => {
+            physics.speed_limit = (value > 0.).then_some(value);
+            console.print(format!("speed_limit = {}", value), WHITE);
+          }
+          _ => console.print(format!("unknown parameter `{}`", param), RED),
+        },
+        Ok(Command::Save(name)) => {
+          let path = scenario::path(&name);
+          match scenario::save(&objects, physics.potential, &path) {
+            Ok(()) => console.print(format!("saved {}", path), WHITE),
+            Err(e) => console.print(format!("saving failed: {}", e), RED),
+          }
+        }
+        Ok(Command::Load(name)) => {
+          let path = scenario::path(&name);
+          match scenario::load(&path) {
+            Ok((system, potential)) => {
+              physics.potential = potential;
+              replacement = Some(system);
+              state.scenario = Some(path);
+            }
+            Err(e) => console.print(e, RED),
+          }
+        }
+        Ok(Command::Import(paths)) => match ephemeris::load(&paths) {
+          Ok(mut system) => {
+            paint_planets(&mut system, &palette);
+            console.print(format!("imported {} bodies", system.len()), WHITE);
+            replacement = Some(system);
+          }
+          Err(e) => console.print(e, RED),
+        },
+        Ok(Command::Rebound(true, path)) => match rebound::save(&objects, physics.g, &path) {
+          Ok(()) => console.print(format!("saved {}", path), WHITE),
+          Err(e) => console.print(format!("saving failed: {}", e), RED),
+        },
+        Ok(Command::Rebound(false, path)) => match rebound::load(&path) {
+          Ok((mut system, g)) => {
+            paint_planets(&mut system, &palette);
+            if let Some(g) = g {
+              physics.g = g;
+              console.print(format!("G = {}", g), WHITE);
+            }
+            replacement = Some(system);
+          }
+          Err(e) => console.print(e, RED),
+        },
+        Ok(Command::Seed(seed)) => {
+          let mut rng = StdRng::seed_from_u64(seed);
+          replacement = Some(random_setup(&palette, &mut rng, &physics));
+        }
+        Ok(Command::Exec(path)) if depth >= MAX_SCRIPT_DEPTH => {
+          let text = format!(
+            "{}: scripts nested more than {} deep, does one run itself?",
+            path, MAX_SCRIPT_DEPTH
+          );
+          console.print(text, RED);
+        }
+        Ok(Command::Exec(path)) => match fs::read_to_string(&path) {
+          // queued in front, so a script runs before whatever follows it
+          Ok(script) => {
+            let lines = script
+              .lines()
+              .map(str::trim)
+              .filter(|l| !l.is_empty() && !l.starts_with('#'));
+            for (i, l) in Vec::from_iter(lines).into_iter().enumerate() {
+              commands.insert(i, (l.to_owned(), depth + 1));
+            }
+          }
+          Err(e) => console.print(format!("{}: {}", path, e), RED),
+        },
+        Ok(Command::Trail(length)) => match objects.iter_mut().find(|p| Some(p.id) == selected) {
+          Some(body) => {
+            body.trail_length = length;
+            let text = match length {
+              Some(n) => format!("{} keeps {} trail points", body.name, n),
+              None => format!("{} shares the trail budget", body.name),
+            };
+            console.print(text, WHITE);
+          }
+          None => console.print("select a body first", RED),
+        },
+        Ok(Command::Recenter) => actions.push(Action::Recenter),
+        Ok(Command::Potential(potential)) => {
+          physics.potential = potential;
+          drift.reset();
+          let text = potential.map_or("no potential".to_owned(), |p| format!("potential {}", p));
+          console.print(text, WHITE);
+        }
+        Ok(Command::Theme(name)) => {
+          config.theme.name = name.clone();
+          theme = Theme::from_config(&config.theme);
+          theme.nebula &= config.stream.key.is_none();
+          options.trail_style = theme.trail;
+          // planets already drawn keep their colors, new systems take the palette
+          palette = self::palette(&config, &theme);
+          stars = (0..config.stars.count)
+            .map(|_| Star::new(&config.stars, theme.star_color))
+            .collect();
+          state.theme = Some(name);
+        }
+        Ok(Command::Pause) => held = !held,
+        Ok(Command::Clear) => console.clear(),
+        Ok(Command::Help) => {
+          for line in console::HELP {
+            console.print(line, LIGHTGRAY);
+          }
+        }
+        Err(e) => console.print(e, RED),
+      }
+    }
+
+    if replacement.is_some() {
+      menu = None;
+    }
+
+    if actions.contains(&Action::Reset) {
+      replacement = Some(random_setup(&palette, &mut rand::thread_rng(), &physics));
+    }
+
+    if let Some(system) = replacement {
+      ghosts.capture(&objects);
+      objects = system;
+      initial = objects.clone();
+      sim_days = 0;
+      drift.reset();
+      #[cfg(feature = "metrics")]
+      metrics.reset_baseline();
+      sparklines.clear();
+      events.clear();
+      conics.clear();
+      inset.clear();
+      selected = None;
+      if let Some(measurement) = measurement.as_mut() {
+        measurement.clear();
+      }
+    }
+
+    if actions.contains(&Action::SpeedTrails) {
+      options.trail_by_speed = !options.trail_by_speed;
+    }
+
+    if actions.contains(&Action::RimLight) {
+      options.rim_light = !options.rim_light;
+    }
+
+    if actions.contains(&Action::Terminator) {
+      options.terminator = !options.terminator;
+    }
+
+    if actions.contains(&Action::Shadows) {
+      options.shadows = !options.shadows;
+    }
+
+    if actions.contains(&Action::Heatmap) {
+      options.heatmap = Heatmap::cycle(options.heatmap);
+    }
+
+    if actions.contains(&Action::Fullscreen) {
+      state.track_window();
+      state.fullscreen = !state.fullscreen;
+      state::set_fullscreen(state.fullscreen);
+    }
+
+    if actions.contains(&Action::Help) {
+      show_help = !show_help;
+    }
+
+    if actions.contains(&Action::Recenter) {
+      frame::zero_momentum(&mut objects);
+      frame::recenter(&mut objects);
+      drift.reset();
+      events.post(sim_days, "recentered on the barycenter", WHITE);
+    }
+
+    // held rather than pressed, so the field keeps growing while a key is down
+    if menu.is_none() && !typing {
+      let push = [
+        (Action::FieldLeft, -Vec2::X),
+        (Action::FieldRight, Vec2::X),
+        (Action::FieldUp, -Vec2::Y),
+        (Action::FieldDown, Vec2::Y),
+      ]
+      .into_iter()
+      .filter(|&(action, _)| input.down(action))
+      .fold(Vec2::ZERO, |sum, (_, dir)| sum + dir);
+      physics.field += push * FIELD_RATE * get_frame_time();
+    }
+    if actions.contains(&Action::FieldReset) {
+      physics.field = Vec2::ZERO;
+    }
+
+    if actions.contains(&Action::Insertion) {
+      insertion = insertion.cycle();
+    }
+
+    if actions.contains(&Action::OtherTrails) {
+      options.other_trails = options.other_trails.cycle();
+    }
+
+    if actions.contains(&Action::Hud) {
+      show_hud = !show_hud;
+    }
+
+    if actions.contains(&Action::Minimap) {
+      show_minimap = !show_minimap;
+    }
+
+    if actions.contains(&Action::Population) {
+      show_population = !show_population;
+    }
+
+    if actions.contains(&Action::Sparklines) {
+      sparklines.visible = !sparklines.visible;
+      sparklines.clear();
+    }
+
+    if actions.contains(&Action::Elements) {
+      elements.visible = !elements.visible;
+    }
+
+    if actions.contains(&Action::Poincare) {
+      poincare.visible = !poincare.visible;
+    }
+
+    if actions.contains(&Action::Profiler) {
+      profiler.visible = !profiler.visible;
+    }
+
+    if actions.contains(&Action::View3d) {
+      view3d.enabled = !view3d.enabled;
+    }
+
+    if actions.contains(&Action::SlowMotion) {
+      slowmo.enabled = !slowmo.enabled;
+    }
+
+    if actions.contains(&Action::Inset) {
+      inset.visible = !inset.visible;
+    }
+
+    if actions.contains(&Action::Tour) {
+      tour = match tour {
+        Some(_) => None,
+        None => Some(Tour::new(config.tour.seconds)),
+      };
+    }
+
+    if actions.contains(&Action::Split) {
+      // the second camera follows the selected body, the sun without one
+      split = match split {
+        Some(_) => None,
+        None => selected.or(objects.last().map(|p| p.id)).map(Split::new),
+      };
+    }
+
+    if actions.contains(&Action::Grid) {
+      options.grid = Grid::cycle(options.grid);
+    }
+
+    if actions.contains(&Action::Measure) {
+      measurement = match measurement {
+        Some(_) => None,
+        None => Some(Measurement::default()),
+      };
+    }
+
+    if actions.contains(&Action::Ghosts) {
+      ghosts.visible = !ghosts.visible;
+    }
+
+    if actions.contains(&Action::Exposure) {
+      exposure = match exposure {
+        Some(_) => None,
+        None => Some(Exposure::new()),
+      };
+    }
+
+    if let Some(exposure) = exposure.as_ref() {
+      if actions.contains(&Action::ClearExposure) {
+        exposure.clear();
+      }
+      if actions.contains(&Action::SaveExposure) {
+        let path = exposure.export();
+        events.post(sim_days, format!("saved {}", path), WHITE);
+      }
+    }
+
+    if actions.contains(&Action::ExportSvg) {
+      let path = timestamped_path("trails", "svg");
+      match svg::export(&objects, &path) {
+        Ok(()) => events.post(sim_days, format!("saved {}", path), WHITE),
+        Err(e) => events.post(sim_days, format!("SVG export failed: {}", e), RED),
+      }
+    }
+
+    if actions.contains(&Action::TimeScale) {
+      time_scale = (time_scale + 1) % TIME_SCALES.len();
+    }
+    if slowmo.update(&objects, &mut time_scale, get_frame_time()) {
+      console.print("slow motion for a close encounter", GRAY);
+    }
+
+    let paused = menu.is_some() || held || input.down(Action::Pause);
+    #[cfg(feature = "net")]
+    let spectating = spectator.is_some();
+    #[cfg(not(feature = "net"))]
+    let spectating = false;
+    #[cfg(feature = "net")]
+    if let Some(watching) = spectator.as_mut() {
+      match watching.poll() {
+        Ok(Some(snapshot)) => {
+          sim_days = snapshot.day;
+          spectate::apply(&mut objects, snapshot, physics.trail_budget);
+        }
+        Ok(None) => {}
+        Err(e) => {
+          events.post(sim_days, format!("stopped spectating: {}", e), RED);
+          spectator = None;
+        }
+      }
+    }
+    if !spectating && !paused {
+      let per_frame = TIME_SCALES[time_scale] as f32;
+      if args.lockstep.is_some() {
+        // whole steps only, so runs line up frame by frame as well
+        pending_steps = per_frame;
+      } else {
+        pending_steps += get_frame_time() * STEPS_PER_SECOND * per_frame;
+        pending_steps = pending_steps.min(per_frame * MAX_CATCH_UP);
+      }
+      while pending_steps >= 1. {
+        pending_steps -= 1.;
+        #[cfg(feature = "metrics")]
+        let started = std::time::Instant::now();
+        let (happened, corrected) = advance(&mut objects, &physics, &mut drift, &mut timings);
+        #[cfg(feature = "metrics")]
+        metrics.record_step(started.elapsed(), &happened);
+        hooks.dispatch(&happened, &objects);
+        sparklines.sample(&objects, physics.g);
+        elements.sample(&objects, &conics, selected, physics.g);
+        poincare.record(&objects, &conics, selected);
+        chaos.step(&objects, selected, physics.g, DAY);
+        if let Some(precession) = precession.as_mut() {
+          precession.record(&objects, physics.g, sim_days);
+        }
+        for (text, color) in notices.try_iter() {
+          events.post(sim_days, text, color);
+        }
+        for (id, body) in feeds.try_iter() {
+          if let Some(hole) = objects.iter().find(|p| p.id == id) {
+            disks.feed(hole, &body);
+          }
+        }
+        disks.step(&objects, physics.g, DAY);
+        for id in tour_stops.try_iter() {
+          if let Some(tour) = tour.as_mut() {
+            tour.note(id);
+          }
+        }
+        for flash in new_flashes.try_iter() {
+          inset.event(flash.pos);
+          flashes.push(flash);
+        }
+        ripples.extend(new_ripples.try_iter());
+        for (id, scar) in new_scars.try_iter() {
+          if let Some(body) = objects.iter_mut().find(|p| p.id == id) {
+            scars::record(body, scar, sim_days);
+          }
+        }
+        sim_days += 1;
+        if args.lockstep.is_some() && sim_days.is_multiple_of(args.hash_every) {
+          let hash = lockstep::state_hash(&objects);
+          println!("day {}: {:016x}", sim_days, hash);
+          if let Some(Err(e)) = recorder.as_mut().map(|r| r.checkpoint(sim_days, hash)) {
+            eprintln!("recording stopped: {}", e);
+            recorder = None;
+          }
+        }
+        if let Some(scale) = corrected {
+          let text = format!(
+            "day {}: energy drift corrected, speeds x{:.5}",
+            sim_days, scale
+          );
+          console.print(text, GRAY);
+        }
+      }
+    }
+    #[cfg(feature = "net")]
+    if let Some(broadcaster) = broadcaster.as_mut() {
+      broadcaster.send(&objects, sim_days);
+    }
+
+    for (id, center, change) in conics.update(&objects, physics.g, sim_days) {
+      let find = |id: u32| objects.iter().find(|p| p.id == id);
+      let (Some(body), Some(center)) = (find(id), find(center)) else {
+        continue;
+      };
+      let text = match change {
+        conics::Change::Captured => format!("{} captured by {}", body.name, center.name),
+        conics::Change::Escaping => format!("{} escaping {}", body.name, center.name),
+      };
+      events.post(sim_days, text, body.color);
+      if let Some(body) = objects.iter_mut().find(|p| p.id == id) {
+        body.captured = change == conics::Change::Captured;
+      }
+      if let Some(tour) = tour.as_mut().filter(|_| change == conics::Change::Captured) {
+        tour.note(id);
+      }
+    }
+
+    let drawing = Instant::now();
+    let dt = get_frame_time();
+    objects.retain_mut(|p| match p.departing.as_mut() {
+      Some(t) => {
+        *t -= dt;
+        *t > 0.
+      }
+      None => true,
+    });
+
+    let sun_pos = objects.last().map_or(Vec2::ZERO, |p| p.pos);
+    let position = |id: u32| objects.iter().find(|p| p.id == id).map(|p| p.pos);
+    // where the camera is following from, the last place seen if it's gone
+    let from = followed
+      .map_or(Some(sun_pos), position)
+      .unwrap_or(last_follow);
+    if actions.contains(&Action::Follow) {
+      followed = selected.filter(|&id| followed != Some(id));
+      let to = followed.and_then(position).unwrap_or(sun_pos);
+      camera.glide_to(from, to, Vec2::ZERO, camera.zoom);
+    }
+    if let Some((id, zoom)) = tour.as_mut().and_then(|tour| tour.update(&objects)) {
+      followed = Some(id);
+      camera.glide_to(from, position(id).unwrap_or(sun_pos), Vec2::ZERO, zoom);
+    }
+    if actions.contains(&Action::ResetCamera) {
+      followed = None;
+      camera.glide_to(from, sun_pos, Vec2::ZERO, 1.);
+    }
+    let follow = match followed.and_then(position) {
+      Some(pos) => pos,
+      // merged into something or ejected, back to the sun
+      None => {
+        if followed.take().is_some() {
+          camera.glide_to(from, sun_pos, camera.offset, camera.zoom);
+        }
+        sun_pos
+      }
+    };
+    last_follow = follow;
+    camera.area = split.as_ref().map(|_| split::left());
+    if let Some(split) = split.as_mut() {
+      split.camera.area = Some(split::right());
+    }
+    let mouse = Vec2::from(mouse_position());
+    // The 3D view takes the mouse over, and touch gestures are flat only.
+    let gesture = if view3d.enabled {
+      view3d.handle_input();
+      None
+    } else {
+      match split.as_mut().filter(|_| split::right().contains(mouse)) {
+        Some(split) => {
+          camera.release();
+          let follow = split.follow_pos(&objects);
+          split.camera.handle_input(follow);
+        }
+        None => {
+          if let Some(split) = split.as_mut() {
+            split.camera.release();
+          }
+          camera.handle_input(follow);
+        }
+      }
+      touch.update(&mut camera, follow)
+    };
+    let view = camera.view(follow);
+    let split_view = split
+      .as_ref()
+      .filter(|_| !view3d.enabled)
+      .map(|split| split.camera.view(split.follow_pos(&objects)));
+    // whichever half the mouse is over, for picking and spawning
+    let pointed = split_view
+      .filter(|_| split::right().contains(mouse))
+      .unwrap_or(view);
+
+    let click = match gesture {
+      Some(Gesture::Tap(pos)) => Some(pos),
+      _ => is_mouse_button_pressed(MouseButton::Left).then(|| Vec2::from(mouse_position())),
+    }
+    .filter(|_| menu.is_none() && !view3d.enabled);
+    if let Some(click) = click {
+      let body = body_at(&objects, &pointed, click).map(|p| p.id);
+      match measurement.as_mut() {
+        Some(measurement) => measurement.click(match body {
+          Some(id) => Anchor::Body(id),
+          None => Anchor::Point(pointed.screen_to_world(click)),
+        }),
+        None => selected = body,
+      }
+    }
+    let pair = measurement.as_ref().and_then(Measurement::bodies);
+    let approach = predictor.update(&objects, pair, physics.g, sim_days);
+    if let Some(approach) = approach.filter(|_| config.measure.slow_approach) {
+      // real seconds until the approach at the current time scale
+      let seconds = |scale: usize| {
+        (approach.day - sim_days) as f32 / (TIME_SCALES[scale] as f32 * STEPS_PER_SECOND)
+      };
+      if time_scale > 0 && seconds(time_scale) < SLOW_APPROACH_SECONDS {
+        time_scale -= 1;
+        let text = format!("slowing to x{} for the approach", TIME_SCALES[time_scale]);
+        console.print(text, GRAY);
+      }
+    }
+
+    // followed around whatever the body orbited when it was selected
+    if precession.as_ref().map(Precession::body) != selected {
+      precession = selected.and_then(|id| {
+        let body = objects.iter().find(|p| p.id == id)?;
+        let center = orbits::dominant_attractor(&objects, body.pos)?;
+        Some(Precession::new(id, center.id))
+      });
+    }
+
+    for (&action, preset) in Action::SPAWN.iter().zip(&presets::PRESETS) {
+      if actions.contains(&action) {
+        let mut body = Planet {
+          id: Planet::next_id(),
+          name: match preset.kind {
+            BodyKind::Planet => next_planet_name(&objects),
+            _ => names::star_name(&mut rand::thread_rng()),
+          },
+          kind: preset.kind,
+          pos: pointed.screen_to_world(mouse),
+          mass: preset.mass,
+          color: preset
+            .color
+            .unwrap_or_else(|| random_color(&palette, &mut rand::thread_rng())),
+          ..Default::default()
+        };
+        insertion.launch(&mut body, &objects, physics.g);
+        events.post(sim_days, format!("spawned {}", body.name), body.color);
+        add_body(&mut objects, body);
+      }
+    }
+
+    if let Some(Gesture::Spawn { at, drag }) = gesture {
+      let planet = Planet {
+        id: Planet::next_id(),
+        name: next_planet_name(&objects),
+        pos: view.screen_to_world(at),
+        mass: SPAWN_MASS,
+        velocity: drag / view.scale / SPAWN_DRAG_PER_SPEED,
+        color: random_color(&palette, &mut rand::thread_rng()),
+        ..Default::default()
+      };
+      add_body(&mut objects, planet);
+    }
+
+    if view3d.enabled {
+      flashes.clear();
+      let sun = objects.last().map_or(Vec3::ZERO, |p| p.pos.extend(p.z));
+      view3d.render(&objects, sun, &options);
+    } else {
+      // Drawn into its own target, so it has to happen outside the lensing pass.
+      if let Some(exposure) = exposure.as_mut() {
+        exposure.accumulate(&objects, &view);
+      }
+
+      // Everything but the holes themselves gets bent by the lens.
+      let black_hole = objects
+        .iter()
+        .filter(|p| p.kind == BodyKind::BlackHole)
+        .max_by(|a, b| a.mass.total_cmp(&b.mass))
+        .map(|hole| (hole.pos, hole.radius(view.scale)));
+      if black_hole.is_some() {
+        lensing.begin(theme.background);
+      }
+
+      if theme.nebula {
+        nebula.render(&view);
+      }
+
+      options.trail_stride = detail.trail_stride();
+      for s in stars.iter().step_by(detail.star_stride()) {
+        s.render(&view);
+      }
+
+      meteors.update(dt);
+      if detail.effects() {
+        meteors.render();
+      }
+      if !detail.effects() {
+        ripples.clear();
+      }
+      ripples.retain(|r: &Ripple| r.render(&view));
+
+      if let Some(grid) = options.grid {
+        grid.render(&view);
+      }
+      boundary::render(&view);
+      ghosts.render(&view);
+      if let Some(exposure) = exposure.as_ref() {
+        exposure.render();
+      }
+
+      // Planets are lit by their nearest star, possibly through an eclipse.
+      let lights = Vec::from_iter(objects.iter().enumerate().map(|(i, obj)| {
+        lighting::nearest_star(&objects, obj.pos)
+          .filter(|_| obj.kind == BodyKind::Planet)
+          .map(|star| (star, lighting::eclipse(&objects, i, star)))
+      }));
+
+      for (obj, light) in objects.iter_mut().zip(&lights) {
+        let eclipsed = light.is_some_and(|(_, e)| e > 0.);
+        if eclipsed && !obj.eclipsed {
+          events.post(sim_days, "eclipse!", obj.color);
+        }
+        obj.eclipsed = eclipsed;
+      }
+
+      if options.shadows {
+        for (obj, light) in objects.iter().zip(&lights) {
+          if let Some((star, _)) = light {
+            lighting::draw_shadow(&view, obj, *star);
+          }
+        }
+      }
+
+      let fills = options.heatmap.map_or_else(
+        || Vec::from_iter(objects.iter().map(|p| p.color)),
+        |mode| mode.colors(&objects),
+      );
+      let trail = |obj: &Planet| match (selected, options.other_trails) {
+        (Some(id), _) if id == obj.id => TrailEmphasis::Highlighted,
+        (None, _) | (_, OtherTrails::Shown) => TrailEmphasis::Normal,
+        (_, OtherTrails::Dimmed) => TrailEmphasis::Dimmed,
+        (_, OtherTrails::Hidden) => TrailEmphasis::Hidden,
+      };
+      // Lowest first, so bodies above the plane pass in front of those below.
+      let mut order = Vec::from_iter(0..objects.len());
+      order.sort_by(|&a, &b| objects[a].z.total_cmp(&objects[b].z));
+      for &i in order.iter() {
+        let (obj, light, fill) = (&objects[i], lights[i], fills[i]);
+        if obj.kind != BodyKind::BlackHole {
+          let style = BodyStyle {
+            fill,
+            light,
+            trail: trail(obj),
+          };
+          obj.render(&view, &options, &assets, &style);
+        }
+      }
+
+      if let Some((pos, radius)) = black_hole {
+        lensing.finish(view.world_to_screen(pos), radius * LENS_STRENGTH);
+      }
+
+      disks.update(&objects, dt);
+      disks.render(&objects, &view);
+
+      for (obj, &fill) in objects.iter().zip(&fills) {
+        if obj.kind == BodyKind::BlackHole {
+          let style = BodyStyle {
+            fill,
+            light: None,
+            trail: trail(obj),
+          };
+          obj.render(&view, &options, &assets, &style);
+        }
+      }
+
+      let chosen = objects.iter().find(|p| Some(p.id) == selected);
+      if let Some(body) = chosen {
+        draw_selection(&view, body);
+      }
+      if !detail.effects() {
+        flashes.clear();
+      }
+      flashes.retain(|f: &Flash| f.render(&view));
+      touch.render();
+
+      boundary::render_warnings(&objects, &view);
+      if let Some(measurement) = measurement.as_ref() {
+        measurement.render(&objects, &view, approach, sim_days);
+      }
+
+      // The split half and the inset show the bodies again from elsewhere,
+      // without the backdrop effects.
+      let draw_again = |other: &View| {
+        disks.render(&objects, other);
+        for &i in order.iter() {
+          let style = BodyStyle {
+            fill: fills[i],
+            light: lights[i],
+            trail: trail(&objects[i]),
+          };
+          objects[i].render(other, &options, &assets, &style);
+        }
+        if let Some(body) = chosen {
+          draw_selection(other, body);
+        }
+        for flash in flashes.iter() {
+          flash.render(other);
+        }
+      };
+      if actions.contains(&Action::Snapshot) {
+        let path = stream::snapshot(|| draw_again(&view));
+        events.post(sim_days, format!("saved {}", path), WHITE);
+      }
+      if let (Some(split), Some(right)) = (split.as_ref(), split_view.as_ref()) {
+        split.begin(theme.background);
+        for s in stars.iter().step_by(detail.star_stride()) {
+          s.render(right);
+        }
+        if let Some(grid) = options.grid {
+          grid.render(right);
+        }
+        draw_again(right);
+        split.finish(&objects);
+      }
+      if let Some(close) = inset.begin(&objects, selected, theme.background) {
+        draw_again(&close);
+        inset.finish();
+      }
+
+      if menu.is_none() && !console.is_open() {
+        if let Some(body) = body_at(&objects, &pointed, mouse) {
+          let orbit = conics.get(body.id).and_then(|orbit| {
+            let center = objects.iter().find(|p| p.id == orbit.center)?;
+            Some((orbit, center))
+          });
+          let chaos = chaos.estimate(body.id);
+          tooltip::render(body, objects.last(), orbit, chaos, mouse);
+        }
+      }
+    }
+
+    slowmo.render();
+    if screensaver.is_none() {
+      draw_clock(sim_days, TIME_SCALES[time_scale]);
+      if let Some(precession) = precession.as_ref() {
+        precession.render(&objects);
+      }
+      if let Some(mode) = options.heatmap {
+        mode.render_legend();
+      }
+      events.render();
+    }
+    if show_minimap {
+      minimap::render(&objects, &view);
+    }
+    if show_hud {
+      presets::render_hotbar(insertion);
+      hud::render(&hud::HudStats {
+        bodies: objects.len(),
+        sim_days,
+        time_scale: TIME_SCALES[time_scale],
+        paused,
+        integrator: INTEGRATOR,
+        field: physics.field,
+      });
+    }
+    if show_population {
+      population::render(&objects, &conics);
+    }
+    sparklines.render();
+    elements.render(&objects);
+    poincare.render(&objects);
+    if show_help {
+      let on_off = |on: bool| if on { "on" } else { "off" }.to_owned();
+      let or_off = |mode: Option<String>| mode.unwrap_or_else(|| "off".to_owned());
+      help::render(
+        &input,
+        &[
+          ("Trails", format!("{:?}", options.trail_style)),
+          ("Speed trails", on_off(options.trail_by_speed)),
+          ("Rim lighting", on_off(options.rim_light)),
+          ("Night side", on_off(options.terminator)),
+          ("Shadows", on_off(options.shadows)),
+          (
+            "Heatmap",
+            or_off(options.heatmap.map(|h| format!("{:?}", h))),
+          ),
+          ("Grid", or_off(options.grid.map(|g| format!("{:?}", g)))),
+          ("Other trails", options.other_trails.label().to_owned()),
+          ("Spawn orbit", insertion.label().to_owned()),
+          ("Minimap", on_off(show_minimap)),
+          ("Ghosts", on_off(ghosts.visible)),
+          ("Measure", on_off(measurement.is_some())),
+          ("Long exposure", on_off(exposure.is_some())),
+          ("Slow motion", on_off(slowmo.enabled)),
+          ("Inset", on_off(inset.visible)),
+          ("Split screen", on_off(split.is_some())),
+          ("Tour", on_off(tour.is_some())),
+          ("Time scale", format!("x{}", TIME_SCALES[time_scale])),
+        ],
+      );
+    }
+
+    if let Some(menu) = menu.as_ref() {
+      menu.render(&settings);
+    }
+    console.render();
+    timings.rendering = drawing.elapsed();
+    if let Some(level) = detail.update(timings.total(), dt) {
+      let text = match level {
+        0 => "full detail restored".to_owned(),
+        n => format!("frame budget exceeded, detail level -{}", n),
+      };
+      console.print(text, GRAY);
+    }
+    profiler.push(timings);
+    profiler.render();
+
+    if quit || is_quit_requested() {
+      // a screensaver or kiosk leaves the settings as they were
+      if screensaver.is_some() || kiosk.is_some() {
+        break;
+      }
+      state.track_window();
+      state = State {
+        time_scale,
+        camera_offset: camera.offset,
+        camera_zoom: camera.zoom,
+        trail_by_speed: options.trail_by_speed,
+        rim_light: options.rim_light,
+        terminator: options.terminator,
+        shadows: options.shadows,
+        heatmap: options.heatmap,
+        grid: options.grid,
+        other_trails: options.other_trails,
+        show_hud,
+        show_minimap,
+        show_ghosts: ghosts.visible,
+        ..state
+      };
+      if let Err(e) = state.save() {
+        eprintln!("couldn't save {}: {}", state::STATE_PATH, e);
+      }
+      break;
+    }
+
+    next_frame().await
+  }
+}
+
+/// First planet name after the sun's that no body has taken yet.
+fn next_planet_name(objects: &[Planet]) -> String {
+  let star = objects.last().map_or("", |p| p.name.as_str());
+  (0..)
+    .map(|i| names::planet_name(star, i))
+    .find(|name| objects.iter().all(|p| &p.name != name))
+    .unwrap()
+}
+
+/// Adds a body without displacing the sun from the end of the list.
+fn add_body(objects: &mut Vec<Planet>, body: Planet) {
+  objects.insert(objects.len().saturating_sub(1), body);
+}
+
+/// Advances the simulation by a single physics step.
+///
+/// Returns what happened during the step, in order. Bodies that crossed the
+/// cull distance are marked as departing and removed once faded out.
+fn step(
+  objects: &mut Vec<Planet>,
+  physics: &Physics,
+  dt: f32,
+  timings: &mut Timings,
+) -> Vec<SimEvent> {
+  let mut happened = Vec::new();
+  for p in objects.iter_mut() {
+    if p.departing.is_none() && p.pos.extend(p.z).length() > CULL_DISTANCE {
+      p.departing = Some(DEPART_TIME);
+      happened.push(SimEvent::Ejection(p.id));
+    }
+  }
+
+  if physics.star_lifetime > 0. {
+    for star in objects.iter_mut() {
+      if star.kind == BodyKind::Star && star.departing.is_none() {
+        if let Some(transition) = stellar::evolve(star, physics.star_lifetime, dt) {
+          happened.push(SimEvent::Stellar(star.id, transition));
+        }
+      }
+    }
+  }
+
+  let started = Instant::now();
+  let sun_pos = objects.last().map_or(Vec2::ZERO, |p| p.pos);
+  let share = trail_share(objects, physics.trail_budget);
+  for obj in objects.iter_mut() {
+    obj.record_trail(&sun_pos, share);
+  }
+  timings.trails += started.elapsed();
+
+  let started = Instant::now();
+  let pairs = integrate::close_pairs(objects, physics.g, dt);
+  integrate::kick(objects, &pairs, physics.g, dt, physics.speed_limit);
+  if physics.radiation.dust_mass > 0. {
+    radiation::kick(objects, physics.g, dt, &physics.radiation);
+  }
+  if let Some(potential) = physics.potential.as_ref() {
+    potential::kick(objects, potential, physics.g, dt);
+  }
+  if physics.field != Vec2::ZERO {
+    for p in objects.iter_mut() {
+      if p.kind == BodyKind::Planet && p.departing.is_none() {
+        p.velocity += physics.field * dt;
+      }
+    }
+  }
+  let integrating = Instant::now();
+  timings.forces += integrating - started;
+  integrate::drift(objects, &pairs, physics.g, dt);
+  let colliding = Instant::now();
+  timings.integration += colliding - integrating;
+  let merges = collision::merge_collisions(objects);
+  happened.extend(merges.into_iter().map(SimEvent::Merge));
+  let shredded = accretion::shred(objects);
+  happened.extend(shredded.into_iter().map(SimEvent::Shredded));
+  timings.collisions += colliding.elapsed();
+
+  happened
+}
+
+/// One step as every run takes it, live, replayed or batched: the physics,
+/// then the energy correction where nothing is meant to change the energy.
+/// Returns what happened and the velocity scale if it corrected.
+fn advance(
+  objects: &mut Vec<Planet>,
+  physics: &Physics,
+  drift: &mut energy::Drift,
+  timings: &mut Timings,
+) -> (Vec<SimEvent>, Option<f32>) {
+  let happened = step(objects, physics, DAY, timings);
+  // winds carry energy away and the potential's share isn't counted,
+  // neither of which the correction should fight
+  let losing_mass = physics.star_lifetime > 0.
+    && objects
+      .iter()
+      .any(|p| p.kind == BodyKind::Star && p.evolution.losing_mass());
+  if losing_mass || physics.potential.is_some() || physics.field != Vec2::ZERO {
+    drift.reset();
+  }
+  let corrected = drift.update(objects, physics.g);
+  (happened, corrected)
+}
+
+/// Trail points each body may keep, the budget less what bodies with their
+/// own length take, split evenly between the rest.
+fn trail_share(objects: &[Planet], budget: usize) -> usize {
+  // short trails still show the direction of travel
+  const MIN_TRAIL_LENGTH: usize = 20;
+  let fixed: usize = objects.iter().filter_map(|p| p.trail_length).sum();
+  let shared = objects.iter().filter(|p| p.trail_length.is_none()).count();
+  (budget.saturating_sub(fixed) / shared.max(1)).max(MIN_TRAIL_LENGTH)
+}
+
+/// File name in the working directory that won't clash with earlier exports.
+fn timestamped_path(prefix: &str, extension: &str) -> String {
+  let stamp = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_or(0, |d| d.as_secs());
+  format!("{}-{}.{}", prefix, stamp, extension)
+}
+
+fn format_day(sim_days: u64) -> String {
+  format!(
+    "Year {} day {}",
+    sim_days / DAYS_PER_YEAR,
+    sim_days % DAYS_PER_YEAR
+  )
+}
+
+fn draw_clock(sim_days: u64, time_scale: u32) {
+  let text = format!("{}  x{}", format_day(sim_days), time_scale);
+  hidpi::draw_text_sharp(&text, 10., screen_height() - 10., 24., GRAY);
+}
+
+/// Random colors for imported planets that came without any.
+fn paint_planets(objects: &mut [Planet], palette: &[Color]) {
+  let uncolored = |p: &&mut Planet| p.kind == BodyKind::Planet && p.color == Color::default();
+  for p in objects.iter_mut().filter(uncolored) {
+    p.color = random_color(palette, &mut rand::thread_rng());
+  }
+}
+
+/// Color from `palette`, or fully random when it is empty.
+fn random_color(palette: &[Color], rng: &mut impl Rng) -> Color {
+  match palette.choose(rng) {
+    Some(&c) => c,
+    None => Color::from_rgba(
+      rng.gen_range(20..=255),
+      rng.gen_range(20..=255),
+      rng.gen_range(20..=255),
+      255,
+    ),
+  }
+}
+
+/// New random system, planets colored from `palette` or fully at random
+/// when it is empty.
+fn random_setup(palette: &[Color], rng: &mut impl Rng, physics: &Physics) -> Vec<Planet> {
+  let amount = rng.gen_range(4..=12);
+
+  let name = names::star_name(rng);
+  let sun = if rng.gen_bool(BLACK_HOLE_CHANCE) {
+    Planet {
+      id: Planet::next_id(),
+      name: format!("{} X-1", name),
+      kind: BodyKind::BlackHole,
+      mass: SUN_MASS,
+      color: BLACK,
+      ..Default::default()
+    }
+  } else {
+    Planet {
+      id: Planet::next_id(),
+      name,
+      kind: BodyKind::Star,
+      mass: SUN_MASS,
+      color: SUN_COLOR,
+      ..Default::default()
+    }
+  };
+
+  let mut planets = Vec::from_iter((0..amount).map(|i| {
+    let mut planet = Planet {
+      id: Planet::next_id(),
+      name: names::planet_name(&sun.name, i),
+      pos: Vec2 {
+        x: rng.gen_range(-MAX_ORBIT_RADIUS..MAX_ORBIT_RADIUS),
+        y: rng.gen_range(-MAX_ORBIT_RADIUS..MAX_ORBIT_RADIUS),
+      },
+      mass: rng.gen_range(50.0..=5000.0),
+      color: random_color(palette, rng),
+      ..Default::default()
+    };
+    if let Some(limit) = physics.orbit_speed_limit {
+      let closest = orbits::circular_radius(sun.mass + planet.mass, physics.g, limit);
+      if planet.pos.length() < closest {
+        planet.pos = planet.pos.try_normalize().unwrap_or(Vec2::X) * closest;
+      }
+    }
+    let ring_chance = planet.class().ring_chance();
+    planet.rings = rng.gen_bool(ring_chance).then(|| rng.gen_range(-0.5..=0.5));
+    planet.velocity = orbits::orbit_velocity(&planet, &sun, physics.g).truncate();
+    planet.velocity.x += rng.gen_range(-ORBIT_ELLIPTICITY..=ORBIT_ELLIPTICITY);
+    if physics.inclination > 0. {
+      // turns the orbit plane about the line to the sun
+      let tilt = rng
+        .gen_range(-physics.inclination..=physics.inclination)
+        .to_radians();
+      planet.vz = planet.velocity.length() * tilt.sin();
+      planet.velocity *= tilt.cos();
+    }
+    planet
+  }));
+  planets.push(sun);
+
+  // Moons go well inside their planet's Hill sphere, but clear of the
+  // planet itself, so there has to be room for both.
+  for i in 0..amount {
+    let planet = &planets[i];
+    let hill = orbits::hill_radius(&planets, planet);
+    let (inner, outer) = (planet.radius(1.) * 1.5, hill * MOON_ORBIT_HILL);
+    if inner >= outer || !rng.gen_bool(planet.class().moon_chance()) {
+      continue;
+    }
+    let angle = rng.gen_range(0. ..std::f32::consts::TAU);
+    let mut moon = Planet {
+      id: Planet::next_id(),
+      name: format!("{} I", planet.name),
+      pos: planet.pos + Vec2::from_angle(angle) * rng.gen_range(inner..outer),
+      z: planet.z,
+      mass: rng.gen_range(3.0..=30.0),
+      color: random_color(palette, rng),
+      ..Default::default()
+    };
+    // not so close it would be torn apart straight away
+    let distance = moon.pos.distance(planet.pos);
+    let roche = accretion::roche_radius(planet, &moon);
+    if distance < roche {
+      moon.pos = planet.pos + Vec2::from_angle(angle) * roche * 1.1;
+    }
+    let velocity = orbits::local_orbit_velocity(&planets, &moon, physics.g);
+    (moon.velocity, moon.vz) = (velocity.truncate(), velocity.z);
+    add_body(&mut planets, moon);
+  }
+  physics.frame.apply(&mut planets);
+  planets
+}
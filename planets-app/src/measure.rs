@@ -0,0 +1,91 @@
+use macroquad::prelude::*;
+
+use crate::approach::Approach;
+use crate::camera::View;
+use crate::{hidpi, units, Planet};
+
+/// End of a measurement: a fixed world point or a body that keeps moving.
+#[derive(Debug, Clone, Copy)]
+pub enum Anchor {
+  Point(Vec2),
+  Body(u32),
+}
+
+/// Click-to-measure tool, two anchors at a time.
+#[derive(Debug, Default)]
+pub struct Measurement {
+  anchors: Vec<Anchor>,
+}
+
+impl Measurement {
+  /// Adds an anchor, starting over once a measurement is complete.
+  pub fn click(&mut self, anchor: Anchor) {
+    if self.anchors.len() == 2 {
+      self.anchors.clear();
+    }
+    self.anchors.push(anchor);
+  }
+
+  pub fn clear(&mut self) {
+    self.anchors.clear();
+  }
+
+  /// Both ends, when the measurement is between two bodies.
+  pub fn bodies(&self) -> Option<(u32, u32)> {
+    match self.anchors[..] {
+      [Anchor::Body(a), Anchor::Body(b)] => Some((a, b)),
+      _ => None,
+    }
+  }
+
+  /// Draws the anchors and what lies between them, with the predicted
+  /// `approach` of two bodies as of `day`.
+  pub fn render(&self, objects: &[Planet], view: &View, approach: Option<Approach>, day: u64) {
+    let resolve = |anchor: &Anchor| match *anchor {
+      Anchor::Point(p) => Some((p, None)),
+      Anchor::Body(id) => objects
+        .iter()
+        .find(|p| p.id == id)
+        .map(|p| (p.pos, Some(p.velocity))),
+    };
+    let ends = Vec::from_iter(self.anchors.iter().filter_map(resolve));
+    let color = Color::new(0.9, 0.9, 0.3, 0.9);
+
+    for (p, _) in ends.iter() {
+      let s = view.world_to_screen(*p);
+      draw_circle_lines(s.x, s.y, 6., 1.5, color);
+    }
+
+    let [(a, va), (b, vb)] = ends[..] else {
+      return;
+    };
+    let (sa, sb) = (view.world_to_screen(a), view.world_to_screen(b));
+    draw_line(sa.x, sa.y, sb.x, sb.y, 1.5, color);
+
+    let dist = a.distance(b);
+    let mut lines = vec![format!(
+      "{} ({})",
+      units::format_distance(dist, false),
+      units::format_distance(dist, true)
+    )];
+
+    if let (Some(va), Some(vb)) = (va, vb) {
+      let v = vb - va;
+      lines.push(format!("relative speed {:.3} u/day", v.length()));
+    }
+    if let Some(approach) = approach {
+      lines.push(format!(
+        "closest approach {} in {} days",
+        units::format_distance(approach.distance, false),
+        approach.day - day
+      ));
+    } else if self.bodies().is_some() {
+      lines.push("no close approach ahead".to_owned());
+    }
+
+    let mid = (sa + sb) / 2.;
+    for (i, line) in lines.iter().enumerate() {
+      hidpi::draw_text_sharp(line, mid.x + 10., mid.y + i as f32 * 20., 20., color);
+    }
+  }
+}
@@ -0,0 +1,191 @@
+use macroquad::prelude::*;
+
+use crate::hidpi;
+use crate::input::Action;
+use crate::scenario;
+
+/// What picking a menu entry asks the app to do.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Choice {
+  Resume,
+  /// Back to how the current system started.
+  Restart,
+  NewSystem,
+  Save,
+  Load(String),
+  /// Same as pressing the key bound to the action.
+  Toggle(Action),
+  Quit,
+}
+
+#[derive(Debug)]
+enum Page {
+  Main,
+  Load(Vec<String>),
+  Settings,
+}
+
+/// Settings page entries, shown with their current state.
+pub const SETTINGS: [Action; 7] = [
+  Action::SpeedTrails,
+  Action::RimLight,
+  Action::Terminator,
+  Action::Shadows,
+  Action::Hud,
+  Action::Minimap,
+  Action::Ghosts,
+];
+
+const SIZE: f32 = 28.;
+const WIDTH: f32 = 420.;
+
+/// Pause menu, keyboard and mouse driven. The simulation stays paused while
+/// it is open.
+#[derive(Debug)]
+pub struct Menu {
+  page: Page,
+  cursor: usize,
+}
+
+impl Menu {
+  pub fn new() -> Self {
+    Menu {
+      page: Page::Main,
+      cursor: 0,
+    }
+  }
+
+  /// Handles navigation; `Escape` goes back a page, or resumes from the
+  /// first one.
+  pub fn update(&mut self, settings: &[bool]) -> Option<Choice> {
+    let entries = self.entries(settings);
+
+    if is_key_pressed(KeyCode::Escape) {
+      return match self.page {
+        Page::Main => Some(Choice::Resume),
+        _ => {
+          self.open(Page::Main);
+          None
+        }
+      };
+    }
+    if is_key_pressed(KeyCode::Down) {
+      self.cursor = (self.cursor + 1) % entries.len();
+    }
+    if is_key_pressed(KeyCode::Up) {
+      self.cursor = (self.cursor + entries.len() - 1) % entries.len();
+    }
+
+    let (x, y) = mouse_position();
+    let rect = self.rect(entries.len());
+    let hovered = (rect.contains(vec2(x, y)) && y >= rect.y + SIZE)
+      .then(|| ((y - rect.y) / SIZE) as usize - 1)
+      .filter(|&i| i < entries.len());
+    if let Some(i) = hovered {
+      self.cursor = i;
+    }
+
+    let picked = is_key_pressed(KeyCode::Enter)
+      || (hovered.is_some() && is_mouse_button_pressed(MouseButton::Left));
+    if !picked {
+      return None;
+    }
+    match (&self.page, self.cursor) {
+      (Page::Main, 0) => Some(Choice::Resume),
+      (Page::Main, 1) => Some(Choice::Restart),
+      (Page::Main, 2) => Some(Choice::NewSystem),
+      (Page::Main, 3) => Some(Choice::Save),
+      (Page::Main, 4) => {
+        self.open(Page::Load(scenario::list()));
+        None
+      }
+      (Page::Main, 5) => {
+        self.open(Page::Settings);
+        None
+      }
+      (Page::Main, _) => Some(Choice::Quit),
+      (Page::Load(files), i) => match files.get(i) {
+        Some(file) => Some(Choice::Load(file.clone())),
+        None => {
+          self.open(Page::Main);
+          None
+        }
+      },
+      (Page::Settings, i) => match SETTINGS.get(i) {
+        Some(&action) => Some(Choice::Toggle(action)),
+        None => {
+          self.open(Page::Main);
+          None
+        }
+      },
+    }
+  }
+
+  pub fn render(&self, settings: &[bool]) {
+    let entries = self.entries(settings);
+    let rect = self.rect(entries.len());
+    draw_rectangle(
+      0.,
+      0.,
+      screen_width(),
+      screen_height(),
+      Color::new(0., 0., 0., 0.4),
+    );
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(0., 0., 0., 0.85));
+
+    let title = match self.page {
+      Page::Main => "Paused",
+      Page::Load(_) => "Load system",
+      Page::Settings => "Settings",
+    };
+    hidpi::draw_text_sharp(title, rect.x + 20., rect.y + SIZE * 0.8, SIZE, WHITE);
+    for (i, entry) in entries.iter().enumerate() {
+      let y = rect.y + SIZE * (i as f32 + 1.8);
+      let color = if i == self.cursor { YELLOW } else { LIGHTGRAY };
+      hidpi::draw_text_sharp(entry, rect.x + 40., y, SIZE * 0.8, color);
+    }
+  }
+
+  fn open(&mut self, page: Page) {
+    self.page = page;
+    self.cursor = 0;
+  }
+
+  fn entries(&self, settings: &[bool]) -> Vec<String> {
+    let mut entries = match &self.page {
+      Page::Main => {
+        return [
+          "Resume",
+          "Restart system",
+          "New system",
+          "Save system",
+          "Load system",
+          "Settings",
+          "Quit",
+        ]
+        .map(str::to_owned)
+        .to_vec()
+      }
+      Page::Load(files) if files.is_empty() => vec!["no saved systems".to_owned()],
+      Page::Load(files) => files.clone(),
+      Page::Settings => Vec::from_iter(
+        SETTINGS
+          .iter()
+          .zip(settings)
+          .map(|(a, &on)| format!("{}: {}", a.description(), if on { "on" } else { "off" })),
+      ),
+    };
+    entries.push("Back".to_owned());
+    entries
+  }
+
+  fn rect(&self, entries: usize) -> Rect {
+    let height = SIZE * (entries as f32 + 1.5);
+    Rect::new(
+      (screen_width() - WIDTH) / 2.,
+      (screen_height() - height) / 2.,
+      WIDTH,
+      height,
+    )
+  }
+}
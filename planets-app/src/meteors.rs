@@ -0,0 +1,71 @@
+use ::rand::{self, Rng};
+use macroquad::prelude::*;
+
+use crate::camera::base_scale;
+
+/// Streak speed range at zoom 1, in pixels per second.
+const SPEED: std::ops::Range<f32> = 900.0..1600.0;
+const LIFETIME: std::ops::Range<f32> = 0.4..0.9;
+/// Tail length as a fraction of the distance covered per second.
+const TAIL: f32 = 0.12;
+
+struct Meteor {
+  pos: Vec2,
+  velocity: Vec2,
+  age: f32,
+  lifetime: f32,
+}
+
+/// Purely cosmetic streaks across the background, in screen space.
+pub struct Meteors {
+  /// Average streaks per minute, zero disables them.
+  per_minute: f32,
+  active: Vec<Meteor>,
+}
+
+impl Meteors {
+  pub fn new(per_minute: f32) -> Self {
+    Meteors {
+      per_minute,
+      active: Vec::new(),
+    }
+  }
+
+  pub fn update(&mut self, dt: f32) {
+    let mut rng = rand::thread_rng();
+
+    if rng.gen_bool((self.per_minute / 60. * dt).clamp(0., 1.) as f64) {
+      let (w, h) = (screen_width(), screen_height());
+      let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+      self.active.push(Meteor {
+        pos: vec2(rng.gen_range(0.0..w), rng.gen_range(0.0..h)),
+        velocity: Vec2::from_angle(angle) * rng.gen_range(SPEED) * base_scale(),
+        age: 0.,
+        lifetime: rng.gen_range(LIFETIME),
+      });
+    }
+
+    for m in self.active.iter_mut() {
+      m.pos += m.velocity * dt;
+      m.age += dt;
+    }
+    self.active.retain(|m| m.age < m.lifetime);
+  }
+
+  pub fn render(&self) {
+    for m in self.active.iter() {
+      // quick fade in, slow fade out
+      let t = m.age / m.lifetime;
+      let alpha = (t * 8.).min(1.) * (1. - t);
+      let tail = m.pos - m.velocity * TAIL;
+      draw_line(
+        m.pos.x,
+        m.pos.y,
+        tail.x,
+        tail.y,
+        1.5 * base_scale(),
+        Color::new(1., 1., 0.9, alpha),
+      );
+    }
+  }
+}
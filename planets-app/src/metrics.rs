@@ -0,0 +1,110 @@
+//! Counters and gauges for monitoring long runs, served in the Prometheus
+//! text format on the HTTP server's `/metrics`.
+
+use planets_core::energy;
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use crate::Planet;
+use crate::SimEvent;
+
+#[derive(Debug, Default)]
+pub struct Metrics {
+  steps: u64,
+  step_seconds: f64,
+  last_step_seconds: f64,
+  collisions: u64,
+  ejections: u64,
+  /// Energy and body count drift is measured against; a change in the
+  /// count starts over.
+  baseline: Option<(f64, usize)>,
+}
+
+impl Metrics {
+  pub fn record_step(&mut self, elapsed: Duration, happened: &[SimEvent]) {
+    self.steps += 1;
+    self.last_step_seconds = elapsed.as_secs_f64();
+    self.step_seconds += self.last_step_seconds;
+    for event in happened {
+      match event {
+        SimEvent::Merge(_) => self.collisions += 1,
+        SimEvent::Ejection(_) => self.ejections += 1,
+        SimEvent::Stellar(..) | SimEvent::Shredded(_) => {}
+      }
+    }
+  }
+
+  /// For a new system, whose energy has nothing to do with the old one's.
+  pub fn reset_baseline(&mut self) {
+    self.baseline = None;
+  }
+
+  pub fn render(&mut self, objects: &[Planet], g: f32) -> String {
+    let energy = energy::total(objects, g);
+    let baseline = match self.baseline {
+      Some((e, len)) if len == objects.len() => e,
+      _ => self.baseline.insert((energy, objects.len())).0,
+    };
+    let drift = if baseline == 0. {
+      0.
+    } else {
+      (energy - baseline) / baseline.abs()
+    };
+
+    let mut text = String::new();
+    let mut metric = |name: &str, kind: &str, help: &str, value: f64| {
+      let _ = writeln!(text, "# HELP planets_{} {}", name, help);
+      let _ = writeln!(text, "# TYPE planets_{} {}", name, kind);
+      let _ = writeln!(text, "planets_{} {}", name, value);
+    };
+    metric(
+      "steps_total",
+      "counter",
+      "Physics steps run.",
+      self.steps as f64,
+    );
+    metric(
+      "step_seconds_total",
+      "counter",
+      "Time spent in physics steps.",
+      self.step_seconds,
+    );
+    metric(
+      "last_step_seconds",
+      "gauge",
+      "Duration of the latest physics step.",
+      self.last_step_seconds,
+    );
+    metric(
+      "bodies",
+      "gauge",
+      "Bodies in the system.",
+      objects.len() as f64,
+    );
+    metric(
+      "energy",
+      "gauge",
+      "Total kinetic and potential energy.",
+      energy,
+    );
+    metric(
+      "energy_drift_ratio",
+      "gauge",
+      "Relative change in total energy since the body count last changed.",
+      drift,
+    );
+    metric(
+      "collisions_total",
+      "counter",
+      "Bodies merged by collisions.",
+      self.collisions as f64,
+    );
+    metric(
+      "ejections_total",
+      "counter",
+      "Bodies lost past the cull distance.",
+      self.ejections as f64,
+    );
+    text
+  }
+}
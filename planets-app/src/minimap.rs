@@ -0,0 +1,40 @@
+use macroquad::prelude::*;
+
+use crate::camera::{base_scale, View};
+use crate::{Planet, CULL_DISTANCE};
+
+/// Side of the minimap at zoom 1, in pixels.
+const SIZE: f32 = 220.;
+const MARGIN: f32 = 10.;
+
+/// Whole-system overview in the bottom right corner.
+///
+/// The map covers everything up to the cull distance around the origin at a
+/// fixed scale, so bodies far outside the main view are still visible.
+pub fn render(objects: &[Planet], view: &View) {
+  let size = SIZE * base_scale();
+  let corner = vec2(
+    screen_width() - size - MARGIN,
+    screen_height() - size - MARGIN,
+  );
+  let scale = size / (CULL_DISTANCE * 2.);
+  let to_map = |p: Vec2| corner + size / 2. + p * scale;
+
+  draw_rectangle(corner.x, corner.y, size, size, Color::new(0., 0., 0., 0.6));
+  draw_rectangle_lines(corner.x, corner.y, size, size, 1., DARKGRAY);
+
+  for p in objects.iter() {
+    let m = to_map(p.pos);
+    draw_circle(m.x, m.y, (p.radius(scale) * 4.).max(1.5), p.color);
+  }
+
+  // current viewport, clipped to the map
+  let clip = |p: Vec2| p.clamp(corner, corner + size);
+  let a = clip(to_map(view.screen_to_world(Vec2::ZERO)));
+  let b = clip(to_map(
+    view.screen_to_world(vec2(screen_width(), screen_height())),
+  ));
+  if b.x > a.x && b.y > a.y {
+    draw_rectangle_lines(a.x, a.y, b.x - a.x, b.y - a.y, 1., WHITE);
+  }
+}
@@ -0,0 +1,33 @@
+use ::rand::{seq::SliceRandom, Rng};
+
+/// Survey catalogs stars are named after, like real exoplanet hosts.
+const CATALOGS: [&str; 8] = [
+  "Gliese",
+  "HD",
+  "Kepler-",
+  "TOI-",
+  "WASP-",
+  "HAT-P-",
+  "TRAPPIST-",
+  "Ross",
+];
+
+pub fn star_name(rng: &mut impl Rng) -> String {
+  let catalog = CATALOGS.choose(rng).unwrap();
+  let number = rng.gen_range(1..2000);
+  if catalog.ends_with('-') {
+    format!("{}{}", catalog, number)
+  } else {
+    format!("{} {}", catalog, number)
+  }
+}
+
+/// The star's name followed by a letter counting up from `b`, the
+/// convention for exoplanets.
+pub fn planet_name(star: &str, index: usize) -> String {
+  let letters = b'z' - b'b' + 1;
+  match u8::try_from(index) {
+    Ok(i) if i < letters => format!("{} {}", star, (b'b' + i) as char),
+    _ => format!("{} {}", star, index + 1),
+  }
+}
@@ -0,0 +1,137 @@
+use ::rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use macroquad::prelude::*;
+
+use crate::camera::View;
+
+const TEXTURE_WIDTH: u16 = 480;
+const TEXTURE_HEIGHT: u16 = 270;
+const OCTAVES: u32 = 5;
+/// Noise features across the texture width.
+const FREQUENCY: f32 = 3.;
+/// Fraction of camera motion the nebula follows.
+const PARALLAX: f32 = 0.02;
+/// Extra size on each side so parallax never reveals the texture edge.
+const OVERSCAN: f32 = 0.1;
+
+/// Static noise cloud generated once and drawn behind the starfield.
+pub struct Nebula {
+  texture: Texture2D,
+}
+
+impl Nebula {
+  pub fn new(seed: u64) -> Self {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let density = Perlin::new(&mut rng);
+    let hue = Perlin::new(&mut rng);
+
+    let mut image = Image::gen_image_color(TEXTURE_WIDTH, TEXTURE_HEIGHT, BLANK);
+    let step = FREQUENCY / TEXTURE_WIDTH as f32;
+    for y in 0..TEXTURE_HEIGHT {
+      for x in 0..TEXTURE_WIDTH {
+        let (nx, ny) = (x as f32 * step, y as f32 * step);
+        let d = (density.fbm(nx, ny) * 0.5 + 0.5).clamp(0., 1.);
+        let t = (hue.fbm(nx * 0.5, ny * 0.5) * 0.5 + 0.5).clamp(0., 1.);
+        let violet = vec3(0.45, 0.12, 0.55);
+        let teal = vec3(0.08, 0.35, 0.5);
+        let c = violet.lerp(teal, t);
+        image.set_pixel(
+          x as u32,
+          y as u32,
+          Color::new(c.x, c.y, c.z, d.powi(3) * 0.6),
+        );
+      }
+    }
+
+    let texture = Texture2D::from_image(&image);
+    texture.set_filter(FilterMode::Linear);
+    Nebula { texture }
+  }
+
+  /// Draws the nebula filling the screen, shifted slightly against the
+  /// camera position for a sense of depth.
+  pub fn render(&self, view: &View) {
+    let (w, h) = (screen_width(), screen_height());
+    let margin = vec2(w, h) * OVERSCAN;
+    let shift = (-view.zero * view.scale * PARALLAX).clamp(-margin, margin);
+
+    draw_texture_ex(
+      self.texture,
+      -margin.x + shift.x,
+      -margin.y + shift.y,
+      WHITE,
+      DrawTextureParams {
+        dest_size: Some(vec2(w, h) + margin * 2.),
+        ..Default::default()
+      },
+    );
+  }
+}
+
+/// Classic 2D gradient noise over a shuffled permutation table.
+struct Perlin {
+  perm: [u8; 512],
+}
+
+impl Perlin {
+  fn new(rng: &mut impl Rng) -> Self {
+    let mut table: Vec<u8> = (0..=255).collect();
+    table.shuffle(rng);
+    let mut perm = [0; 512];
+    for (i, p) in perm.iter_mut().enumerate() {
+      *p = table[i % 256];
+    }
+    Perlin { perm }
+  }
+
+  fn hash(&self, x: usize, y: usize) -> u8 {
+    self.perm[self.perm[x] as usize + y]
+  }
+
+  /// Noise in roughly -1..1.
+  fn noise(&self, x: f32, y: f32) -> f32 {
+    let (xi, yi) = (x.floor() as i32 & 255, y.floor() as i32 & 255);
+    let (xf, yf) = (x - x.floor(), y - y.floor());
+    let (u, v) = (fade(xf), fade(yf));
+    let (xi, yi) = (xi as usize, yi as usize);
+
+    let aa = grad(self.hash(xi, yi), xf, yf);
+    let ba = grad(self.hash(xi + 1, yi), xf - 1., yf);
+    let ab = grad(self.hash(xi, yi + 1), xf, yf - 1.);
+    let bb = grad(self.hash(xi + 1, yi + 1), xf - 1., yf - 1.);
+
+    lerp(lerp(aa, ba, u), lerp(ab, bb, u), v)
+  }
+
+  /// Fractal sum of octaves, normalized to roughly -1..1.
+  fn fbm(&self, x: f32, y: f32) -> f32 {
+    let (mut sum, mut amplitude, mut frequency, mut norm) = (0., 1., 1., 0.);
+    for _ in 0..OCTAVES {
+      sum += self.noise(x * frequency, y * frequency) * amplitude;
+      norm += amplitude;
+      amplitude *= 0.5;
+      frequency *= 2.;
+    }
+    sum / norm
+  }
+}
+
+fn fade(t: f32) -> f32 {
+  t * t * t * (t * (t * 6. - 15.) + 10.)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+  a + (b - a) * t
+}
+
+fn grad(hash: u8, x: f32, y: f32) -> f32 {
+  match hash & 7 {
+    0 => x + y,
+    1 => x - y,
+    2 => -x + y,
+    3 => -x - y,
+    4 => x,
+    5 => -x,
+    6 => y,
+    _ => -y,
+  }
+}
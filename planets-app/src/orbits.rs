@@ -0,0 +1,100 @@
+use macroquad::prelude::*;
+
+use crate::Planet;
+
+/// Share of the circular speed given to elliptical insertions, which puts
+/// the spawn point at apoapsis.
+const ELLIPTICAL_SPEED: f32 = 0.75;
+
+/// Starting velocity of interactively spawned bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Insertion {
+  Circular,
+  Elliptical,
+  /// Moving along with the attractor, so it falls straight in.
+  AtRest,
+}
+
+impl Insertion {
+  pub fn cycle(self) -> Insertion {
+    match self {
+      Insertion::Circular => Insertion::Elliptical,
+      Insertion::Elliptical => Insertion::AtRest,
+      Insertion::AtRest => Insertion::Circular,
+    }
+  }
+
+  pub fn label(self) -> &'static str {
+    match self {
+      Insertion::Circular => "circular",
+      Insertion::Elliptical => "elliptical",
+      Insertion::AtRest => "at rest",
+    }
+  }
+
+  /// Puts `body` level with whatever dominates gravity where it is and
+  /// sets its velocity relative to that, at rest when there is nothing.
+  pub fn launch(self, body: &mut Planet, objects: &[Planet], g: f32) {
+    let Some(center) = dominant_attractor(objects, body.pos) else {
+      return;
+    };
+    body.z = center.z;
+    let circular = orbit_velocity(body, center, g);
+    let carried = center.velocity.extend(center.vz);
+    let velocity = match self {
+      Insertion::Circular => circular,
+      Insertion::Elliptical => carried + (circular - carried) * ELLIPTICAL_SPEED,
+      Insertion::AtRest => carried,
+    };
+    (body.velocity, body.vz) = (velocity.truncate(), velocity.z);
+  }
+}
+
+/// Velocity for a circular orbit of `sat` around `center` alone, turning
+/// about the z axis and carried along with the center.
+pub fn orbit_velocity(sat: &Planet, center: &Planet, g: f32) -> Vec3 {
+  let diff = sat.pos.extend(sat.z) - center.pos.extend(center.z);
+  let speed = (g * (center.mass + sat.mass) / diff.length()).sqrt();
+  let tan = Vec3::Z.cross(diff).normalize();
+  tan * speed + center.velocity.extend(center.vz)
+}
+
+/// Distance at which a circular orbit around `mass` has the given speed,
+/// any closer and it has to be faster.
+pub fn circular_radius(mass: f32, g: f32, speed: f32) -> f32 {
+  g * mass / (speed * speed)
+}
+
+/// Circular orbit velocity around whatever dominates gravity at `sat`'s
+/// position, at rest when there is nothing to orbit.
+pub fn local_orbit_velocity(objects: &[Planet], sat: &Planet, g: f32) -> Vec3 {
+  dominant_attractor(objects, sat.pos).map_or(Vec3::ZERO, |center| orbit_velocity(sat, center, g))
+}
+
+/// The lightest body whose Hill sphere contains `pos`, so a point next to
+/// a planet orbits the planet rather than the sun.
+pub fn dominant_attractor(objects: &[Planet], pos: Vec2) -> Option<&Planet> {
+  objects
+    .iter()
+    .filter(|p| p.pos != pos && p.departing.is_none())
+    .filter(|p| p.pos.distance(pos) < hill_radius(objects, p))
+    .min_by(|a, b| a.mass.total_cmp(&b.mass))
+}
+
+/// Distance out to which `body` holds on to satellites against the pull of
+/// its own primary, the heavier body pulling hardest on it. Unbounded for
+/// bodies with no heavier neighbor.
+pub fn hill_radius(objects: &[Planet], body: &Planet) -> f32 {
+  let primary = objects
+    .iter()
+    .filter(|p| p.mass > body.mass)
+    .max_by(|a, b| pull(a, body.pos).total_cmp(&pull(b, body.pos)));
+  match primary {
+    Some(primary) => body.pos.distance(primary.pos) * (body.mass / (3. * primary.mass)).cbrt(),
+    None => f32::INFINITY,
+  }
+}
+
+fn pull(body: &Planet, pos: Vec2) -> f32 {
+  body.mass / body.pos.distance_squared(pos)
+}
@@ -0,0 +1,29 @@
+use macroquad::prelude::*;
+
+use crate::theme::hex_color;
+
+const PASTEL: [u32; 8] = [
+  0xffb3ba, 0xffdfba, 0xffffba, 0xbaffc9, 0xbae1ff, 0xd7baff, 0xf2c6de, 0xc9e4de,
+];
+
+const NEON: [u32; 7] = [
+  0xff00ff, 0x00ffff, 0x39ff14, 0xfff01f, 0xff3131, 0xff9900, 0x7df9ff,
+];
+
+/// Rocky browns and greys, icy blues and banded gas-giant tans.
+const REALISTIC: [u32; 10] = [
+  0x8c7b6b, 0xa0522d, 0x6e6259, 0xc1440e, 0xb8d4e3, 0x8fb8de, 0x5b8fb9, 0xd8ca9d, 0xc9a66b,
+  0xe3bb76,
+];
+
+/// Planet colors by palette name, empty for fully random colors.
+pub fn named(name: &str) -> Option<Vec<Color>> {
+  let colors: &[u32] = match name {
+    "pastel" => &PASTEL,
+    "neon" => &NEON,
+    "realistic" => &REALISTIC,
+    "random" => &[],
+    _ => return None,
+  };
+  Some(Vec::from_iter(colors.iter().map(|&c| hex_color(c))))
+}
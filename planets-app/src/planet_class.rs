@@ -0,0 +1,110 @@
+use macroquad::prelude::*;
+use std::f32::consts::PI;
+
+/// Kind of planet, decided by mass alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanetClass {
+  Rocky,
+  IceGiant,
+  GasGiant,
+  /// Too light to fuse hydrogen, but hot enough to glow faintly.
+  BrownDwarf,
+}
+
+/// Density the class sizes are relative to, Earth's in g/cm³.
+const ROCKY_DENSITY: f32 = 5.5;
+
+/// Ring sweep relative to the planet radius.
+const RING_WIDTH: f32 = 2.2;
+/// Apparent ring height relative to its width, as seen at an angle.
+const RING_FLATTENING: f32 = 0.3;
+const RING_SEGMENTS: usize = 32;
+
+impl PlanetClass {
+  pub fn of(mass: f32) -> PlanetClass {
+    match mass {
+      m if m < 600. => PlanetClass::Rocky,
+      m if m < 1800. => PlanetClass::IceGiant,
+      m if m < 4500. => PlanetClass::GasGiant,
+      _ => PlanetClass::BrownDwarf,
+    }
+  }
+
+  pub fn label(self) -> &'static str {
+    match self {
+      PlanetClass::Rocky => "rocky planet",
+      PlanetClass::IceGiant => "ice giant",
+      PlanetClass::GasGiant => "gas giant",
+      PlanetClass::BrownDwarf => "brown dwarf",
+    }
+  }
+
+  /// Mean density in g/cm³.
+  pub fn density(self) -> f32 {
+    match self {
+      PlanetClass::Rocky => ROCKY_DENSITY,
+      PlanetClass::IceGiant => 1.6,
+      PlanetClass::GasGiant => 0.7,
+      // degenerate matter packs a lot more mass into a Jupiter-sized ball
+      PlanetClass::BrownDwarf => 20.,
+    }
+  }
+
+  /// Radius relative to a rocky planet of the same mass.
+  pub fn size(self) -> f32 {
+    (ROCKY_DENSITY / self.density()).cbrt()
+  }
+
+  /// Number of cloud bands across the disc, 0 for a plain surface.
+  pub fn bands(self) -> f32 {
+    match self {
+      PlanetClass::Rocky => 0.,
+      PlanetClass::IceGiant => 3.,
+      PlanetClass::GasGiant => 7.,
+      PlanetClass::BrownDwarf => 5.,
+    }
+  }
+
+  pub fn ring_chance(self) -> f64 {
+    match self {
+      PlanetClass::Rocky => 0.,
+      PlanetClass::IceGiant => 0.3,
+      PlanetClass::GasGiant => 0.6,
+      PlanetClass::BrownDwarf => 0.1,
+    }
+  }
+
+  pub fn moon_chance(self) -> f64 {
+    match self {
+      PlanetClass::Rocky => 0.1,
+      PlanetClass::IceGiant => 0.3,
+      PlanetClass::GasGiant => 0.5,
+      PlanetClass::BrownDwarf => 0.4,
+    }
+  }
+
+  /// Tint of the glow around the body, if it shines on its own.
+  pub fn glow(self) -> Option<Color> {
+    match self {
+      PlanetClass::BrownDwarf => Some(Color::new(0.7, 0.2, 0.1, 0.5)),
+      _ => None,
+    }
+  }
+}
+
+/// Draws the far or near half of a ring system tilted by `tilt` radians,
+/// so the planet can be drawn in between.
+pub fn draw_ring(center: Vec2, radius: f32, tilt: f32, color: Color, near: bool) {
+  let (sin, cos) = tilt.sin_cos();
+  let axes = vec2(RING_WIDTH, RING_WIDTH * RING_FLATTENING) * radius;
+  let point = |t: f32| {
+    let p = vec2(t.cos(), t.sin()) * axes;
+    center + vec2(p.x * cos - p.y * sin, p.x * sin + p.y * cos)
+  };
+  let start = if near { 0. } else { PI };
+  for i in 0..RING_SEGMENTS / 2 {
+    let t = start + PI * i as f32 / (RING_SEGMENTS / 2) as f32;
+    let (a, b) = (point(t), point(t + 2. * PI / RING_SEGMENTS as f32));
+    draw_line(a.x, a.y, b.x, b.y, (radius * 0.25).max(1.), color);
+  }
+}
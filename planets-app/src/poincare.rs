@@ -0,0 +1,100 @@
+//! Poincaré surface of section of the selected body: its x and x velocity
+//! around what it orbits every time it crosses y = 0 going up. A regular
+//! orbit lays its points out along a smooth curve, a chaotic one scatters
+//! them over an area.
+
+use macroquad::prelude::*;
+
+use crate::camera::base_scale;
+use crate::conics::Conics;
+use crate::{hidpi, Planet};
+
+/// Crossings kept, the oldest dropped first.
+const MAX_POINTS: usize = 5000;
+/// Room left below for the hotbar.
+const BOTTOM: f32 = 50.;
+
+#[derive(Debug, Default)]
+pub struct Poincare {
+  pub visible: bool,
+  /// Body and center the points are of; a change of either starts over.
+  pair: Option<(u32, u32)>,
+  /// Position and velocity relative to the center as of the last step.
+  last: Option<(Vec2, Vec2)>,
+  points: Vec<Vec2>,
+}
+
+impl Poincare {
+  /// Call once per step with the selected body.
+  pub fn record(&mut self, objects: &[Planet], conics: &Conics, selected: Option<u32>) {
+    if !self.visible {
+      return;
+    }
+    let find = |id: u32| objects.iter().find(|p| p.id == id);
+    let pair = selected.and_then(|id| Some((find(id)?, find(conics.get(id)?.center)?)));
+    let ids = pair.map(|(body, center)| (body.id, center.id));
+    if ids != self.pair {
+      self.pair = ids;
+      self.last = None;
+      self.points.clear();
+    }
+    let Some((body, center)) = pair else {
+      return;
+    };
+    let now = (body.pos - center.pos, body.velocity - center.velocity);
+    if let Some((pos, velocity)) = self.last {
+      if pos.y < 0. && now.0.y >= 0. {
+        // where between the two steps it crossed
+        let t = -pos.y / (now.0.y - pos.y);
+        let x = pos.x + (now.0.x - pos.x) * t;
+        let vx = velocity.x + (now.1.x - velocity.x) * t;
+        if self.points.len() == MAX_POINTS {
+          self.points.remove(0);
+        }
+        self.points.push(vec2(x, vx));
+      }
+    }
+    self.last = Some(now);
+  }
+
+  /// Bottom center, scaled to fit the points.
+  pub fn render(&self, objects: &[Planet]) {
+    if !self.visible {
+      return;
+    }
+    let Some(body) = self
+      .pair
+      .and_then(|(id, _)| objects.iter().find(|p| p.id == id))
+    else {
+      return;
+    };
+    let scale = base_scale();
+    let line = 16. * scale;
+    let size = 240. * scale;
+    let corner = vec2(
+      screen_width() / 2. - size / 2.,
+      screen_height() - BOTTOM - size - line * 1.5,
+    );
+    draw_rectangle(
+      corner.x - 6.,
+      corner.y,
+      size + 12.,
+      size + line * 1.5 + 6.,
+      Color::new(0., 0., 0., 0.6),
+    );
+    let title = format!("{}: x, vx at y = 0 ({})", body.name, self.points.len());
+    hidpi::draw_text_sharp(&title, corner.x, corner.y + line, line, body.color);
+
+    let (low, high) = self.points.iter().fold(
+      (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+      |(lo, hi), &p| (lo.min(p), hi.max(p)),
+    );
+    let span = (high - low).max(Vec2::splat(f32::EPSILON));
+    let top = corner.y + line * 1.5;
+    for &p in &self.points {
+      let t = (p - low) / span;
+      let s = vec2(corner.x + t.x * size, top + (1. - t.y) * size);
+      draw_rectangle(s.x, s.y, 1.5 * scale, 1.5 * scale, body.color);
+    }
+  }
+}
@@ -0,0 +1,99 @@
+//! Live statistics of the planets as a whole, for watching a swarm accrete
+//! or scatter: how mass, eccentricity and orbit size are spread, and how
+//! many are still bound.
+
+use macroquad::prelude::*;
+
+use crate::camera::base_scale;
+use crate::conics::{Conic, Conics};
+use crate::{hidpi, BodyKind, Planet};
+
+const BINS: usize = 14;
+/// Decades of mass and of semi-major axis the histograms span.
+const MASS_DECADES: (f32, f32) = (0., 7.);
+const AXIS_DECADES: (f32, f32) = (1., 4.5);
+const MARGIN: f32 = 10.;
+
+/// Counts of `values` in `BINS` even bins from `low` to `high`, the ends
+/// catching anything beyond them.
+fn histogram(values: impl Iterator<Item = f32>, (low, high): (f32, f32)) -> [u32; BINS] {
+  let mut bins = [0; BINS];
+  for v in values.filter(|v| v.is_finite()) {
+    let t = (v - low) / (high - low) * BINS as f32;
+    bins[(t.max(0.) as usize).min(BINS - 1)] += 1;
+  }
+  bins
+}
+
+/// Panel on the left, below the stats.
+pub fn render(objects: &[Planet], conics: &Conics) {
+  let scale = base_scale();
+  let line = 16. * scale;
+  let width = 240. * scale;
+  let graph = 36. * scale;
+
+  let planets = || {
+    objects
+      .iter()
+      .filter(|p| p.kind == BodyKind::Planet && p.departing.is_none())
+  };
+  let orbits = || {
+    planets()
+      .filter_map(|p| conics.get(p.id))
+      .filter(|o| o.conic == Conic::Elliptic)
+  };
+  let bound = orbits().count();
+  let unbound = planets().count() - bound;
+  let charts = [
+    (
+      "mass",
+      histogram(planets().map(|p| p.mass.log10()), MASS_DECADES),
+      MASS_DECADES,
+      "1e",
+    ),
+    (
+      "eccentricity",
+      histogram(orbits().map(|o| o.eccentricity), (0., 1.)),
+      (0., 1.),
+      "",
+    ),
+    (
+      "semi-major axis",
+      histogram(orbits().map(|o| o.semi_major_axis.log10()), AXIS_DECADES),
+      AXIS_DECADES,
+      "1e",
+    ),
+  ];
+
+  let height = line * 1.5 + charts.len() as f32 * (graph + line * 2.5);
+  let corner = vec2(MARGIN, screen_height() / 2. - height / 2.);
+  draw_rectangle(
+    corner.x - 5.,
+    corner.y,
+    width + 10.,
+    height,
+    Color::new(0., 0., 0., 0.6),
+  );
+  let text = format!("{} bound, {} unbound", bound, unbound);
+  hidpi::draw_text_sharp(&text, corner.x, corner.y + line, line, WHITE);
+
+  let mut y = corner.y + line * 1.5;
+  let bar = width / BINS as f32;
+  for (label, bins, (low, high), prefix) in charts {
+    y += line;
+    hidpi::draw_text_sharp(label, corner.x, y, line, LIGHTGRAY);
+    let top = bins.iter().copied().max().unwrap_or(0).max(1) as f32;
+    let bottom = y + 4. + graph;
+    for (i, &count) in bins.iter().enumerate() {
+      let h = count as f32 / top * graph;
+      draw_rectangle(corner.x + i as f32 * bar, bottom - h, bar - 1., h, SKYBLUE);
+    }
+    y = bottom + line;
+    let ends = [(low, corner.x), (high, corner.x + width)];
+    for (value, x) in ends {
+      let text = format!("{}{}", prefix, value);
+      let x = x - measure_text(&text, None, line as u16, 1.).width * (x - corner.x) / width;
+      hidpi::draw_text_sharp(&text, x, y, line, GRAY);
+    }
+  }
+}
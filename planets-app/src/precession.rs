@@ -0,0 +1,142 @@
+//! Where the selected body's periapsis falls on each pass, and how fast it
+//! turns: perturbations from other bodies make orbits precess, and so does
+//! anything that isn't plain inverse-square gravity.
+
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+use std::f32::consts::{PI, TAU};
+
+use crate::{hidpi, Planet};
+
+/// Periapsis passes kept for the rate and the graph.
+const MAX_PASSES: usize = 200;
+
+/// Periapsis passes of one body around one center.
+#[derive(Debug)]
+pub struct Precession {
+  body: u32,
+  center: u32,
+  /// Distance at the last two steps, the latest last.
+  distances: [f32; 2],
+  /// Day of each pass and the periapsis longitude in radians, unwrapped
+  /// so it keeps counting past a full turn.
+  passes: VecDeque<(u64, f32)>,
+}
+
+impl Precession {
+  pub fn new(body: u32, center: u32) -> Self {
+    Precession {
+      body,
+      center,
+      distances: [f32::INFINITY; 2],
+      passes: VecDeque::new(),
+    }
+  }
+
+  pub fn body(&self) -> u32 {
+    self.body
+  }
+
+  /// Call after every step: a pass is when the distance stops falling.
+  pub fn record(&mut self, objects: &[Planet], g: f32, day: u64) {
+    let find = |id: u32| objects.iter().find(|p| p.id == id);
+    let (Some(body), Some(center)) = (find(self.body), find(self.center)) else {
+      return;
+    };
+    let r = (body.pos - center.pos).extend(body.z - center.z);
+    let v = (body.velocity - center.velocity).extend(body.vz - center.vz);
+    let [before, last] = self.distances;
+    self.distances = [last, r.length()];
+    if !(last < before && last <= r.length()) {
+      return;
+    }
+
+    // the eccentricity vector points at periapsis, and barely moves
+    // between steps
+    let mu = g * (body.mass + center.mass);
+    let e = ((v.length_squared() - mu / r.length()) * r - r.dot(v) * v) / mu;
+    let mut longitude = e.y.atan2(e.x);
+    if let Some(&(_, previous)) = self.passes.back() {
+      let turn = (longitude - previous + PI).rem_euclid(TAU) - PI;
+      longitude = previous + turn;
+    }
+    if self.passes.len() == MAX_PASSES {
+      self.passes.pop_front();
+    }
+    self.passes.push_back((day, longitude));
+  }
+
+  /// Mean precession in degrees per orbit and per thousand days, once
+  /// there are two passes to compare.
+  pub fn rate(&self) -> Option<(f32, f32)> {
+    let (&(first_day, first), &(last_day, last)) = (self.passes.front()?, self.passes.back()?);
+    if last_day == first_day {
+      return None;
+    }
+    let turned = (last - first).to_degrees();
+    let orbits = (self.passes.len() - 1) as f32;
+    let days = (last_day - first_day) as f32;
+    Some((turned / orbits, turned / days * 1000.))
+  }
+
+  /// Readout and a graph of longitude against pass, bottom left above the
+  /// clock.
+  pub fn render(&self, objects: &[Planet]) {
+    const SIZE: f32 = 18.;
+    const WIDTH: f32 = 200.;
+    const GRAPH: f32 = 60.;
+    let (Some(body), Some(&(_, latest))) = (
+      objects.iter().find(|p| p.id == self.body),
+      self.passes.back(),
+    ) else {
+      return;
+    };
+    let mut lines = vec![format!(
+      "{} periapsis at {:.2}°",
+      body.name,
+      latest.to_degrees().rem_euclid(360.)
+    )];
+    if let Some((per_orbit, per_kday)) = self.rate() {
+      lines.push(format!(
+        "precession {:+.4}°/orbit, {:+.3}°/1000 days",
+        per_orbit, per_kday
+      ));
+    }
+    lines.push(format!("{} passes", self.passes.len()));
+
+    let width = lines
+      .iter()
+      .map(|l| measure_text(l, None, SIZE as u16, 1.).width)
+      .fold(WIDTH, f32::max);
+    let height = lines.len() as f32 * SIZE + GRAPH + 16.;
+    let corner = vec2(10., screen_height() - 70. - height);
+    draw_rectangle(
+      corner.x - 5.,
+      corner.y,
+      width + 10.,
+      height,
+      Color::new(0., 0., 0., 0.6),
+    );
+    for (i, line) in lines.iter().enumerate() {
+      let color = if i == 0 { body.color } else { LIGHTGRAY };
+      hidpi::draw_text_sharp(
+        line,
+        corner.x,
+        corner.y + SIZE * (i as f32 + 1.),
+        SIZE,
+        color,
+      );
+    }
+
+    let first = self.passes.front().map_or(0., |&(_, l)| l);
+    let offsets = Vec::from_iter(self.passes.iter().map(|&(_, l)| l - first));
+    let span = offsets.iter().fold(f32::EPSILON, |m, o| m.max(o.abs()));
+    let middle = corner.y + height - GRAPH / 2. - 6.;
+    draw_line(corner.x, middle, corner.x + width, middle, 1., GRAY);
+    let step = width / (MAX_PASSES - 1) as f32;
+    for (i, offset) in offsets.iter().enumerate() {
+      let y = middle - offset / span * GRAPH / 2.;
+      draw_circle(corner.x + i as f32 * step, y, 1.5, body.color);
+    }
+  }
+}
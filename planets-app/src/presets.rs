@@ -0,0 +1,67 @@
+use macroquad::prelude::*;
+
+use crate::orbits::Insertion;
+use crate::{hidpi, BodyKind};
+
+/// Body placed by one of the spawn hotkeys.
+#[derive(Debug, Clone, Copy)]
+pub struct Preset {
+  pub label: &'static str,
+  pub kind: BodyKind,
+  pub mass: f32,
+  /// Random from the palette when unset.
+  pub color: Option<Color>,
+}
+
+pub const PRESETS: [Preset; 5] = [
+  Preset {
+    label: "moonlet",
+    kind: BodyKind::Planet,
+    mass: 20.,
+    color: None,
+  },
+  Preset {
+    label: "rocky planet",
+    kind: BodyKind::Planet,
+    mass: 400.,
+    color: None,
+  },
+  Preset {
+    label: "gas giant",
+    kind: BodyKind::Planet,
+    mass: 3000.,
+    color: None,
+  },
+  // a red dwarf, heavy enough to hold moons of its own but not to take
+  // over the system
+  Preset {
+    label: "star",
+    kind: BodyKind::Star,
+    mass: 300000.,
+    color: Some(Color::new(1., 0.55, 0.35, 1.)),
+  },
+  Preset {
+    label: "black hole",
+    kind: BodyKind::BlackHole,
+    mass: 500000.,
+    color: Some(BLACK),
+  },
+];
+
+/// Row of numbered slots along the bottom edge, with the insertion mode
+/// they spawn with.
+pub fn render_hotbar(insertion: Insertion) {
+  const SIZE: f32 = 18.;
+  const SLOT: f32 = 120.;
+
+  let left = (screen_width() - SLOT * PRESETS.len() as f32) / 2.;
+  let top = screen_height() - 40.;
+  for (i, preset) in PRESETS.iter().enumerate() {
+    let x = left + i as f32 * SLOT;
+    draw_rectangle(x + 2., top, SLOT - 4., 30., Color::new(0., 0., 0., 0.5));
+    let text = format!("{} {}", i + 1, preset.label);
+    hidpi::draw_text_sharp(&text, x + 10., top + 21., SIZE, LIGHTGRAY);
+  }
+  let text = format!("orbit: {}", insertion.label());
+  hidpi::draw_text_sharp(&text, left, top - 8., SIZE, GRAY);
+}
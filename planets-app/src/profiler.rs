@@ -0,0 +1,106 @@
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::camera::base_scale;
+use crate::hidpi;
+
+/// Frames kept for the rolling graph.
+const HISTORY: usize = 240;
+/// Frame time the graph is scaled to, 30 fps.
+const GRAPH_SPAN: f32 = 1. / 30.;
+const MARGIN: f32 = 10.;
+
+/// Where the time of one frame went. The physics parts add up over every
+/// step run in the frame.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Timings {
+  pub forces: Duration,
+  pub integration: Duration,
+  pub collisions: Duration,
+  pub trails: Duration,
+  pub rendering: Duration,
+}
+
+impl Timings {
+  fn parts(&self) -> [(&'static str, Duration, Color); 5] {
+    [
+      ("forces", self.forces, ORANGE),
+      ("integration", self.integration, SKYBLUE),
+      ("collisions", self.collisions, RED),
+      ("trails", self.trails, GREEN),
+      ("rendering", self.rendering, VIOLET),
+    ]
+  }
+
+  /// In seconds.
+  pub fn total(&self) -> f32 {
+    self.parts().iter().map(|(_, d, _)| d.as_secs_f32()).sum()
+  }
+}
+
+/// Stacked bar of the last frame over a graph of recent ones, top right.
+#[derive(Default)]
+pub struct Profiler {
+  history: VecDeque<Timings>,
+  pub visible: bool,
+}
+
+impl Profiler {
+  pub fn push(&mut self, timings: Timings) {
+    if self.history.len() == HISTORY {
+      self.history.pop_front();
+    }
+    self.history.push_back(timings);
+  }
+
+  pub fn render(&self) {
+    let Some(last) = self.history.back().filter(|_| self.visible) else {
+      return;
+    };
+    let scale = base_scale();
+    let (width, height) = (HISTORY as f32 * scale, 80. * scale);
+    let line = 16. * scale;
+    let corner = vec2(screen_width() - width - MARGIN, MARGIN);
+    let panel = height + line * 7.5;
+    draw_rectangle(
+      corner.x - 6.,
+      corner.y,
+      width + 12.,
+      panel,
+      Color::new(0., 0., 0., 0.6),
+    );
+
+    // last frame, as shares of the graph span
+    let mut x = corner.x;
+    let bar_y = corner.y + 6.;
+    for (_, duration, color) in last.parts() {
+      let w = duration.as_secs_f32() / GRAPH_SPAN * width;
+      draw_rectangle(x, bar_y, w.min(corner.x + width - x), line * 0.75, color);
+      x += w;
+    }
+
+    let mut y = bar_y + line * 1.75;
+    for (label, duration, color) in last.parts() {
+      let text = format!("{:<12}{:6.2} ms", label, duration.as_secs_f64() * 1e3);
+      hidpi::draw_text_sharp(&text, corner.x, y, line, color);
+      y += line;
+    }
+    let text = format!("{:<12}{:6.2} ms", "total", last.total() * 1e3);
+    hidpi::draw_text_sharp(&text, corner.x, y, line, WHITE);
+
+    let bottom = corner.y + panel - 6.;
+    for (i, frame) in self.history.iter().enumerate() {
+      let x = corner.x + i as f32 * scale;
+      let mut top = bottom;
+      for (_, duration, color) in frame.parts() {
+        let h = (duration.as_secs_f32() / GRAPH_SPAN * height).min(top - (bottom - height));
+        draw_rectangle(x, top - h, scale, h, color);
+        top -= h;
+      }
+    }
+    // 60 fps budget
+    let budget = bottom - height / 2.;
+    draw_line(corner.x, budget, corner.x + width, budget, 1., GRAY);
+  }
+}
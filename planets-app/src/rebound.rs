@@ -0,0 +1,67 @@
+//! Initial conditions shared with the REBOUND N-body package: one particle
+//! per line as `m x y z vx vy vz` in simulation units, with `G` in a
+//! comment so the other side can match it (`sim.G = ...`). Files load
+//! with `numpy.loadtxt` and feed straight into `sim.add`.
+
+use std::fmt::Write as _;
+use std::{fs, io};
+
+use macroquad::prelude::*;
+
+use crate::{ephemeris, Planet};
+
+pub fn save(objects: &[Planet], g: f32, path: &str) -> io::Result<()> {
+  let mut text = format!("# G = {}\n# m x y z vx vy vz\n", g);
+  for p in objects.iter().filter(|p| p.departing.is_none()) {
+    let _ = writeln!(
+      text,
+      "{} {} {} {} {} {} {}",
+      p.mass, p.pos.x, p.pos.y, p.z, p.velocity.x, p.velocity.y, p.vz
+    );
+  }
+  fs::write(path, text)
+}
+
+/// Bodies, heaviest last as the sun, and `G` if the file gives it. The z
+/// columns may be left out.
+pub fn load(path: &str) -> Result<(Vec<Planet>, Option<f32>), String> {
+  let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+  let mut g = None;
+  let mut bodies = Vec::new();
+  for (n, line) in text.lines().enumerate() {
+    let line = line.trim();
+    if let Some(comment) = line.strip_prefix('#') {
+      if let Some(value) = comment.trim().strip_prefix("G =") {
+        g = value.trim().parse().ok();
+      }
+      continue;
+    }
+    if line.is_empty() {
+      continue;
+    }
+    let numbers = line
+      .split_whitespace()
+      .map(str::parse::<f32>)
+      .collect::<Result<Vec<_>, _>>();
+    let (m, x, y, z, vx, vy, vz) = match numbers.as_deref() {
+      Ok(&[m, x, y, z, vx, vy, vz]) => (m, x, y, z, vx, vy, vz),
+      Ok(&[m, x, y, vx, vy]) => (m, x, y, 0., vx, vy, 0.),
+      _ => return Err(format!("{}:{}: expected `m x y z vx vy vz`", path, n + 1)),
+    };
+    bodies.push(Planet {
+      id: Planet::next_id(),
+      name: format!("particle {}", bodies.len()),
+      mass: m,
+      pos: vec2(x, y),
+      velocity: vec2(vx, vy),
+      z,
+      vz,
+      ..Default::default()
+    });
+  }
+  if bodies.is_empty() {
+    return Err(format!("{}: no particles", path));
+  }
+  ephemeris::arrange(&mut bodies);
+  Ok((bodies, g))
+}
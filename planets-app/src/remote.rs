@@ -0,0 +1,215 @@
+//! Remote control over OSC, the UDP protocol tools like TouchDesigner and
+//! Max/MSP speak. A message to `/planets/<command>` runs that console
+//! command with its arguments; `/planets/state` is answered with one
+//! `/planets/body id name mass x y vx vy` message per body.
+
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+
+use crate::Planet;
+
+const PREFIX: &str = "/planets/";
+/// Fields positional numbers fill in, in order, for `/planets/spawn`.
+const SPAWN_FIELDS: [&str; 5] = ["x", "y", "vx", "vy", "mass"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Arg {
+  Int(i32),
+  Float(f32),
+  Str(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+  pub address: String,
+  pub args: Vec<Arg>,
+}
+
+impl Message {
+  /// The console line this message stands for, if any.
+  pub fn command(&self) -> Option<String> {
+    let name = self.address.strip_prefix(PREFIX)?;
+    let numeric = self.args.iter().all(|a| !matches!(a, Arg::Str(_)));
+    let args = self.args.iter().enumerate().map(|(i, arg)| {
+      let value = match arg {
+        Arg::Int(n) => n.to_string(),
+        Arg::Float(x) => x.to_string(),
+        Arg::Str(s) => s.clone(),
+      };
+      match SPAWN_FIELDS.get(i) {
+        Some(field) if name == "spawn" && numeric => format!("{}={}", field, value),
+        _ => value,
+      }
+    });
+    Some(Vec::from_iter(std::iter::once(name.to_owned()).chain(args)).join(" "))
+  }
+
+  pub fn decode(packet: &[u8]) -> Result<Message, String> {
+    let mut pos = 0;
+    let address = read_string(packet, &mut pos)?;
+    if !address.starts_with('/') {
+      return Err(format!("not an OSC message: `{}`", address));
+    }
+    let tags = match read_string(packet, &mut pos) {
+      Ok(tags) => tags,
+      // old senders may leave the type tags out when there are no arguments
+      Err(_) if pos >= packet.len() => ",".to_owned(),
+      Err(e) => return Err(e),
+    };
+    let Some(tags) = tags.strip_prefix(',') else {
+      return Err("missing type tags".to_owned());
+    };
+    let mut args = Vec::new();
+    for tag in tags.chars() {
+      args.push(match tag {
+        'i' => Arg::Int(i32::from_be_bytes(read_word(packet, &mut pos)?)),
+        'f' => Arg::Float(f32::from_be_bytes(read_word(packet, &mut pos)?)),
+        's' => Arg::Str(read_string(packet, &mut pos)?),
+        _ => return Err(format!("unsupported argument type `{}`", tag)),
+      });
+    }
+    Ok(Message { address, args })
+  }
+
+  pub fn encode(&self) -> Vec<u8> {
+    let mut packet = Vec::new();
+    write_string(&mut packet, &self.address);
+    let tags = self.args.iter().map(|a| match a {
+      Arg::Int(_) => 'i',
+      Arg::Float(_) => 'f',
+      Arg::Str(_) => 's',
+    });
+    write_string(
+      &mut packet,
+      &String::from_iter(std::iter::once(',').chain(tags)),
+    );
+    for arg in &self.args {
+      match arg {
+        Arg::Int(n) => packet.extend(n.to_be_bytes()),
+        Arg::Float(x) => packet.extend(x.to_be_bytes()),
+        Arg::Str(s) => write_string(&mut packet, s),
+      }
+    }
+    packet
+  }
+}
+
+/// Strings are nul terminated and padded to a multiple of four bytes.
+fn read_string(packet: &[u8], pos: &mut usize) -> Result<String, String> {
+  let rest = packet.get(*pos..).unwrap_or_default();
+  let len = rest
+    .iter()
+    .position(|&b| b == 0)
+    .ok_or("unterminated string")?;
+  let s = std::str::from_utf8(&rest[..len]).map_err(|e| e.to_string())?;
+  *pos += (len + 4) & !3;
+  Ok(s.to_owned())
+}
+
+fn read_word(packet: &[u8], pos: &mut usize) -> Result<[u8; 4], String> {
+  let word = packet
+    .get(*pos..*pos + 4)
+    .ok_or("truncated argument")?
+    .try_into()
+    .unwrap();
+  *pos += 4;
+  Ok(word)
+}
+
+fn write_string(packet: &mut Vec<u8>, s: &str) {
+  packet.extend(s.as_bytes());
+  packet.resize((packet.len() + 4) & !3, 0);
+}
+
+/// Listens for OSC messages without blocking the frame.
+pub struct Remote {
+  socket: UdpSocket,
+}
+
+impl Remote {
+  pub fn bind(host: &str, port: u16) -> io::Result<Remote> {
+    let socket = UdpSocket::bind((host, port))?;
+    socket.set_nonblocking(true)?;
+    Ok(Remote { socket })
+  }
+
+  /// Messages received since the last call, with who sent them.
+  pub fn poll(&self) -> Vec<(Result<Message, String>, SocketAddr)> {
+    let mut buf = [0; 1536];
+    let mut received = Vec::new();
+    while let Ok((len, from)) = self.socket.recv_from(&mut buf) {
+      received.push((Message::decode(&buf[..len]), from));
+    }
+    received
+  }
+
+  pub fn send_state(&self, to: SocketAddr, objects: &[Planet]) {
+    for p in objects {
+      let message = Message {
+        address: format!("{}body", PREFIX),
+        args: vec![
+          Arg::Int(p.id as i32),
+          Arg::Str(p.name.clone()),
+          Arg::Float(p.mass),
+          Arg::Float(p.pos.x),
+          Arg::Float(p.pos.y),
+          Arg::Float(p.velocity.x),
+          Arg::Float(p.velocity.y),
+        ],
+      };
+      let _ = self.socket.send_to(&message.encode(), to);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn round_trip() {
+    let message = Message {
+      address: "/planets/spawn".to_owned(),
+      args: vec![Arg::Int(3), Arg::Float(-1.5), Arg::Str("moon".to_owned())],
+    };
+    assert_eq!(Message::decode(&message.encode()), Ok(message));
+  }
+
+  #[test]
+  fn missing_type_tags() {
+    let message = Message::decode(b"/planets/pause\0\0").unwrap();
+    assert_eq!(message.command().as_deref(), Some("pause"));
+  }
+
+  #[test]
+  fn spawn_numbers_fill_fields() {
+    let message = Message {
+      address: "/planets/spawn".to_owned(),
+      args: vec![Arg::Float(1.), Arg::Int(2)],
+    };
+    assert_eq!(message.command().as_deref(), Some("spawn x=1 y=2"));
+  }
+
+  #[test]
+  fn truncated_string() {
+    assert!(Message::decode(b"/planets/pa").is_err());
+    assert!(Message::decode(b"/planets/set\0\0\0\0,s\0\0G").is_err());
+  }
+
+  #[test]
+  fn truncated_argument() {
+    assert!(Message::decode(b"/planets/seed\0\0\0,i\0\0\0\0").is_err());
+  }
+
+  #[test]
+  fn bad_type_tags() {
+    assert!(Message::decode(b"/planets/seed\0\0\0,x\0\0\0\0\0\x07").is_err());
+    assert!(Message::decode(b"/planets/seed\0\0\0i\0\0\0\0\0\0\x07").is_err());
+  }
+
+  #[test]
+  fn not_a_message() {
+    assert!(Message::decode(b"#bundle\0").is_err());
+    assert!(Message::decode(b"").is_err());
+  }
+}
@@ -0,0 +1,84 @@
+//! Gravitational waves from two very massive bodies merging, drawn as
+//! rings racing outwards across the background.
+
+use macroquad::prelude::*;
+use std::f32::consts::TAU;
+
+use crate::camera::View;
+use crate::Planet;
+
+/// The lighter of the two has to be at least this heavy, as heavy as a
+/// spawned star, for its merger to make waves worth showing.
+const RIPPLE_MASS: f32 = 1e5;
+/// World units the wave front covers per second.
+const SPEED: f32 = 500.;
+/// Seconds before the rings have faded.
+const DURATION: f64 = 4.;
+/// Crests in the train, and the spacing of the outermost ones in world
+/// units; the ones behind crowd closer like the chirp before a merger.
+const CRESTS: usize = 6;
+const SPACING: f32 = 40.;
+const SEGMENTS: usize = 72;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ripple {
+  pos: Vec2,
+  started: f64,
+  /// Wobble of the rings out of round, in world units.
+  strain: f32,
+  /// Orientation of the wobble, from the direction the two came together.
+  angle: f32,
+}
+
+impl Ripple {
+  /// The ripple from `absorbed` merging into `survivor`, if both were heavy
+  /// enough.
+  pub fn of(survivor: &Planet, absorbed: &Planet) -> Option<Ripple> {
+    let heavier = survivor.mass - absorbed.mass;
+    let lighter = absorbed.mass.min(heavier);
+    if lighter < RIPPLE_MASS {
+      return None;
+    }
+    // amplitude goes with the chirp mass, compressed since it spans decades
+    let chirp = (heavier * lighter).powf(0.6) / (heavier + lighter).powf(0.2);
+    let apart = absorbed.pos - survivor.pos;
+    Some(Ripple {
+      pos: survivor.pos,
+      started: get_time(),
+      strain: chirp.ln(),
+      angle: apart.y.atan2(apart.x),
+    })
+  }
+
+  /// Draws the ripple, false once it has faded.
+  pub fn render(&self, view: &View) -> bool {
+    let age = get_time() - self.started;
+    if age >= DURATION {
+      return false;
+    }
+    let fade = 1. - (age / DURATION) as f32;
+    let front = age as f32 * SPEED;
+    let center = view.world_to_screen(self.pos);
+    let mut behind = 0.;
+    for i in 0..CRESTS {
+      let radius = front - behind;
+      behind += SPACING * (1. - i as f32 / CRESTS as f32);
+      if radius <= 0. {
+        break;
+      }
+      let alpha = 0.5 * fade * (1. - i as f32 / CRESTS as f32);
+      let color = Color::new(0.7, 0.8, 1., alpha);
+      // quadrupole: stretched one way while squeezed across
+      let point = |k: usize| {
+        let theta = k as f32 / SEGMENTS as f32 * TAU;
+        let r = radius + self.strain * fade * (2. * (theta - self.angle)).cos();
+        center + Vec2::from_angle(theta) * r * view.scale
+      };
+      for k in 0..SEGMENTS {
+        let (a, b) = (point(k), point(k + 1));
+        draw_line(a.x, a.y, b.x, b.y, 1.5, color);
+      }
+    }
+    true
+  }
+}
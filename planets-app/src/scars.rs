@@ -0,0 +1,77 @@
+//! Marks left on bodies by what crashed into them: a glow that cools off
+//! right after the impact, a crater that stays, and the history of impacts
+//! behind them.
+
+use macroquad::prelude::*;
+use planets_core::Impact;
+
+use crate::{BodyKind, Planet};
+
+/// Impacts a body remembers, the oldest forgotten first.
+const KEPT: usize = 8;
+/// Seconds the impact site glows for.
+const GLOW_SECONDS: f64 = 3.;
+/// Crater size in radii of the body, for the smallest and largest impactors.
+const CRATER_SIZES: (f32, f32) = (0.08, 0.35);
+
+#[derive(Debug, Clone, Copy)]
+pub struct Scar {
+  /// Simulated day of the impact.
+  pub day: u64,
+  /// Mass of the body that hit.
+  pub mass: f32,
+  /// Speed the two came together at, in units per day.
+  pub speed: f32,
+  /// Direction from the center of the survivor to where it was hit.
+  angle: f32,
+  /// Crater size in radii of the survivor.
+  size: f32,
+  made: f64,
+}
+
+impl Scar {
+  /// What `absorbed` left on `survivor`, already merged.
+  pub fn new(survivor: &Planet, absorbed: &Planet, impact: Impact) -> Scar {
+    let mass = survivor.mass - absorbed.mass;
+    // the energy lost went into the relative motion of the reduced mass
+    let reduced = mass * absorbed.mass / survivor.mass;
+    let apart = absorbed.pos - survivor.pos;
+    let (least, most) = CRATER_SIZES;
+    Scar {
+      day: 0,
+      mass: absorbed.mass,
+      speed: (2. * impact.energy / reduced).sqrt(),
+      angle: apart.y.atan2(apart.x),
+      size: (absorbed.mass / mass).sqrt().clamp(least, most),
+      made: get_time(),
+    }
+  }
+}
+
+/// Adds `scar`, made on `day`, to what `body` remembers.
+pub fn record(body: &mut Planet, scar: Scar, day: u64) {
+  body.scars.push(Scar { day, ..scar });
+  let over = body.scars.len().saturating_sub(KEPT);
+  body.scars.drain(..over);
+}
+
+/// Draws the craters and glows of `body` over its disc at `center`.
+pub fn render(body: &Planet, center: Vec2, radius: f32, opacity: f32) {
+  for scar in &body.scars {
+    let site = center + Vec2::from_angle(scar.angle) * radius * (1. - scar.size);
+    let size = radius * scar.size;
+    // only planets have a surface to keep a crater in
+    if body.kind == BodyKind::Planet {
+      let crater = Color::new(0., 0., 0., 0.35 * opacity);
+      draw_circle(site.x, site.y, size, crater);
+      let rim = Color::new(1., 1., 1., 0.15 * opacity);
+      draw_circle_lines(site.x, site.y, size, 1., rim);
+    }
+    let age = get_time() - scar.made;
+    if age < GLOW_SECONDS {
+      let heat = 1. - (age / GLOW_SECONDS) as f32;
+      let glow = Color::new(1., 0.5 + 0.4 * heat, 0.2, heat * opacity);
+      draw_circle(site.x, site.y, size * (1. + heat), glow);
+    }
+  }
+}
@@ -0,0 +1,190 @@
+//! Systems saved as plain text, one body per line:
+//! `kind mass x y vx vy #rrggbb name`, with the sun last. The name may
+//! contain spaces or be left out. Bodies off the plane are saved as
+//! `kind mass x y z vx vy vz #rrggbb name`. A background potential is
+//! saved on a line of its own, `potential halo 1.5 100`.
+//!
+//! Also reads CSV body lists, `name,mass,x,y,vx,vy,color` in simulation
+//! units, for systems made in spreadsheets or scripts.
+
+use macroquad::prelude::*;
+use std::fmt::Write as _;
+use std::{fs, io};
+
+use crate::theme::parse_hex_color;
+use planets_core::Potential;
+
+use crate::{ephemeris, BodyKind, Planet};
+
+pub const EXTENSION: &str = "system";
+
+/// File a system called `name` is saved as, unless it already has the
+/// extension.
+pub fn path(name: &str) -> String {
+  if name.ends_with(&format!(".{}", EXTENSION)) {
+    name.to_owned()
+  } else {
+    format!("{}.{}", name, EXTENSION)
+  }
+}
+
+pub fn save(objects: &[Planet], potential: Option<Potential>, path: &str) -> io::Result<()> {
+  let mut text = String::new();
+  if let Some(potential) = potential {
+    let _ = writeln!(text, "potential {}", potential);
+  }
+  for p in objects.iter().filter(|p| p.departing.is_none()) {
+    let [r, g, b, _]: [u8; 4] = p.color.into();
+    let (pos, velocity) = if p.z == 0. && p.vz == 0. {
+      (
+        format!("{} {}", p.pos.x, p.pos.y),
+        format!("{} {}", p.velocity.x, p.velocity.y),
+      )
+    } else {
+      (
+        format!("{} {} {}", p.pos.x, p.pos.y, p.z),
+        format!("{} {} {}", p.velocity.x, p.velocity.y, p.vz),
+      )
+    };
+    let _ = writeln!(
+      text,
+      "{} {} {} {} #{:02x}{:02x}{:02x} {}",
+      kind_name(p.kind),
+      p.mass,
+      pos,
+      velocity,
+      r,
+      g,
+      b,
+      p.name
+    );
+  }
+  fs::write(path, text)
+}
+
+/// Bodies of a saved system and the potential it was saved with.
+pub fn load(path: &str) -> Result<(Vec<Planet>, Option<Potential>), String> {
+  let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+  let mut objects = Vec::new();
+  let mut potential = None;
+  for (n, line) in text.lines().enumerate() {
+    let error = |e| format!("{}:{}: {}", path, n + 1, e);
+    if line.trim().is_empty() {
+      continue;
+    }
+    if let Some(rest) = line.strip_prefix("potential ") {
+      potential = Some(rest.parse().map_err(error)?);
+      continue;
+    }
+    objects.push(parse_body(line).map_err(error)?);
+  }
+  Ok((objects, potential))
+}
+
+/// Bodies from a CSV list, heaviest last as the sun. A header line and the
+/// color column are optional; bodies without a color are left to the
+/// caller to paint.
+pub fn load_csv(path: &str) -> Result<Vec<Planet>, String> {
+  let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+  let mut bodies = Vec::new();
+  for (n, line) in text.lines().enumerate() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || (n == 0 && line.starts_with("name")) {
+      continue;
+    }
+    let body = parse_csv_body(line).map_err(|e| format!("{}:{}: {}", path, n + 1, e))?;
+    bodies.push(body);
+  }
+  if bodies.is_empty() {
+    return Err(format!("{}: no bodies", path));
+  }
+  ephemeris::arrange(&mut bodies);
+  Ok(bodies)
+}
+
+fn parse_csv_body(line: &str) -> Result<Planet, String> {
+  let fields = Vec::from_iter(line.split(',').map(str::trim));
+  let (name, numbers, color) = match fields[..] {
+    [name, m, x, y, vx, vy] => (name, [m, x, y, vx, vy], None),
+    [name, m, x, y, vx, vy, color] => (name, [m, x, y, vx, vy], Some(color)),
+    _ => return Err("expected `name,mass,x,y,vx,vy,color`".to_owned()),
+  };
+  let mut numbers = numbers.into_iter().map(|s| {
+    s.parse::<f32>()
+      .map_err(|e| format!("invalid number `{}`: {}", s, e))
+  });
+  let mut number = || numbers.next().unwrap();
+  Ok(Planet {
+    id: Planet::next_id(),
+    name: name.to_owned(),
+    mass: number()?,
+    pos: vec2(number()?, number()?),
+    velocity: vec2(number()?, number()?),
+    color: match color {
+      Some(c) if !c.is_empty() => parse_hex_color(c)?,
+      _ => Color::default(),
+    },
+    ..Default::default()
+  })
+}
+
+/// Saved systems in the working directory, by name.
+pub fn list() -> Vec<String> {
+  let Ok(entries) = fs::read_dir(".") else {
+    return vec![];
+  };
+  let mut paths = Vec::from_iter(entries.filter_map(|e| {
+    let name = e.ok()?.file_name().into_string().ok()?;
+    name.ends_with(&format!(".{}", EXTENSION)).then_some(name)
+  }));
+  paths.sort();
+  paths
+}
+
+fn parse_body(line: &str) -> Result<Planet, String> {
+  let fields = Vec::from_iter(line.split_whitespace());
+  // the color comes after five numbers, or seven with z
+  let color = fields.iter().position(|f| f.starts_with('#'));
+  let Some(color @ (6 | 8)) = color else {
+    return Err("expected `kind mass x y vx vy #rrggbb name`".to_owned());
+  };
+  let numbers = fields[1..color]
+    .iter()
+    .map(|s| {
+      s.parse::<f32>()
+        .map_err(|e| format!("invalid number `{}`: {}", s, e))
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+  let (mass, [x, y, z], [vx, vy, vz]) = match numbers[..] {
+    [m, x, y, vx, vy] => (m, [x, y, 0.], [vx, vy, 0.]),
+    [m, x, y, z, vx, vy, vz] => (m, [x, y, z], [vx, vy, vz]),
+    _ => unreachable!(),
+  };
+  Ok(Planet {
+    id: Planet::next_id(),
+    name: fields[color + 1..].join(" "),
+    kind: parse_kind(fields[0])?,
+    mass,
+    pos: vec2(x, y),
+    velocity: vec2(vx, vy),
+    z,
+    vz,
+    color: parse_hex_color(fields[color])?,
+    ..Default::default()
+  })
+}
+
+pub fn kind_name(kind: BodyKind) -> &'static str {
+  match kind {
+    BodyKind::Planet => "planet",
+    BodyKind::Star => "star",
+    BodyKind::BlackHole => "blackhole",
+  }
+}
+
+fn parse_kind(name: &str) -> Result<BodyKind, String> {
+  [BodyKind::Planet, BodyKind::Star, BodyKind::BlackHole]
+    .into_iter()
+    .find(|&k| kind_name(k) == name)
+    .ok_or_else(|| format!("unknown kind `{}`", name))
+}
@@ -0,0 +1,58 @@
+//! Running as a screensaver: nothing on screen but the system, a fresh one
+//! every few minutes, until the first sign of someone at the machine.
+
+use macroquad::prelude::*;
+
+use crate::{input, state};
+
+/// Seconds after starting when input doesn't count yet, while the window
+/// settles and the cursor reports where it is.
+const GRACE: f64 = 1.;
+/// Pixels the mouse may drift without waking up, for jittery mice.
+const MOUSE_SLOP: f32 = 10.;
+
+#[derive(Debug)]
+pub struct Screensaver {
+  /// Seconds between new systems.
+  every: f64,
+  started: f64,
+  regenerated: f64,
+  /// Where the mouse was once the grace period was over.
+  mouse: Option<Vec2>,
+}
+
+impl Screensaver {
+  /// Goes fullscreen and hides the cursor.
+  pub fn start(minutes: f32) -> Self {
+    state::set_fullscreen(true);
+    show_mouse(false);
+    let now = get_time();
+    Screensaver {
+      every: (minutes.max(0.1) * 60.) as f64,
+      started: now,
+      regenerated: now,
+      mouse: None,
+    }
+  }
+
+  /// Whether any key, button, touch or mouse movement says it's time to
+  /// stop.
+  pub fn woken(&mut self) -> bool {
+    if get_time() - self.started < GRACE {
+      return false;
+    }
+    let mouse = Vec2::from(mouse_position());
+    self.mouse.get_or_insert(mouse).distance(mouse) > MOUSE_SLOP || input::any_pressed()
+  }
+
+  /// True once every few minutes, when a new system should replace the one
+  /// running.
+  pub fn regenerate(&mut self) -> bool {
+    let now = get_time();
+    if now - self.regenerated < self.every {
+      return false;
+    }
+    self.regenerated = now;
+    true
+  }
+}
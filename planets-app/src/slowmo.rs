@@ -0,0 +1,93 @@
+//! Drops to real time for close encounters and collisions about to happen
+//! at high speed, then goes back to the time scale that was set.
+
+use macroquad::prelude::*;
+
+use crate::{Planet, STEPS_PER_SECOND, TIME_SCALES};
+
+/// Pairs passing within this many times their radii count as an encounter.
+const ENCOUNTER_RADII: f32 = 3.;
+/// Real seconds ahead an encounter is caught at the time scale it would be
+/// missed at.
+const LEAD: f32 = 1.5;
+/// Seconds the vignette takes to fade in or out.
+const FADE: f32 = 0.3;
+
+#[derive(Debug, Default)]
+pub struct SlowMotion {
+  pub enabled: bool,
+  /// Time scale to go back to, and the one slowed to, while slowed down.
+  slowed: Option<(usize, usize)>,
+  vignette: f32,
+}
+
+impl SlowMotion {
+  /// Lowers `time_scale` when an encounter is coming up, and restores it
+  /// once every encounter has passed. A time scale changed by hand while
+  /// slowed down is left alone. Returns true when it slowed down.
+  pub fn update(&mut self, objects: &[Planet], time_scale: &mut usize, dt: f32) -> bool {
+    if self.slowed.is_some_and(|(_, to)| to != *time_scale) || !self.enabled {
+      self.slowed = None;
+    }
+    let normal = self.slowed.map_or(*time_scale, |(from, _)| from);
+    let horizon = LEAD * TIME_SCALES[normal] as f32 * STEPS_PER_SECOND;
+    let coming = normal > 0 && self.enabled && encounter_within(objects, horizon);
+
+    let mut slowed_down = false;
+    match self.slowed {
+      None if coming => {
+        self.slowed = Some((*time_scale, 0));
+        *time_scale = 0;
+        slowed_down = true;
+      }
+      Some((from, _)) if !coming => {
+        *time_scale = from;
+        self.slowed = None;
+      }
+      _ => {}
+    }
+    let target = if self.slowed.is_some() { 1. } else { 0. };
+    self.vignette += (target - self.vignette).clamp(-dt / FADE, dt / FADE);
+    slowed_down
+  }
+
+  /// Darkened screen edges while slowed down.
+  pub fn render(&self) {
+    const BANDS: usize = 12;
+    if self.vignette <= 0. {
+      return;
+    }
+    let (w, h) = (screen_width(), screen_height());
+    let band = w.min(h) * 0.15 / BANDS as f32;
+    for i in 0..BANDS {
+      let alpha = 0.5 * self.vignette * (1. - i as f32 / BANDS as f32);
+      let inset = i as f32 * band;
+      draw_rectangle_lines(
+        inset,
+        inset,
+        w - 2. * inset,
+        h - 2. * inset,
+        band * 2.,
+        Color::new(0., 0., 0., alpha),
+      );
+    }
+  }
+}
+
+/// Whether any two active bodies, in a straight line, pass close within
+/// `days`.
+fn encounter_within(objects: &[Planet], days: f32) -> bool {
+  let active = Vec::from_iter(objects.iter().filter(|p| p.departing.is_none()));
+  for (i, a) in active.iter().enumerate() {
+    for b in &active[i + 1..] {
+      let r = (b.pos - a.pos).extend(b.z - a.z);
+      let v = (b.velocity - a.velocity).extend(b.vz - a.vz);
+      let t = -r.dot(v) / v.length_squared().max(f32::EPSILON);
+      let reach = ENCOUNTER_RADII * (a.radius(1.) + b.radius(1.));
+      if (0. ..=days).contains(&t) && (r + v * t).length() < reach {
+        return true;
+      }
+    }
+  }
+  false
+}
@@ -0,0 +1,104 @@
+//! Small live charts of total energy and angular momentum, both of which
+//! should stay flat, so integrator drift and what collisions carry off show
+//! at a glance.
+
+use macroquad::prelude::*;
+use planets_core::energy;
+use std::collections::VecDeque;
+
+use crate::camera::base_scale;
+use crate::{hidpi, Planet};
+
+/// Samples kept for each chart.
+const HISTORY: usize = 240;
+/// Steps between samples, as the energy is quadratic in the body count.
+const SAMPLE_EVERY: u32 = 5;
+const MARGIN: f32 = 10.;
+
+#[derive(Debug, Default)]
+pub struct Sparklines {
+  pub visible: bool,
+  energy: VecDeque<f64>,
+  momentum: VecDeque<f64>,
+  countdown: u32,
+}
+
+impl Sparklines {
+  /// Call once per step; only samples while shown.
+  pub fn sample(&mut self, objects: &[Planet], g: f32) {
+    if !self.visible {
+      return;
+    }
+    if self.countdown > 0 {
+      self.countdown -= 1;
+      return;
+    }
+    self.countdown = SAMPLE_EVERY - 1;
+    if self.energy.len() == HISTORY {
+      self.energy.pop_front();
+      self.momentum.pop_front();
+    }
+    self.energy.push_back(energy::total(objects, g));
+    self.momentum.push_back(energy::angular_momentum(objects));
+  }
+
+  /// For a new system, which has nothing to do with the old one's.
+  pub fn clear(&mut self) {
+    self.energy.clear();
+    self.momentum.clear();
+  }
+
+  /// Top center, each chart labeled with the latest value and its change
+  /// since the first sample kept.
+  pub fn render(&self) {
+    if !self.visible || self.energy.is_empty() {
+      return;
+    }
+    let scale = base_scale();
+    let line = 16. * scale;
+    let (width, graph) = (HISTORY as f32 * scale, 30. * scale);
+    let corner = vec2(screen_width() / 2. - width / 2., MARGIN);
+    let charts = [
+      ("energy", &self.energy, ORANGE),
+      ("angular momentum", &self.momentum, SKYBLUE),
+    ];
+    let height = charts.len() as f32 * (line + graph + 8.) + 4.;
+    draw_rectangle(
+      corner.x - 6.,
+      corner.y,
+      width + 12.,
+      height,
+      Color::new(0., 0., 0., 0.6),
+    );
+
+    let mut y = corner.y;
+    for (label, values, color) in charts {
+      y += line;
+      let (first, last) = (values[0], values[values.len() - 1]);
+      let change = if first == 0. {
+        0.
+      } else {
+        (last - first) / first.abs() * 100.
+      };
+      let text = format!("{}  {:.4e}  {:+.4}%", label, last, change);
+      hidpi::draw_text_sharp(&text, corner.x, y, line, color);
+
+      let (low, high) = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+          (lo.min(v), hi.max(v))
+        });
+      let span = (high - low).max(f64::EPSILON * high.abs().max(1.));
+      let top = y + 4.;
+      let point = |i: usize, v: f64| {
+        let t = ((v - low) / span) as f32;
+        vec2(corner.x + i as f32 * scale, top + graph * (1. - t))
+      };
+      for (i, (&a, &b)) in values.iter().zip(values.iter().skip(1)).enumerate() {
+        let (a, b) = (point(i, a), point(i + 1, b));
+        draw_line(a.x, a.y, b.x, b.y, 1., color);
+      }
+      y = top + graph + 4.;
+    }
+  }
+}
@@ -0,0 +1,308 @@
+//! One instance simulates and broadcasts snapshots over TCP, others connect
+//! as spectators that only render, each with its own camera.
+//!
+//! A snapshot is the day, the body count and the bodies packed as `id kind
+//! mass x y z vx vy vz rgb departing rings stage progress name`, sun last,
+//! all little-endian. It goes out as a frame: a `u32` length, a byte that
+//! is 1 when the snapshot is XORed with the one before it, the snapshot's
+//! length as a `u32`, and then the bytes with every run of zeros
+//! shortened to a zero and the run's length. Most of a body stays the same
+//! from frame to frame, so deltas are mostly zeros.
+
+use macroquad::prelude::*;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+
+use crate::stellar::{Evolution, Stage};
+use crate::{BodyKind, Planet};
+
+/// Snapshots bigger than this are taken as a corrupt stream.
+const MAX_SNAPSHOT: usize = 16 * 1024 * 1024;
+
+pub struct Snapshot {
+  pub day: u64,
+  pub bodies: Vec<Planet>,
+}
+
+/// The simulating side, sending every connected spectator the latest state.
+pub struct Broadcaster {
+  listener: TcpListener,
+  clients: Vec<Client>,
+}
+
+struct Client {
+  stream: TcpStream,
+  /// Whatever part of the last frame hasn't gone out.
+  pending: Vec<u8>,
+  /// The last snapshot sent in full, the next one is sent against.
+  base: Option<Vec<u8>>,
+}
+
+impl Broadcaster {
+  pub fn bind(host: &str, port: u16) -> io::Result<Broadcaster> {
+    let listener = TcpListener::bind((host, port))?;
+    listener.set_nonblocking(true)?;
+    Ok(Broadcaster {
+      listener,
+      clients: Vec::new(),
+    })
+  }
+
+  /// Sends the state to everyone who is caught up, a slow spectator just
+  /// skips snapshots.
+  pub fn send(&mut self, objects: &[Planet], day: u64) {
+    while let Ok((stream, _)) = self.listener.accept() {
+      if stream.set_nonblocking(true).is_ok() {
+        let _ = stream.set_nodelay(true);
+        self.clients.push(Client {
+          stream,
+          pending: Vec::new(),
+          base: None,
+        });
+      }
+    }
+    if self.clients.is_empty() {
+      return;
+    }
+
+    let snapshot = encode(objects, day);
+    self.clients.retain_mut(|client| {
+      if client.pending.is_empty() {
+        client.pending = pack(&snapshot, client.base.as_deref());
+        client.base = Some(snapshot.clone());
+      }
+      match client.stream.write(&client.pending) {
+        Ok(0) => false,
+        Ok(n) => {
+          client.pending.drain(..n);
+          true
+        }
+        Err(e) => e.kind() == ErrorKind::WouldBlock,
+      }
+    });
+  }
+}
+
+/// The watching side.
+pub struct Spectator {
+  stream: TcpStream,
+  received: Vec<u8>,
+  /// The last snapshot, which the next may be sent against.
+  base: Option<Vec<u8>>,
+}
+
+impl Spectator {
+  pub fn connect(addr: &str) -> io::Result<Spectator> {
+    let stream = TcpStream::connect(addr)?;
+    stream.set_nonblocking(true)?;
+    Ok(Spectator {
+      stream,
+      received: Vec::new(),
+      base: None,
+    })
+  }
+
+  /// The newest complete snapshot received since the last call, or an
+  /// error once the connection is gone.
+  pub fn poll(&mut self) -> Result<Option<Snapshot>, String> {
+    let mut buf = [0; 16 * 1024];
+    loop {
+      match self.stream.read(&mut buf) {
+        Ok(0) => return Err("host closed the connection".to_owned()),
+        Ok(n) => self.received.extend(&buf[..n]),
+        Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+        Err(e) => return Err(e.to_string()),
+      }
+    }
+
+    let mut latest = None;
+    while let Some(len) = self.received.get(..4) {
+      let len = u32::from_le_bytes(len.try_into().unwrap()) as usize;
+      if len > MAX_SNAPSHOT {
+        return Err("corrupt snapshot stream".to_owned());
+      }
+      if self.received.len() < 4 + len {
+        break;
+      }
+      let frame = Vec::from_iter(self.received.drain(..4 + len).skip(4));
+      let snapshot = unpack(&frame, self.base.as_deref()).ok_or("corrupt snapshot")?;
+      latest = Some(decode(&snapshot).ok_or("corrupt snapshot")?);
+      self.base = Some(snapshot);
+    }
+    Ok(latest)
+  }
+}
+
+/// Brings the local bodies in line with a snapshot, keeping the trails
+/// drawn so far and growing them by the new positions.
+pub fn apply(objects: &mut Vec<Planet>, snapshot: Snapshot, trail_budget: usize) {
+  let sun_pos = snapshot.bodies.last().map_or(Vec2::ZERO, |p| p.pos);
+  let share = crate::trail_share(&snapshot.bodies, trail_budget);
+  let mut previous = std::mem::take(objects);
+  for mut body in snapshot.bodies {
+    if let Some(i) = previous.iter().position(|p| p.id == body.id) {
+      body.trail = std::mem::take(&mut previous[i].trail);
+      body.eclipsed = previous[i].eclipsed;
+      body.trail_length = previous[i].trail_length;
+    }
+    body.record_trail(&sun_pos, share);
+    objects.push(body);
+  }
+}
+
+fn encode(objects: &[Planet], day: u64) -> Vec<u8> {
+  let mut data = Vec::from(day.to_le_bytes());
+  data.extend((objects.len() as u32).to_le_bytes());
+  for p in objects {
+    let [r, g, b, _]: [u8; 4] = p.color.into();
+    data.extend(p.id.to_le_bytes());
+    data.push(p.kind as u8);
+    let state = [
+      p.mass,
+      p.pos.x,
+      p.pos.y,
+      p.z,
+      p.velocity.x,
+      p.velocity.y,
+      p.vz,
+    ];
+    for x in state {
+      data.extend(x.to_le_bytes());
+    }
+    data.extend([r, g, b]);
+    for x in [p.departing, p.rings] {
+      data.extend(x.unwrap_or(f32::NAN).to_le_bytes());
+    }
+    let (stage, progress) = p.evolution.appearance();
+    data.push(stage as u8);
+    data.extend(progress.to_le_bytes());
+    let name = &p.name.as_bytes()[..p.name.len().min(u16::MAX as usize)];
+    data.extend((name.len() as u16).to_le_bytes());
+    data.extend(name);
+  }
+  data
+}
+
+/// Frames a snapshot, as a delta against `base` when there is one of the
+/// same length.
+fn pack(snapshot: &[u8], base: Option<&[u8]>) -> Vec<u8> {
+  let base = base.filter(|b| b.len() == snapshot.len());
+  let mut frame = vec![0; 4];
+  frame.push(base.is_some() as u8);
+  frame.extend((snapshot.len() as u32).to_le_bytes());
+  let bytes = snapshot
+    .iter()
+    .enumerate()
+    .map(|(i, &b)| base.map_or(b, |base| b ^ base[i]));
+  let mut zeros = 0u8;
+  for b in bytes {
+    if b == 0 && zeros < u8::MAX {
+      zeros += 1;
+      continue;
+    }
+    if zeros > 0 {
+      frame.extend([0, zeros]);
+      zeros = 0;
+    }
+    if b == 0 {
+      zeros = 1;
+    } else {
+      frame.push(b);
+    }
+  }
+  if zeros > 0 {
+    frame.extend([0, zeros]);
+  }
+  let len = (frame.len() - 4) as u32;
+  frame[..4].copy_from_slice(&len.to_le_bytes());
+  frame
+}
+
+/// The snapshot a frame carries, without its length.
+fn unpack(frame: &[u8], base: Option<&[u8]>) -> Option<Vec<u8>> {
+  let (&delta, rest) = frame.split_first()?;
+  let len = u32::from_le_bytes(rest.get(..4)?.try_into().ok()?) as usize;
+  if len > MAX_SNAPSHOT {
+    return None;
+  }
+  let mut snapshot = Vec::with_capacity(len);
+  let mut bytes = rest[4..].iter();
+  while let Some(&b) = bytes.next() {
+    match b {
+      0 => snapshot.resize(snapshot.len() + *bytes.next()? as usize, 0),
+      b => snapshot.push(b),
+    }
+    // a corrupt stream could otherwise blow up a small frame many times over
+    if snapshot.len() > len {
+      return None;
+    }
+  }
+  if snapshot.len() != len {
+    return None;
+  }
+  if delta == 1 {
+    let base = base.filter(|b| b.len() == len)?;
+    snapshot
+      .iter_mut()
+      .zip(base)
+      .for_each(|(b, base)| *b ^= base);
+  }
+  Some(snapshot)
+}
+
+fn decode(frame: &[u8]) -> Option<Snapshot> {
+  let mut rest = frame;
+  let mut take = |n: usize| {
+    let (head, tail) = (rest.get(..n)?, rest.get(n..)?);
+    rest = tail;
+    Some(head)
+  };
+  let day = u64::from_le_bytes(take(8)?.try_into().ok()?);
+  let count = u32::from_le_bytes(take(4)?.try_into().ok()?);
+  let mut bodies = Vec::new();
+  for _ in 0..count {
+    let id = u32::from_le_bytes(take(4)?.try_into().ok()?);
+    let kind = match take(1)?[0] {
+      0 => BodyKind::Planet,
+      1 => BodyKind::Star,
+      _ => BodyKind::BlackHole,
+    };
+    let mut float = || Some(f32::from_le_bytes(take(4)?.try_into().ok()?));
+    let mut state = [0.; 7];
+    for x in state.iter_mut() {
+      *x = float()?;
+    }
+    let [mass, x, y, z, vx, vy, vz] = state;
+    let rgb = take(3)?;
+    let color = Color::from_rgba(rgb[0], rgb[1], rgb[2], 255);
+    let mut optional = || {
+      let x = f32::from_le_bytes(take(4)?.try_into().ok()?);
+      Some((!x.is_nan()).then_some(x))
+    };
+    let (departing, rings) = (optional()?, optional()?);
+    let stage = match take(1)?[0] {
+      0 => Stage::MainSequence,
+      1 => Stage::RedGiant,
+      _ => Stage::Remnant,
+    };
+    let progress = f32::from_le_bytes(take(4)?.try_into().ok()?);
+    let len = u16::from_le_bytes(take(2)?.try_into().ok()?) as usize;
+    let name = String::from_utf8_lossy(take(len)?).into_owned();
+    bodies.push(Planet {
+      id,
+      name,
+      kind,
+      mass,
+      pos: vec2(x, y),
+      velocity: vec2(vx, vy),
+      z,
+      vz,
+      color,
+      departing,
+      rings,
+      evolution: Evolution::seen(stage, progress),
+      ..Default::default()
+    });
+  }
+  Some(Snapshot { day, bodies })
+}
@@ -0,0 +1,76 @@
+//! Vertical split screen: the main camera keeps the left half, and a second
+//! camera with its own pan and zoom follows another body on the right.
+
+use macroquad::prelude::*;
+
+use crate::camera::Camera;
+use crate::hidpi::{self, dpi_scale};
+use crate::Planet;
+
+#[derive(Debug)]
+pub struct Split {
+  pub camera: Camera,
+  /// Body the right half follows.
+  pub follow: u32,
+}
+
+impl Split {
+  pub fn new(follow: u32) -> Self {
+    Split {
+      camera: Camera::default(),
+      follow,
+    }
+  }
+
+  /// The followed body, or the sun once it's gone.
+  pub fn followed<'a>(&self, objects: &'a [Planet]) -> Option<&'a Planet> {
+    objects
+      .iter()
+      .find(|p| p.id == self.follow)
+      .or(objects.last())
+  }
+
+  /// Where the right camera is centered before its own pan.
+  pub fn follow_pos(&self, objects: &[Planet]) -> Vec2 {
+    self.followed(objects).map_or(Vec2::ZERO, |p| p.pos)
+  }
+
+  /// Restricts drawing to the right half, cleared to `background`. Drawing
+  /// keeps using screen coordinates.
+  pub fn begin(&self, background: Color) {
+    let area = right();
+    let dpi = dpi_scale();
+    let mut camera = Camera2D::from_display_rect(area);
+    // GL counts viewport rows from the bottom, the full height makes that moot
+    camera.viewport = Some((
+      (area.x * dpi) as i32,
+      0,
+      (area.w * dpi) as i32,
+      (area.h * dpi) as i32,
+    ));
+    set_camera(&camera);
+    draw_rectangle(area.x, area.y, area.w, area.h, background);
+  }
+
+  /// Back to the whole screen, with a line between the halves.
+  pub fn finish(&self, objects: &[Planet]) {
+    set_default_camera();
+    let area = right();
+    draw_line(area.x, 0., area.x, area.h, 1., GRAY);
+    let label = self.followed(objects).map_or("", |p| p.name.as_str());
+    hidpi::draw_text_sharp(label, area.x + 10., 24., 20., LIGHTGRAY);
+  }
+}
+
+pub fn left() -> Rect {
+  Rect::new(0., 0., screen_width() / 2., screen_height())
+}
+
+pub fn right() -> Rect {
+  Rect::new(
+    screen_width() / 2.,
+    0.,
+    screen_width() / 2.,
+    screen_height(),
+  )
+}
@@ -0,0 +1,132 @@
+use ::rand::{self, distributions::WeightedIndex, prelude::Distribution, Rng};
+use macroquad::prelude::*;
+
+use crate::camera::{base_scale, View};
+use crate::config::StarConfig;
+use crate::hidpi;
+
+/// Fraction of camera motion each depth layer follows, far to near.
+const LAYER_PARALLAX: [f32; 3] = [0.005, 0.015, 0.04];
+/// Star size multiplier per depth layer, far to near.
+const LAYER_SIZE: [f32; 3] = [0.7, 1., 1.3];
+/// Smallest and largest star radius before screen scaling.
+const MIN_MAGNITUDE: f32 = 0.3;
+const MAX_MAGNITUDE: f32 = 2.5;
+
+/// Temperature range in kelvin and share of the naked-eye sky for the
+/// O, B, A, F, G, K and M classes.
+const SPECTRAL_CLASSES: [(f32, f32, f32); 7] = [
+  (30000., 40000., 0.005),
+  (10000., 30000., 0.1),
+  (7500., 10000., 0.2),
+  (6000., 7500., 0.15),
+  (5200., 6000., 0.15),
+  (3700., 5200., 0.25),
+  (2400., 3700., 0.145),
+];
+
+/// Twinkle rate range, radians per second.
+const TWINKLE_FREQUENCY: std::ops::RangeInclusive<f32> = 0.5..=3.;
+/// How far brightness dips at the bottom of a twinkle.
+const TWINKLE_DEPTH: f32 = 0.4;
+
+pub struct Star {
+  /// Position in -1..1 screen-relative coordinates.
+  pos: Vec2,
+  magnitude: f32,
+  color: Color,
+  layer: usize,
+  phase: f32,
+  frequency: f32,
+}
+
+impl Star {
+  /// `tint` overrides the spectral or white star color.
+  pub fn new(config: &StarConfig, tint: Option<Color>) -> Self {
+    let mut rng = rand::thread_rng();
+
+    // Pareto distribution: many faint stars, a handful of bright ones.
+    let u: f32 = rng.gen_range(f32::EPSILON..=1.);
+    let magnitude = (MIN_MAGNITUDE * u.powf(-1. / config.magnitude_exponent)).min(MAX_MAGNITUDE);
+
+    let color = if let Some(tint) = tint {
+      tint
+    } else if config.spectral {
+      let weights = SPECTRAL_CLASSES.iter().map(|&(_, _, share)| share);
+      let class = WeightedIndex::new(weights).unwrap().sample(&mut rng);
+      let (cold, hot, _) = SPECTRAL_CLASSES[class];
+      blackbody_color(rng.gen_range(cold..hot))
+    } else {
+      WHITE
+    };
+
+    Star {
+      pos: Vec2 {
+        x: rng.gen_range(-1.0..1.0),
+        y: rng.gen_range(-1.0..1.0),
+      },
+      magnitude,
+      color,
+      layer: rng.gen_range(0..LAYER_PARALLAX.len()),
+      phase: rng.gen_range(0.0..std::f32::consts::TAU),
+      frequency: rng.gen_range(TWINKLE_FREQUENCY),
+    }
+  }
+
+  pub fn render(&self, view: &View) {
+    let scale = base_scale();
+
+    let (w, h) = (screen_width(), screen_height());
+    let depth = LAYER_PARALLAX[self.layer];
+    // nearer layers spread out a little more when zooming in
+    let spread = view.zoom().powf(depth * 5.);
+    let shift = view.zero * view.scale * depth;
+    let p = vec2(w, h) / 2. + self.pos * vec2(w, h) / 2. * spread - shift;
+
+    let wave = (get_time() as f32 * self.frequency + self.phase).sin() * 0.5 + 0.5;
+    let brightness = 1. - TWINKLE_DEPTH * wave;
+
+    let (x, y) = (p.x.rem_euclid(w), p.y.rem_euclid(h));
+    let radius = self.magnitude * scale * LAYER_SIZE[self.layer];
+    let color = Color {
+      a: brightness,
+      ..self.color
+    };
+
+    // Sub-pixel circles smear into grey blobs, draw those as one crisp pixel.
+    let pixel = hidpi::pixel();
+    if radius * 2. < pixel * 1.5 {
+      draw_rectangle(hidpi::snap(x), hidpi::snap(y), pixel, pixel, color);
+    } else {
+      draw_circle(x, y, radius, color);
+    }
+  }
+}
+
+/// Approximate sRGB color of a black body, after Tanner Helland's fit.
+fn blackbody_color(kelvin: f32) -> Color {
+  let t = kelvin / 100.;
+  let r = if t <= 66. {
+    255.
+  } else {
+    329.7 * (t - 60.).powf(-0.1332)
+  };
+  let g = if t <= 66. {
+    99.47 * t.ln() - 161.12
+  } else {
+    288.12 * (t - 60.).powf(-0.0755)
+  };
+  let b = if t >= 66. {
+    255.
+  } else if t <= 19. {
+    0.
+  } else {
+    138.52 * (t - 10.).ln() - 305.04
+  };
+  Color::new(
+    r.clamp(0., 255.) / 255.,
+    g.clamp(0., 255.) / 255.,
+    b.clamp(0., 255.) / 255.,
+    1.,
+  )
+}
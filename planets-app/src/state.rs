@@ -0,0 +1,190 @@
+//! Window and session state remembered between runs, unlike the config
+//! which is only ever written by the user.
+
+use macroquad::prelude::*;
+use std::{fs, io};
+
+use crate::config::parse_into;
+use crate::grid::Grid;
+use crate::heatmap::Heatmap;
+use crate::{OtherTrails, VIRTUAL_HEIGHT, VIRTUAL_WIDTH};
+
+pub const STATE_PATH: &str = "planets.state";
+
+/// Window and runtime settings at the end of the last run.
+///
+/// The window position isn't kept, miniquad has no way to read or set it.
+#[derive(Debug, Clone)]
+pub struct State {
+  /// Last windowed size, kept while fullscreen so leaving it restores the
+  /// window.
+  pub window_width: i32,
+  pub window_height: i32,
+  pub fullscreen: bool,
+  /// Index into `TIME_SCALES`.
+  pub time_scale: usize,
+  pub camera_offset: Vec2,
+  pub camera_zoom: f32,
+  pub trail_by_speed: bool,
+  pub rim_light: bool,
+  pub terminator: bool,
+  pub shadows: bool,
+  pub heatmap: Option<Heatmap>,
+  pub grid: Option<Grid>,
+  pub other_trails: OtherTrails,
+  pub show_hud: bool,
+  pub show_minimap: bool,
+  pub show_ghosts: bool,
+  /// Each of these is only kept once changed at runtime, so until then the
+  /// config's value holds.
+  pub theme: Option<String>,
+  pub trail_budget: Option<usize>,
+  /// Path of the last `.system` file loaded, reopened at launch.
+  pub scenario: Option<String>,
+}
+
+impl Default for State {
+  fn default() -> Self {
+    State {
+      window_width: VIRTUAL_WIDTH as i32,
+      window_height: VIRTUAL_HEIGHT as i32,
+      fullscreen: false,
+      time_scale: 0,
+      camera_offset: Vec2::ZERO,
+      camera_zoom: 1.,
+      trail_by_speed: false,
+      rim_light: true,
+      terminator: true,
+      shadows: true,
+      heatmap: None,
+      grid: None,
+      other_trails: OtherTrails::Shown,
+      show_hud: false,
+      show_minimap: true,
+      show_ghosts: false,
+      theme: None,
+      trail_budget: None,
+      scenario: None,
+    }
+  }
+}
+
+impl State {
+  /// Saved state, the defaults when there is none or it can't be read.
+  pub fn load() -> Self {
+    let mut state = State::default();
+    let Ok(text) = fs::read_to_string(STATE_PATH) else {
+      return state;
+    };
+    for line in text.lines() {
+      if let Some((key, value)) = line.split_once('=') {
+        if let Err(e) = state.set(key.trim(), value.trim()) {
+          eprintln!("{}: {}", STATE_PATH, e);
+        }
+      }
+    }
+    state
+  }
+
+  pub fn save(&self) -> io::Result<()> {
+    let mut lines = vec![
+      ("window.width", self.window_width.to_string()),
+      ("window.height", self.window_height.to_string()),
+      ("window.fullscreen", self.fullscreen.to_string()),
+      ("time_scale", self.time_scale.to_string()),
+      ("camera.x", self.camera_offset.x.to_string()),
+      ("camera.y", self.camera_offset.y.to_string()),
+      ("camera.zoom", self.camera_zoom.to_string()),
+      ("render.trail_by_speed", self.trail_by_speed.to_string()),
+      ("render.rim_light", self.rim_light.to_string()),
+      ("render.terminator", self.terminator.to_string()),
+      ("render.shadows", self.shadows.to_string()),
+      (
+        "render.heatmap",
+        self.heatmap.map_or("off", Heatmap::label).to_owned(),
+      ),
+      (
+        "render.grid",
+        self.grid.map_or("off", Grid::label).to_owned(),
+      ),
+      ("render.other_trails", self.other_trails.label().to_owned()),
+      ("show.hud", self.show_hud.to_string()),
+      ("show.minimap", self.show_minimap.to_string()),
+      ("show.ghosts", self.show_ghosts.to_string()),
+    ];
+    lines.extend(self.theme.clone().map(|t| ("theme", t)));
+    lines.extend(
+      self
+        .trail_budget
+        .map(|n| ("physics.trail_budget", n.to_string())),
+    );
+    lines.extend(self.scenario.clone().map(|p| ("scenario", p)));
+    let text = String::from_iter(lines.into_iter().map(|(k, v)| format!("{} = {}\n", k, v)));
+    fs::write(STATE_PATH, text)
+  }
+
+  /// Records the current window size, unless it is the fullscreen one.
+  pub fn track_window(&mut self) {
+    if !self.fullscreen {
+      self.window_width = screen_width() as i32;
+      self.window_height = screen_height() as i32;
+    }
+  }
+
+  fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+    match key {
+      "window.width" => parse_into(&mut self.window_width, value),
+      "window.height" => parse_into(&mut self.window_height, value),
+      "window.fullscreen" => parse_into(&mut self.fullscreen, value),
+      "time_scale" => parse_into(&mut self.time_scale, value),
+      "camera.x" => parse_into(&mut self.camera_offset.x, value),
+      "camera.y" => parse_into(&mut self.camera_offset.y, value),
+      "camera.zoom" => parse_into(&mut self.camera_zoom, value),
+      "render.trail_by_speed" => parse_into(&mut self.trail_by_speed, value),
+      "render.rim_light" => parse_into(&mut self.rim_light, value),
+      "render.terminator" => parse_into(&mut self.terminator, value),
+      "render.shadows" => parse_into(&mut self.shadows, value),
+      "render.heatmap" => parse_mode(&mut self.heatmap, value, Heatmap::from_label),
+      "render.grid" => parse_mode(&mut self.grid, value, Grid::from_label),
+      "render.other_trails" => OtherTrails::from_label(value)
+        .map(|t| self.other_trails = t)
+        .ok_or_else(|| format!("invalid value `{}`", value)),
+      "show.hud" => parse_into(&mut self.show_hud, value),
+      "show.minimap" => parse_into(&mut self.show_minimap, value),
+      "show.ghosts" => parse_into(&mut self.show_ghosts, value),
+      "theme" => {
+        self.theme = Some(value.to_owned());
+        Ok(())
+      }
+      "physics.trail_budget" => {
+        let mut n = 0;
+        parse_into(&mut n, value)?;
+        self.trail_budget = Some(n);
+        Ok(())
+      }
+      "scenario" => {
+        self.scenario = Some(value.to_owned());
+        Ok(())
+      }
+      _ => Err(format!("unknown key `{}`", key)),
+    }
+  }
+}
+
+/// Parses `off` or a mode label.
+fn parse_mode<T>(
+  field: &mut Option<T>,
+  value: &str,
+  from_label: fn(&str) -> Option<T>,
+) -> Result<(), String> {
+  *field = match value {
+    "off" => None,
+    _ => Some(from_label(value).ok_or_else(|| format!("invalid value `{}`", value))?),
+  };
+  Ok(())
+}
+
+pub fn set_fullscreen(fullscreen: bool) {
+  // SAFETY: only asks the window to change mode, no GL state is touched.
+  unsafe { get_internal_gl().quad_context.set_fullscreen(fullscreen) }
+}
@@ -0,0 +1,152 @@
+//! Stars losing mass to their wind and ending their lives, on a timescale
+//! far faster than real ones so orbits visibly widen as a sun lightens.
+
+use macroquad::prelude::*;
+
+use crate::{BodyKind, Planet, SUN_MASS};
+
+/// Share of its mass a star blows away over the main sequence.
+const MAIN_SEQUENCE_LOSS: f32 = 0.1;
+/// Length of the red giant stage, relative to the main sequence.
+const GIANT_SHARE: f32 = 0.1;
+/// Share of its mass a star blows away as a red giant.
+const GIANT_LOSS: f32 = 0.3;
+/// Radius of a red giant at its largest, relative to the star it was.
+const GIANT_SIZE: f32 = 5.;
+const GIANT_COLOR: Color = Color::new(0.95, 0.3, 0.15, 1.);
+/// Share of the mass the collapse leaves in a white dwarf.
+const DWARF_MASS: f32 = 0.6;
+const DWARF_SIZE: f32 = 0.3;
+const DWARF_COLOR: Color = Color::new(0.85, 0.9, 1., 1.);
+/// Stars born at least this heavy end as a supernova instead.
+const SUPERNOVA_MASS: f32 = 2. * SUN_MASS;
+/// Share of the mass a supernova leaves in the black hole.
+const REMNANT_MASS: f32 = 0.3;
+/// Main sequence lifetimes fall steeply with mass.
+const LIFETIME_EXPONENT: f32 = 2.5;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+  #[default]
+  MainSequence,
+  RedGiant,
+  /// A white dwarf, or a black hole after a supernova.
+  Remnant,
+}
+
+/// Where a star is in its life, filled in on its first step.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Evolution {
+  stage: Stage,
+  /// Days since the star was born, or since evolution was turned on.
+  age: f32,
+  /// Through the current stage, from 0 to 1.
+  progress: f32,
+  birth_mass: f32,
+  birth_color: Color,
+}
+
+impl Evolution {
+  /// Radius relative to a main sequence star of the same mass.
+  pub fn size(&self) -> f32 {
+    match self.stage {
+      Stage::MainSequence => 1.,
+      Stage::RedGiant => 1. + (GIANT_SIZE - 1.) * self.progress,
+      Stage::Remnant => DWARF_SIZE,
+    }
+  }
+
+  /// Still blowing mass away, so the system's energy keeps changing.
+  pub fn losing_mass(&self) -> bool {
+    self.stage != Stage::Remnant
+  }
+
+  /// Stage and progress through it, all it takes to draw the star.
+  #[cfg(feature = "net")]
+  pub fn appearance(&self) -> (Stage, f32) {
+    (self.stage, self.progress)
+  }
+
+  /// A star that looks the part, for spectators, which never age it.
+  #[cfg(feature = "net")]
+  pub fn seen(stage: Stage, progress: f32) -> Evolution {
+    Evolution {
+      stage,
+      progress,
+      ..Default::default()
+    }
+  }
+}
+
+/// A star moving on to the next stage of its life.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+  RedGiant,
+  WhiteDwarf,
+  Supernova,
+}
+
+impl Transition {
+  pub fn describe(&self, name: &str) -> String {
+    match self {
+      Transition::RedGiant => format!("{} swells into a red giant", name),
+      Transition::WhiteDwarf => format!("{} collapses into a white dwarf", name),
+      Transition::Supernova => format!("{} goes supernova", name),
+    }
+  }
+}
+
+/// Ages a star by `dt` days, where a star of the sun's mass spends
+/// `lifetime` days on the main sequence.
+pub fn evolve(star: &mut Planet, lifetime: f32, dt: f32) -> Option<Transition> {
+  let evolution = &mut star.evolution;
+  if evolution.birth_mass == 0. {
+    evolution.birth_mass = star.mass;
+    evolution.birth_color = star.color;
+  }
+  let main_sequence = lifetime * (SUN_MASS / evolution.birth_mass).powf(LIFETIME_EXPONENT);
+  let giant = main_sequence * GIANT_SHARE;
+  evolution.age += dt;
+
+  match evolution.stage {
+    Stage::MainSequence => {
+      star.mass -= evolution.birth_mass * MAIN_SEQUENCE_LOSS * dt / main_sequence;
+      evolution.progress = evolution.age / main_sequence;
+      if evolution.progress < 1. {
+        return None;
+      }
+      evolution.stage = Stage::RedGiant;
+      evolution.progress = 0.;
+      Some(Transition::RedGiant)
+    }
+    Stage::RedGiant => {
+      star.mass -= evolution.birth_mass * GIANT_LOSS * dt / giant;
+      evolution.progress = ((evolution.age - main_sequence) / giant).min(1.);
+      star.color = mix(evolution.birth_color, GIANT_COLOR, evolution.progress);
+      if evolution.progress < 1. {
+        return None;
+      }
+      evolution.stage = Stage::Remnant;
+      if evolution.birth_mass >= SUPERNOVA_MASS {
+        star.mass *= REMNANT_MASS;
+        star.kind = BodyKind::BlackHole;
+        star.color = BLACK;
+        Some(Transition::Supernova)
+      } else {
+        star.mass *= DWARF_MASS;
+        star.color = DWARF_COLOR;
+        Some(Transition::WhiteDwarf)
+      }
+    }
+    Stage::Remnant => None,
+  }
+}
+
+fn mix(a: Color, b: Color, t: f32) -> Color {
+  Color::new(
+    a.r + (b.r - a.r) * t,
+    a.g + (b.g - a.g) * t,
+    a.b + (b.b - a.b) * t,
+    a.a,
+  )
+}
@@ -0,0 +1,24 @@
+//! Output meant to be laid over other video, as in a stream: a flat key
+//! color for OBS to cut out, and snapshots with real transparency.
+
+use macroquad::prelude::*;
+
+use crate::exposure;
+use crate::hidpi::physical_screen_size;
+
+/// Draws into an offscreen target cleared to full transparency, and saves
+/// it as a PNG with its alpha channel. Returns the file name.
+pub fn snapshot(draw: impl FnOnce()) -> String {
+  let (width, height) = physical_screen_size();
+  let target = render_target(width.max(1), height.max(1));
+  let mut camera = Camera2D::from_display_rect(Rect::new(0., 0., screen_width(), screen_height()));
+  camera.render_target = Some(target);
+  set_camera(&camera);
+  clear_background(Color::new(0., 0., 0., 0.));
+  draw();
+  set_default_camera();
+
+  let path = exposure::save_png(target.texture, "snapshot");
+  target.delete();
+  path
+}
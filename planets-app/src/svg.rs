@@ -0,0 +1,73 @@
+use macroquad::prelude::*;
+use std::fmt::Write as _;
+use std::{fs, io};
+
+use crate::Planet;
+
+/// Padding around the exported drawing, in world units.
+const MARGIN: f32 = 50.;
+
+/// Writes the current trails and bodies as vector art, in world units.
+pub fn export(objects: &[Planet], path: &str) -> io::Result<()> {
+  let points = objects
+    .iter()
+    .flat_map(|p| p.trail.iter().map(|t| t.pos).chain([p.pos]));
+  let (min, max) = points.fold(
+    (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+    |(min, max), p| (min.min(p), max.max(p)),
+  );
+  let (min, size) = if min.x.is_finite() {
+    (min - MARGIN, max - min + MARGIN * 2.)
+  } else {
+    (Vec2::ZERO, Vec2::ONE)
+  };
+
+  let mut svg = String::new();
+  let _ = writeln!(
+    svg,
+    r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+    min.x, min.y, size.x, size.y
+  );
+  let _ = writeln!(
+    svg,
+    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="black"/>"#,
+    min.x, min.y, size.x, size.y
+  );
+
+  for p in objects.iter() {
+    let color = hex(p.color);
+    // one line per segment, so each can fade like it does on screen
+    let segments = Vec::from_iter(p.trail.iter().step_by(2).map(|t| t.pos));
+    let len = segments.len().saturating_sub(1);
+    for (i, (a, b)) in segments.iter().zip(segments.iter().skip(1)).enumerate() {
+      let fade = (len - i) as f32 / len as f32;
+      let _ = writeln!(
+        svg,
+        r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="{}" stroke-opacity="{:.3}" stroke-width="{:.2}" stroke-linecap="round"/>"#,
+        a.x,
+        a.y,
+        b.x,
+        b.y,
+        color,
+        fade,
+        3. * fade
+      );
+    }
+    let _ = writeln!(
+      svg,
+      r#"<circle cx="{:.2}" cy="{:.2}" r="{:.2}" fill="{}"/>"#,
+      p.pos.x,
+      p.pos.y,
+      p.radius(1.),
+      color
+    );
+  }
+  svg.push_str("</svg>\n");
+
+  fs::write(path, svg)
+}
+
+fn hex(c: Color) -> String {
+  let [r, g, b, _]: [u8; 4] = c.into();
+  format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
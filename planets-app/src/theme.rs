@@ -0,0 +1,108 @@
+use macroquad::prelude::*;
+use std::str::FromStr;
+
+use crate::config::ThemeConfig;
+
+/// How trails are drawn behind bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrailStyle {
+  /// Thinning and fading out towards the tail.
+  Fading,
+  Solid,
+  Dotted,
+}
+
+impl FromStr for TrailStyle {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "fading" => Ok(TrailStyle::Fading),
+      "solid" => Ok(TrailStyle::Solid),
+      "dotted" => Ok(TrailStyle::Dotted),
+      _ => Err("expected fading, solid or dotted".to_owned()),
+    }
+  }
+}
+
+/// Scene colors and styles, picked by name in the config.
+#[derive(Debug, Clone)]
+pub struct Theme {
+  pub background: Color,
+  /// Paints every background star in one color instead of by spectral class.
+  pub star_color: Option<Color>,
+  pub nebula: bool,
+  pub trail: TrailStyle,
+  /// Colors random planets are drawn from, fully random when empty.
+  pub palette: Vec<Color>,
+}
+
+/// Color from a `0xRRGGBB` literal.
+pub fn hex_color(rgb: u32) -> Color {
+  Color::from_rgba((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8, 255)
+}
+
+/// Parses `#rrggbb`.
+pub fn parse_hex_color(s: &str) -> Result<Color, String> {
+  s.strip_prefix('#')
+    .filter(|hex| hex.len() == 6)
+    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+    .map(hex_color)
+    .ok_or_else(|| format!("invalid color `{}`, expected #rrggbb", s))
+}
+
+/// Okabe and Ito's palette, distinguishable with the common forms of color
+/// blindness.
+const OKABE_ITO: [u32; 7] = [
+  0xE69F00, 0x56B4E9, 0x009E73, 0xF0E442, 0x0072B2, 0xD55E00, 0xCC79A7,
+];
+
+/// Ink colors that stay readable on the light paper background.
+const INKS: [u32; 6] = [0x1f3a5f, 0x8c2f39, 0x2d6a4f, 0x6d597a, 0xb5651d, 0x3d405b];
+
+impl Theme {
+  pub fn named(name: &str) -> Option<Theme> {
+    match name {
+      "dark" => Some(Theme {
+        background: BLACK,
+        star_color: None,
+        nebula: true,
+        trail: TrailStyle::Fading,
+        palette: vec![],
+      }),
+      "paper" => Some(Theme {
+        background: hex_color(0xf4f1e8),
+        star_color: Some(hex_color(0x9a968c)),
+        nebula: false,
+        trail: TrailStyle::Solid,
+        palette: Vec::from_iter(INKS.map(hex_color)),
+      }),
+      "colorblind" => Some(Theme {
+        background: BLACK,
+        star_color: None,
+        nebula: true,
+        trail: TrailStyle::Fading,
+        palette: Vec::from_iter(OKABE_ITO.map(hex_color)),
+      }),
+      _ => None,
+    }
+  }
+
+  /// The named theme with any individual overrides applied on top.
+  pub fn from_config(config: &ThemeConfig) -> Theme {
+    let mut theme = Theme::named(&config.name).unwrap_or_else(|| {
+      eprintln!("unknown theme `{}`, using dark", config.name);
+      Theme::named("dark").unwrap()
+    });
+    if let Some(c) = config.background {
+      theme.background = c;
+    }
+    if let Some(c) = config.stars {
+      theme.star_color = Some(c);
+    }
+    if let Some(style) = config.trail {
+      theme.trail = style;
+    }
+    theme
+  }
+}
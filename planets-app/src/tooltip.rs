@@ -0,0 +1,87 @@
+use macroquad::prelude::*;
+
+use crate::chaos::Estimate;
+use crate::conics::Orbit;
+use crate::{format_day, hidpi, units, BodyKind, Planet};
+
+/// Small panel next to the cursor describing the body under it, with its
+/// orbit around `center` when it has one and how chaotic it is when it's
+/// being followed.
+pub fn render(
+  body: &Planet,
+  sun: Option<&Planet>,
+  orbit: Option<(&Orbit, &Planet)>,
+  chaos: Option<Estimate>,
+  mouse: Vec2,
+) {
+  const SIZE: f32 = 18.;
+  /// Most recent impacts listed.
+  const SCARS_LISTED: usize = 3;
+
+  let kind = match body.kind {
+    BodyKind::Planet => body.class().label(),
+    BodyKind::Star => "star",
+    BodyKind::BlackHole => "black hole",
+  };
+  let speed = body.velocity.length();
+  let km_per_s = speed as f64 * units::km_per_unit() / 86400.;
+  let mut lines = vec![
+    format!("{} ({})", body.name, kind),
+    format!(
+      "mass {:.0} ({:.2e} kg)",
+      body.mass,
+      body.mass as f64 * units::kg_per_unit()
+    ),
+    format!("speed {:.3} u/day ({:.1} km/s)", speed, km_per_s),
+  ];
+  if let Some(sun) = sun.filter(|s| s.id != body.id) {
+    let dist = body.pos.distance(sun.pos);
+    lines.push(format!(
+      "{:.0} u from the sun ({})",
+      dist,
+      units::format_distance(dist, true)
+    ));
+  }
+  if let Some((orbit, center)) = orbit {
+    lines.push(format!(
+      "{} around {}, e = {:.3}",
+      orbit.conic.label(),
+      center.name,
+      orbit.eccentricity
+    ));
+  }
+  if let Some(chaos) = chaos {
+    lines.push(format!(
+      "Lyapunov {:.2e}/day over {:.0} days, {}",
+      chaos.exponent,
+      chaos.days,
+      chaos.label()
+    ));
+  }
+  if !body.scars.is_empty() {
+    lines.push(format!("{} impacts remembered:", body.scars.len()));
+  }
+  for scar in body.scars.iter().rev().take(SCARS_LISTED) {
+    lines.push(format!(
+      "  {}: mass {:.0} at {:.2} u/day",
+      format_day(scar.day),
+      scar.mass,
+      scar.speed
+    ));
+  }
+
+  let width = lines
+    .iter()
+    .map(|l| measure_text(l, None, SIZE as u16, 1.).width)
+    .fold(0., f32::max);
+  let height = lines.len() as f32 * SIZE + 8.;
+  // keep it on screen near the right and bottom edges
+  let x = (mouse.x + 16.).min(screen_width() - width - 12.);
+  let y = (mouse.y + 16.).min(screen_height() - height - 4.);
+
+  draw_rectangle(x, y, width + 12., height, Color::new(0., 0., 0., 0.7));
+  for (i, line) in lines.iter().enumerate() {
+    let color = if i == 0 { body.color } else { LIGHTGRAY };
+    hidpi::draw_text_sharp(line, x + 6., y + SIZE * (i as f32 + 1.), SIZE, color);
+  }
+}
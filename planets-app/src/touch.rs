@@ -0,0 +1,135 @@
+use macroquad::prelude::*;
+
+use crate::camera::Camera;
+
+/// Seconds a finger has to rest before a drag aims a spawn instead of
+/// panning.
+const LONG_PRESS: f64 = 0.5;
+/// Movement in pixels still counted as a tap.
+const TAP_SLOP: f32 = 12.;
+
+/// One-shot results of a finished gesture, in screen coordinates.
+#[derive(Debug, Clone, Copy)]
+pub enum Gesture {
+  Tap(Vec2),
+  /// Long press at `at` dragged out by `drag` before letting go.
+  Spawn {
+    at: Vec2,
+    drag: Vec2,
+  },
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+enum State {
+  #[default]
+  Idle,
+  Press {
+    start: Vec2,
+    since: f64,
+  },
+  Pan {
+    last: Vec2,
+  },
+  Aim {
+    start: Vec2,
+    current: Vec2,
+  },
+  Pinch {
+    distance: f32,
+    center: Vec2,
+  },
+}
+
+/// Pinch to zoom, drag to pan, tap and long-press-drag for the rest.
+#[derive(Debug, Default)]
+pub struct TouchInput {
+  state: State,
+}
+
+impl TouchInput {
+  /// Moves the camera for pans and pinches and returns finished gestures.
+  pub fn update(&mut self, camera: &mut Camera, follow: Vec2) -> Option<Gesture> {
+    let mut touches = touches();
+    touches.sort_by_key(|t| t.id);
+
+    if let [a, b, ..] = &touches[..] {
+      let distance = a.position.distance(b.position).max(1.);
+      let center = (a.position + b.position) / 2.;
+      if let State::Pinch {
+        distance: last_distance,
+        center: last_center,
+      } = self.state
+      {
+        camera.pan(follow, center - last_center);
+        camera.zoom_at(follow, center, distance / last_distance);
+      }
+      self.state = State::Pinch { distance, center };
+      return None;
+    }
+
+    let Some(touch) = touches.first() else {
+      self.state = State::Idle;
+      return None;
+    };
+    let pos = touch.position;
+    let ended = matches!(touch.phase, TouchPhase::Ended | TouchPhase::Cancelled);
+
+    let (state, gesture) = match self.state {
+      // lifting one finger of a pinch shouldn't turn into a tap
+      State::Pinch { .. } if !ended => (State::Pan { last: pos }, None),
+      State::Idle if !ended => (
+        State::Press {
+          start: pos,
+          since: get_time(),
+        },
+        None,
+      ),
+      State::Press { start, .. } if ended => (State::Idle, Some(Gesture::Tap(start))),
+      State::Press { start, since } => {
+        if pos.distance(start) > TAP_SLOP {
+          (State::Pan { last: pos }, None)
+        } else if get_time() - since >= LONG_PRESS {
+          (
+            State::Aim {
+              start,
+              current: pos,
+            },
+            None,
+          )
+        } else {
+          (self.state, None)
+        }
+      }
+      State::Pan { last } => {
+        camera.pan(follow, pos - last);
+        (State::Pan { last: pos }, None)
+      }
+      State::Aim { start, .. } if ended => (
+        State::Idle,
+        Some(Gesture::Spawn {
+          at: start,
+          drag: pos - start,
+        }),
+      ),
+      State::Aim { start, .. } => (
+        State::Aim {
+          start,
+          current: pos,
+        },
+        None,
+      ),
+      _ => (State::Idle, None),
+    };
+    self.state = if ended { State::Idle } else { state };
+    gesture
+  }
+
+  /// Shows where a long-press spawn will land and which way it will fly.
+  pub fn render(&self) {
+    if let State::Aim { start, current } = self.state {
+      let color = Color::new(1., 1., 1., 0.7);
+      draw_circle_lines(start.x, start.y, 10., 1.5, color);
+      draw_line(start.x, start.y, current.x, current.y, 1.5, color);
+    }
+  }
+}
@@ -0,0 +1,74 @@
+//! Unattended camera that moves on to another body every few seconds,
+//! visiting whatever was just part of a collision, capture or supernova
+//! first, and going round the system by mass otherwise.
+
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+
+use crate::{orbits, Planet, VIRTUAL_HEIGHT};
+
+/// Widest and closest zoom the tour picks for a body.
+const ZOOM_RANGE: (f32, f32) = (2., 8.);
+
+#[derive(Debug)]
+pub struct Tour {
+  seconds: f32,
+  /// Time of the next move.
+  next: f64,
+  /// Bodies that took part in events since, oldest first.
+  recent: VecDeque<u32>,
+  /// Place in the round of the system by mass.
+  round: usize,
+}
+
+impl Tour {
+  /// A tour staying `seconds` at every stop, starting right away.
+  pub fn new(seconds: f32) -> Self {
+    Tour {
+      seconds: seconds.max(1.),
+      next: get_time(),
+      recent: VecDeque::new(),
+      round: 0,
+    }
+  }
+
+  /// Remembers a body that just took part in an event, to visit it next.
+  pub fn note(&mut self, id: u32) {
+    if !self.recent.contains(&id) {
+      self.recent.push_back(id);
+    }
+  }
+
+  /// The next stop once it's time to move on: a body and the zoom to show
+  /// it at.
+  pub fn update(&mut self, objects: &[Planet]) -> Option<(u32, f32)> {
+    if get_time() < self.next {
+      return None;
+    }
+    let exists = |id: u32| objects.iter().any(|p| p.id == id && p.departing.is_none());
+    self.recent.retain(|&id| exists(id));
+    let id = match self.recent.pop_front() {
+      Some(id) => id,
+      None => {
+        let mut bodies = Vec::from_iter(objects.iter().filter(|p| p.departing.is_none()));
+        bodies.sort_by(|a, b| b.mass.total_cmp(&a.mass));
+        let body = bodies.get(self.round % bodies.len().max(1))?;
+        self.round += 1;
+        body.id
+      }
+    };
+    self.next = get_time() + self.seconds as f64;
+    let body = objects.iter().find(|p| p.id == id)?;
+    Some((id, zoom_for(objects, body)))
+  }
+}
+
+/// Zoom showing a body's Hill sphere, the space its moons fit in, and the
+/// whole system around the central body.
+fn zoom_for(objects: &[Planet], body: &Planet) -> f32 {
+  let hill = orbits::hill_radius(objects, body);
+  if hill.is_infinite() {
+    return 1.;
+  }
+  (VIRTUAL_HEIGHT / (8. * hill)).clamp(ZOOM_RANGE.0, ZOOM_RANGE.1)
+}
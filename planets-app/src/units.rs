@@ -0,0 +1,37 @@
+//! Physical interpretation of simulation units.
+//!
+//! The simulation runs on `G` scaled by `SCALE_FACTOR` in arbitrary units.
+//! Reading a step as one day and the default sun as one solar mass fixes the
+//! remaining unit of length, which comes out at roughly a million kilometres.
+
+use crate::{G, SUN_MASS};
+
+const G_SI: f64 = 6.674e-11;
+const SOLAR_MASS_KG: f64 = 1.989e30;
+const SECONDS_PER_STEP: f64 = 86400.;
+
+/// Kilograms per simulation mass unit.
+pub fn kg_per_unit() -> f64 {
+  SOLAR_MASS_KG / SUN_MASS as f64
+}
+
+/// Kilometres per simulation length unit.
+pub fn km_per_unit() -> f64 {
+  // G = G_SI * M * T^2 / L^3, solved for L
+  (G_SI * kg_per_unit() * SECONDS_PER_STEP * SECONDS_PER_STEP / G as f64).cbrt() / 1000.
+}
+
+/// Human readable distance, in simulation units or converted to kilometres.
+pub fn format_distance(units: f32, km: bool) -> String {
+  if !km {
+    return format!("{} u", units);
+  }
+  let km = units as f64 * km_per_unit();
+  if km >= 1e9 {
+    format!("{:.1}B km", km / 1e9)
+  } else if km >= 1e6 {
+    format!("{:.1}M km", km / 1e6)
+  } else {
+    format!("{:.0} km", km)
+  }
+}
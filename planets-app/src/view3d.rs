@@ -0,0 +1,163 @@
+use macroquad::models::{Mesh, Vertex};
+use macroquad::prelude::*;
+
+use crate::{Planet, RenderOptions};
+
+const ORBIT_SPEED: f32 = 0.01;
+const ZOOM_STEP: f32 = 1.1;
+const MIN_DISTANCE: f32 = 100.;
+const MAX_DISTANCE: f32 = 8000.;
+/// Just short of straight down or up, where the camera's up vector flips.
+const MAX_PITCH: f32 = 1.5;
+const FOV: f32 = std::f32::consts::FRAC_PI_4;
+
+/// Trails are ribbons this many pixels wide, turned to face the camera.
+const TRAIL_WIDTH: f32 = 1.5;
+/// Trail segments per mesh, well inside what one draw call takes.
+const TRAIL_BATCH: usize = 1000;
+
+const PLANE_SLICES: u32 = 40;
+const PLANE_SPACING: f32 = 50.;
+
+/// Perspective camera orbiting the central body, an alternative to the flat
+/// top-down view.
+#[derive(Debug)]
+pub struct View3d {
+  pub enabled: bool,
+  yaw: f32,
+  /// Angle above the orbital plane.
+  pitch: f32,
+  distance: f32,
+  drag: Option<Vec2>,
+}
+
+impl Default for View3d {
+  fn default() -> Self {
+    View3d {
+      enabled: false,
+      yaw: 0.,
+      pitch: 0.6,
+      distance: 2500.,
+      drag: None,
+    }
+  }
+}
+
+impl View3d {
+  /// Left-button drag orbits, mouse wheel moves in and out.
+  pub fn handle_input(&mut self) {
+    let (_, wheel) = mouse_wheel();
+    if wheel != 0. {
+      self.distance =
+        (self.distance / ZOOM_STEP.powf(wheel.signum())).clamp(MIN_DISTANCE, MAX_DISTANCE);
+    }
+
+    let mouse = Vec2::from(mouse_position());
+    if is_mouse_button_down(MouseButton::Left) {
+      if let Some(last) = self.drag {
+        let delta = (mouse - last) * ORBIT_SPEED;
+        self.yaw -= delta.x;
+        self.pitch = (self.pitch + delta.y).clamp(-MAX_PITCH, MAX_PITCH);
+      }
+      self.drag = Some(mouse);
+    } else {
+      self.drag = None;
+    }
+  }
+
+  /// Draws bodies and trails around `target`, leaving the default camera set
+  /// for whatever is drawn on top.
+  pub fn render(&self, objects: &[Planet], target: Vec3, options: &RenderOptions) {
+    let target = to_render(target);
+    let offset = vec3(
+      self.pitch.cos() * self.yaw.sin(),
+      self.pitch.sin(),
+      self.pitch.cos() * self.yaw.cos(),
+    );
+    let camera = target + offset * self.distance;
+    set_camera(&Camera3D {
+      position: camera,
+      target,
+      up: Vec3::Y,
+      fovy: FOV,
+      ..Default::default()
+    });
+
+    let plane = Color::new(0.5, 0.5, 0.6, 0.15);
+    push_model_matrix(Mat4::from_translation(target));
+    draw_grid(PLANE_SLICES, PLANE_SPACING, plane, plane);
+    pop_model_matrix();
+
+    // world units per pixel at unit distance from the camera
+    let per_pixel = 2. * (FOV / 2.).tan() / screen_height();
+    for obj in objects {
+      draw_trail(obj, camera, per_pixel, options.trail_stride);
+      let pos = to_render(obj.pos.extend(obj.z));
+      draw_sphere(pos, obj.radius(1.), None, obj.color);
+    }
+
+    set_default_camera();
+  }
+}
+
+/// The trail as a ribbon of quads facing `camera`, the same width on screen
+/// however far away each part is.
+fn draw_trail(obj: &Planet, camera: Vec3, per_pixel: f32, stride: usize) {
+  let count = obj.trail.len();
+  let points = Vec::from_iter(obj.trail.iter().enumerate().step_by(stride).map(|(i, p)| {
+    // newest first, so the oldest end fades out
+    let mut color = obj.color;
+    color.a = (count - i) as f32 / count as f32;
+    (to_render(p.pos.extend(p.z)), color)
+  }));
+  let mut mesh = Mesh {
+    vertices: Vec::new(),
+    indices: Vec::new(),
+    texture: None,
+  };
+  for pair in points.windows(2) {
+    let [(a, a_color), (b, b_color)] = [pair[0], pair[1]];
+    let half = (b - a).cross(camera - a).normalize_or_zero()
+      * (0.5 * TRAIL_WIDTH * per_pixel * camera.distance(a));
+    let base = mesh.vertices.len() as u16;
+    let corners = [
+      (a - half, a_color),
+      (a + half, a_color),
+      (b + half, b_color),
+      (b - half, b_color),
+    ];
+    for (position, color) in corners {
+      mesh.vertices.push(Vertex {
+        position,
+        uv: Vec2::ZERO,
+        color,
+      });
+    }
+    mesh.indices.extend([0, 1, 2, 0, 2, 3].map(|k| base + k));
+    if mesh.vertices.len() >= 4 * TRAIL_BATCH {
+      draw_mesh(&mesh);
+      mesh.vertices.clear();
+      mesh.indices.clear();
+    }
+  }
+  if !mesh.vertices.is_empty() {
+    draw_mesh(&mesh);
+  }
+}
+
+/// Simulation `z` is up, which is `y` for the camera.
+fn to_render(p: Vec3) -> Vec3 {
+  vec3(p.x, p.z, p.y)
+}
+
+/// Pushes a transform for the grid, which `draw_grid` always centers on the
+/// origin.
+fn push_model_matrix(matrix: Mat4) {
+  unsafe { get_internal_gl() }
+    .quad_gl
+    .push_model_matrix(matrix);
+}
+
+fn pop_model_matrix() {
+  unsafe { get_internal_gl() }.quad_gl.pop_model_matrix();
+}
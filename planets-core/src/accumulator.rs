@@ -0,0 +1,30 @@
+use glam::Vec3;
+
+/// Running sum of many small vectors. With the `kahan` feature the rounding
+/// error of each addition is carried into the next, so thousands of faint
+/// pulls don't vanish next to one big one.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Accumulator {
+  sum: Vec3,
+  #[cfg(feature = "kahan")]
+  compensation: Vec3,
+}
+
+impl Accumulator {
+  #[cfg(not(feature = "kahan"))]
+  pub fn add(&mut self, v: Vec3) {
+    self.sum += v;
+  }
+
+  #[cfg(feature = "kahan")]
+  pub fn add(&mut self, v: Vec3) {
+    let y = v - self.compensation;
+    let t = self.sum + y;
+    self.compensation = (t - self.sum) - y;
+    self.sum = t;
+  }
+
+  pub fn sum(&self) -> Vec3 {
+    self.sum
+  }
+}
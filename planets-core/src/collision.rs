@@ -0,0 +1,101 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use glam::Vec3;
+
+use crate::Body;
+
+/// Where and how hard two bodies hit.
+#[derive(Debug, Clone, Copy)]
+pub struct Impact {
+  pub pos: Vec3,
+  /// Kinetic energy of the relative motion, lost to the merge.
+  pub energy: f32,
+}
+
+/// Two bodies touched and `absorbed` merged into the heavier `survivor`,
+/// adding its mass and momentum.
+#[derive(Debug, Clone)]
+pub struct Merge<B> {
+  pub survivor: u32,
+  pub absorbed: B,
+  pub impact: Impact,
+}
+
+/// Merges touching bodies into the heavier of each pair, keeping mass,
+/// momentum and the last body's place at the end of the list.
+pub fn merge_collisions<B: Body>(bodies: &mut Vec<B>) -> Vec<Merge<B>> {
+  let mut merges = Vec::new();
+  while let Some((i, j)) = first_touching(bodies) {
+    let (heavier, lighter) = if bodies[i].mass() >= bodies[j].mass() {
+      (i, j)
+    } else {
+      (j, i)
+    };
+    merges.push(absorb(bodies, heavier, lighter));
+  }
+  merges
+}
+
+/// Merges the body at `absorbed` into the one at `survivor` whether they
+/// touch or not, keeping mass, momentum and the last body's place at the
+/// end of the list.
+pub fn absorb<B: Body>(bodies: &mut Vec<B>, survivor: usize, absorbed: usize) -> Merge<B> {
+  let (a, b) = (&bodies[survivor], &bodies[absorbed]);
+  let mass = a.mass() + b.mass();
+  let (pos, velocity) = (
+    (a.pos() * a.mass() + b.pos() * b.mass()) / mass,
+    (a.velocity() * a.mass() + b.velocity() * b.mass()) / mass,
+  );
+  let impact = Impact {
+    pos,
+    energy: 0.5 * a.mass() * b.mass() / mass * a.velocity().distance_squared(b.velocity()),
+  };
+
+  let kept = &mut bodies[survivor];
+  kept.set_mass(mass);
+  kept.set_pos(pos);
+  kept.set_velocity(velocity);
+  let id = kept.id();
+
+  let mut gone = absorbed;
+  if absorbed == bodies.len() - 1 {
+    bodies.swap(survivor, absorbed);
+    gone = survivor;
+  }
+  Merge {
+    survivor: id,
+    absorbed: bodies.remove(gone),
+    impact,
+  }
+}
+
+/// The touching pair of active bodies that comes first in list order.
+///
+/// A uniform grid with cells as wide as the largest body sorts bodies into
+/// buckets, so only bodies in neighbouring cells are compared and thousands
+/// of small ones stay cheap. The grid only covers x and y, as systems are
+/// much flatter than they are wide.
+fn first_touching<B: Body>(bodies: &[B]) -> Option<(usize, usize)> {
+  let active = || bodies.iter().enumerate().filter(|(_, b)| b.active());
+  let cell = 2. * active().map(|(_, b)| b.radius()).fold(0., f32::max);
+  if cell <= 0. {
+    return None;
+  }
+  let key = |pos: Vec3| ((pos.x / cell).floor() as i32, (pos.y / cell).floor() as i32);
+  let mut grid: BTreeMap<(i32, i32), Vec<usize>> = BTreeMap::new();
+  for (i, b) in active() {
+    grid.entry(key(b.pos())).or_default().push(i);
+  }
+
+  active().find_map(|(i, a)| {
+    let (x, y) = key(a.pos());
+    (x - 1..=x + 1)
+      .flat_map(|x| (y - 1..=y + 1).map(move |y| (x, y)))
+      .filter_map(|c| grid.get(&c))
+      .flatten()
+      .filter(|&&j| j > i)
+      .filter(|&&j| a.pos().distance(bodies[j].pos()) < a.radius() + bodies[j].radius())
+      .min()
+      .map(|&j| (i, j))
+  })
+}
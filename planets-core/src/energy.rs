@@ -0,0 +1,141 @@
+//! Total energy and angular momentum bookkeeping, and an optional nudge that
+//! holds the energy constant against integrator drift.
+
+use alloc::vec::Vec;
+use glam::{DVec3, Vec3};
+
+use crate::Body;
+
+/// Largest velocity rescale of a single correction, so a violent close
+/// encounter is smoothed over many passes instead of undone at once.
+const MAX_CORRECTION: f64 = 0.01;
+
+/// Kinetic plus potential energy of the active bodies, summed in f64
+/// since the terms are large and of opposite sign.
+pub fn total<B: Body>(objects: &[B], g: f32) -> f64 {
+  let active = Vec::from_iter(objects.iter().filter(|p| p.active()));
+  kinetic(&active) + potential(&active, g)
+}
+
+/// Size of the angular momentum of the active bodies around their
+/// barycenter, summed in f64 like the energy.
+pub fn angular_momentum<B: Body>(objects: &[B]) -> f64 {
+  let active = Vec::from_iter(objects.iter().filter(|p| p.active()));
+  let mass: f32 = active.iter().map(|p| p.mass()).sum();
+  if mass <= 0. {
+    return 0.;
+  }
+  let center = active
+    .iter()
+    .fold(Vec3::ZERO, |sum, p| sum + p.pos() * p.mass())
+    / mass;
+  let drift = active
+    .iter()
+    .fold(Vec3::ZERO, |sum, p| sum + p.velocity() * p.mass())
+    / mass;
+  let total = active.iter().fold(DVec3::ZERO, |sum, p| {
+    let l = (p.pos() - center).cross(p.velocity() - drift) * p.mass();
+    sum + l.as_dvec3()
+  });
+  total.length()
+}
+
+fn kinetic<B: Body>(objects: &[&B]) -> f64 {
+  objects
+    .iter()
+    .map(|p| 0.5 * p.mass() as f64 * p.velocity().length_squared() as f64)
+    .sum()
+}
+
+fn potential<B: Body>(objects: &[&B], g: f32) -> f64 {
+  let mut sum = 0.;
+  for (i, a) in objects.iter().enumerate() {
+    for b in &objects[i + 1..] {
+      let dist = a.pos().distance(b.pos()) as f64;
+      if dist > 0. {
+        sum -= g as f64 * a.mass() as f64 * b.mass() as f64 / dist;
+      }
+    }
+  }
+  sum
+}
+
+/// Rescales velocities every `every` steps to bring total energy back to
+/// what it was at the start.
+#[derive(Debug)]
+pub struct Drift {
+  every: u32,
+  countdown: u32,
+  /// Energy to hold, with the body count and G it was measured for; a
+  /// change to either starts over from the new energy.
+  reference: Option<(f64, usize, f32)>,
+}
+
+impl Drift {
+  /// Zero `every` disables correction.
+  pub fn new(every: u32) -> Self {
+    Drift {
+      every,
+      countdown: every,
+      reference: None,
+    }
+  }
+
+  /// Forgets the reference energy, for deliberate changes to the system.
+  pub fn reset(&mut self) {
+    self.reference = None;
+  }
+
+  /// Call once per step; returns the velocity scale when it corrected.
+  pub fn update<B: Body>(&mut self, objects: &mut [B], g: f32) -> Option<f32> {
+    if self.every == 0 {
+      return None;
+    }
+    let count = objects.iter().filter(|p| p.active()).count();
+    let target = match self.reference {
+      Some((e, len, ref_g)) if len == count && ref_g == g => e,
+      _ => {
+        self.reference = Some((total(objects, g), count, g));
+        self.countdown = self.every;
+        return None;
+      }
+    };
+    self.countdown -= 1;
+    if self.countdown > 0 {
+      return None;
+    }
+    self.countdown = self.every;
+    let energy = total(objects, g);
+
+    // only motion relative to the barycenter is scaled, so momentum stays
+    let mut active = Vec::from_iter(objects.iter_mut().filter(|p| p.active()));
+    let mass: f32 = active.iter().map(|p| p.mass()).sum();
+    let momentum = active
+      .iter()
+      .fold(Vec3::ZERO, |sum, p| sum + p.velocity() * p.mass());
+    let drift = if mass > 0. {
+      momentum / mass
+    } else {
+      Vec3::ZERO
+    };
+    let relative: f64 = active
+      .iter()
+      .map(|p| 0.5 * p.mass() as f64 * (p.velocity() - drift).length_squared() as f64)
+      .sum();
+    if relative <= 0. {
+      return None;
+    }
+    let wanted = relative + target - energy;
+    if wanted <= 0. {
+      return None;
+    }
+    let scale = (wanted / relative)
+      .sqrt()
+      .clamp(1. - MAX_CORRECTION, 1. + MAX_CORRECTION) as f32;
+    for p in active.iter_mut() {
+      let velocity = drift + (p.velocity() - drift) * scale;
+      p.set_velocity(velocity);
+    }
+    Some(scale)
+  }
+}
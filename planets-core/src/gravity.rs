@@ -0,0 +1,31 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use glam::Vec3;
+
+use crate::{Accumulator, Body};
+
+/// Gravitational acceleration of every body, zero for inactive ones.
+/// Each pair is computed once and pulls both bodies, in opposite
+/// directions and in proportion to the other's mass. The pull within each
+/// of `except` is left out.
+pub fn accelerations<B: Body>(bodies: &[B], except: &[(usize, usize)], g: f32) -> Vec<Vec3> {
+  let active = |(_, b): &(usize, &B)| b.active();
+  let mut partner = vec![usize::MAX; bodies.len()];
+  for &(i, j) in except {
+    partner[i] = j;
+    partner[j] = i;
+  }
+  let mut totals = vec![Accumulator::default(); bodies.len()];
+  for (i, a) in bodies.iter().enumerate().filter(active) {
+    for (j, b) in bodies.iter().enumerate().skip(i + 1).filter(active) {
+      if partner[i] == j {
+        continue;
+      }
+      let offset = b.pos() - a.pos();
+      let pull = offset.normalize() * g / offset.length_squared();
+      totals[i].add(pull * b.mass());
+      totals[j].add(-pull * a.mass());
+    }
+  }
+  Vec::from_iter(totals.iter().map(Accumulator::sum))
+}
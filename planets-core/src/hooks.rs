@@ -0,0 +1,128 @@
+//! What happened during a step, and callbacks for code that wants to react
+//! to it (sounds, scripts, the event log) without polling the body list.
+//! `S` is whatever the app's stars go through as they age.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::{Body, Impact, Merge};
+
+#[derive(Debug, Clone)]
+pub enum SimEvent<B, S> {
+  /// Two bodies touched and merged.
+  Merge(Merge<B>),
+  /// Crossed the cull distance and started fading out.
+  Ejection(u32),
+  /// A star entered the next stage of its life.
+  Stellar(u32, S),
+  /// Torn apart inside a black hole's or star's Roche radius and fed to it.
+  Shredded(Merge<B>),
+}
+
+type OnCollision<B> = Box<dyn FnMut(&B, &B, Impact)>;
+type OnMerge<B> = Box<dyn FnMut(&B, &B)>;
+type OnEjection<B> = Box<dyn FnMut(&B)>;
+type OnStellar<B, S> = Box<dyn FnMut(&B, S)>;
+type OnShred<B> = Box<dyn FnMut(&B, &B)>;
+
+/// Callbacks run for each event, in the order they were added.
+pub struct Hooks<B, S> {
+  collision: Vec<OnCollision<B>>,
+  merge: Vec<OnMerge<B>>,
+  ejection: Vec<OnEjection<B>>,
+  stellar: Vec<OnStellar<B, S>>,
+  shred: Vec<OnShred<B>>,
+}
+
+impl<B, S> Default for Hooks<B, S> {
+  fn default() -> Self {
+    Hooks {
+      collision: Vec::new(),
+      merge: Vec::new(),
+      ejection: Vec::new(),
+      stellar: Vec::new(),
+      shred: Vec::new(),
+    }
+  }
+}
+
+impl<B: Body, S: Copy> Hooks<B, S> {
+  /// Called on impact with the two bodies, the survivor already merged.
+  pub fn on_collision(&mut self, f: impl FnMut(&B, &B, Impact) + 'static) {
+    self.collision.push(Box::new(f));
+  }
+
+  /// Called after every collision with the survivor and the body it
+  /// absorbed.
+  pub fn on_merge(&mut self, f: impl FnMut(&B, &B) + 'static) {
+    self.merge.push(Box::new(f));
+  }
+
+  pub fn on_ejection(&mut self, f: impl FnMut(&B) + 'static) {
+    self.ejection.push(Box::new(f));
+  }
+
+  /// Called with the star as it is after the transition.
+  pub fn on_stellar(&mut self, f: impl FnMut(&B, S) + 'static) {
+    self.stellar.push(Box::new(f));
+  }
+
+  /// Called with the black hole or star, already fed, and the body it tore
+  /// apart.
+  pub fn on_shred(&mut self, f: impl FnMut(&B, &B) + 'static) {
+    self.shred.push(Box::new(f));
+  }
+
+  /// Runs the callbacks for `events`, which `objects` is the state after.
+  pub fn dispatch(&mut self, events: &[SimEvent<B, S>], objects: &[B]) {
+    // a body a later merge in the same step absorbed is only left in that
+    // event, as it was when it went
+    let absorbed = |i: usize, id: u32| {
+      events[i + 1..].iter().find_map(|e| match e {
+        SimEvent::Merge(m) | SimEvent::Shredded(m) if m.absorbed.id() == id => Some(&m.absorbed),
+        _ => None,
+      })
+    };
+    let find = |i: usize, id: u32| {
+      objects
+        .iter()
+        .find(|p| p.id() == id)
+        .or_else(|| absorbed(i, id))
+    };
+    for (i, event) in events.iter().enumerate() {
+      match event {
+        SimEvent::Merge(Merge {
+          survivor,
+          absorbed,
+          impact,
+        }) => {
+          if let Some(survivor) = find(i, *survivor) {
+            for f in &mut self.collision {
+              f(survivor, absorbed, *impact);
+            }
+            for f in &mut self.merge {
+              f(survivor, absorbed);
+            }
+          }
+        }
+        SimEvent::Ejection(id) => {
+          if let Some(body) = find(i, *id) {
+            self.ejection.iter_mut().for_each(|f| f(body));
+          }
+        }
+        SimEvent::Stellar(id, transition) => {
+          if let Some(star) = find(i, *id) {
+            self.stellar.iter_mut().for_each(|f| f(star, *transition));
+          }
+        }
+        SimEvent::Shredded(Merge {
+          survivor, absorbed, ..
+        }) => {
+          if let Some(hole) = find(i, *survivor) {
+            self.shred.iter_mut().for_each(|f| f(hole, absorbed));
+          }
+        }
+      }
+    }
+  }
+}
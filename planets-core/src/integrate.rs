@@ -0,0 +1,121 @@
+//! Semi-implicit Euler: velocities are kicked by gravity first, then
+//! positions drift with the new velocities.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use glam::Vec3;
+
+use crate::{gravity, kepler, Body};
+
+/// A pair is close when their free-fall or crossing time is under this
+/// many steps, too few for a fixed step to follow their encounter.
+const CLOSE_STEPS: f32 = 10.;
+/// Just inside touching, so `collision::merge_collisions` sees the contact.
+const CONTACT: f64 = 0.999;
+
+/// Pairs of active bodies so close that a step of `dt` can't follow their
+/// encounter, closest first and each body in at most one. Their pull on
+/// each other is left out of `kick`, and `drift` moves them along their
+/// exact two-body orbit instead, so a slingshot plays out the same whatever
+/// the step phase.
+pub fn close_pairs<B: Body>(bodies: &[B], g: f32, dt: f32) -> Vec<(usize, usize)> {
+  let active = |(_, b): &(usize, &B)| b.active();
+  let mut close = Vec::new();
+  for (i, a) in bodies.iter().enumerate().filter(active) {
+    for (j, b) in bodies.iter().enumerate().skip(i + 1).filter(active) {
+      let r = a.pos().distance(b.pos());
+      let free_fall = (r * r * r / (g * (a.mass() + b.mass()))).sqrt();
+      let crossing = r / a.velocity().distance(b.velocity());
+      let time = free_fall.min(crossing);
+      if time < CLOSE_STEPS * dt {
+        close.push((time, i, j));
+      }
+    }
+  }
+  close.sort_by(|a, b| a.0.total_cmp(&b.0));
+  let mut paired = vec![false; bodies.len()];
+  let mut pairs = Vec::new();
+  for (_, i, j) in close {
+    if !paired[i] && !paired[j] {
+      paired[i] = true;
+      paired[j] = true;
+      pairs.push((i, j));
+    }
+  }
+  pairs
+}
+
+/// Accelerates every active body by gravity for `dt`, but for the pull
+/// within each of `pairs`, capping its speed at `speed_limit` if there is
+/// one.
+pub fn kick<B: Body>(
+  bodies: &mut [B],
+  pairs: &[(usize, usize)],
+  g: f32,
+  dt: f32,
+  speed_limit: Option<f32>,
+) {
+  let pulls = gravity::accelerations(bodies, pairs, g);
+  for (body, pull) in bodies.iter_mut().zip(pulls) {
+    if !body.active() {
+      continue;
+    }
+    let mut velocity = body.velocity() + pull * dt;
+    if let Some(limit) = speed_limit {
+      velocity = velocity.clamp_length_max(limit);
+    }
+    body.set_velocity(velocity);
+  }
+}
+
+/// Moves every body along its velocity for `dt`, and each of `pairs` along
+/// its two-body orbit around their center of mass, which moves straight on.
+/// A pair that would touch during the step is left touching, at the speed
+/// it would hit with, for `collision::merge_collisions` to merge.
+pub fn drift<B: Body>(bodies: &mut [B], pairs: &[(usize, usize)], g: f32, dt: f32) {
+  let moved = Vec::from_iter(
+    pairs
+      .iter()
+      .map(|&(i, j)| pair_drift(&bodies[i], &bodies[j], g, dt)),
+  );
+  for body in bodies.iter_mut() {
+    body.set_pos(body.pos() + body.velocity() * dt);
+  }
+  for (&(i, j), [a, b]) in pairs.iter().zip(moved) {
+    for (k, (pos, velocity)) in [(i, a), (j, b)] {
+      bodies[k].set_pos(pos);
+      bodies[k].set_velocity(velocity);
+    }
+  }
+}
+
+/// Positions and velocities of `a` and `b` after `dt` on their own.
+fn pair_drift<B: Body>(a: &B, b: &B, g: f32, dt: f32) -> [(Vec3, Vec3); 2] {
+  let (ma, mb) = (a.mass() as f64, b.mass() as f64);
+  let mass = ma + mb;
+  let mu = g as f64 * mass;
+  let dt = dt as f64;
+  let (pa, pb) = (a.pos().as_dvec3(), b.pos().as_dvec3());
+  let (va, vb) = (a.velocity().as_dvec3(), b.velocity().as_dvec3());
+  let center = (pa * ma + pb * mb) / mass + (va * ma + vb * mb) / mass * dt;
+  let momentum = (va * ma + vb * mb) / mass;
+
+  let (r0, v0) = (pb - pa, vb - va);
+  let (mut r, mut v) = kepler::drift(r0, v0, mu, dt);
+  let touch = (a.radius() + b.radius()) as f64;
+  let passed_periapsis = r0.dot(v0) < 0. && (r.dot(v) >= 0. || kepler::period(r0, v0, mu) <= dt);
+  if r.length() < touch || (passed_periapsis && kepler::periapsis(r0, v0, mu) < touch) {
+    // the speed at contact, from the energy of the orbit
+    let d = touch * CONTACT;
+    let speed = (v0.length_squared() + 2. * mu * (1. / d - 1. / r0.length()))
+      .max(0.)
+      .sqrt();
+    r = r.try_normalize().unwrap_or(r0.normalize()) * d;
+    v = v.try_normalize().unwrap_or(-r0.normalize()) * speed;
+  }
+  [
+    (center - r * (mb / mass), momentum - v * (mb / mass)),
+    (center + r * (ma / mass), momentum + v * (ma / mass)),
+  ]
+  .map(|(pos, velocity)| (pos.as_vec3(), velocity.as_vec3()))
+}
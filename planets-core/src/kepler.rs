@@ -0,0 +1,91 @@
+//! The two-body problem solved exactly: where a relative orbit takes a pair
+//! after a given time, whether it is bound or not. Universal variables keep
+//! ellipses, parabolas and hyperbolas in one formula.
+
+use glam::DVec3;
+
+/// Iterations of the solver, which converges in a handful.
+const MAX_ITERATIONS: usize = 50;
+const TOLERANCE: f64 = 1e-12;
+
+/// Stumpff functions C(z) and S(z), by their series where the closed forms
+/// lose everything to cancellation.
+fn stumpff(z: f64) -> (f64, f64) {
+  if z > 1e-3 {
+    let s = z.sqrt();
+    ((1. - s.cos()) / z, (s - s.sin()) / (z * s))
+  } else if z < -1e-3 {
+    let s = (-z).sqrt();
+    ((s.cosh() - 1.) / -z, (s.sinh() - s) / (-z * s))
+  } else {
+    (
+      1. / 2. - z / 24. + z * z / 720.,
+      1. / 6. - z / 120. + z * z / 5040.,
+    )
+  }
+}
+
+/// Relative position and velocity `dt` later on the orbit of `pos` and
+/// `velocity` around a mass with gravitational parameter `mu`.
+pub fn drift(pos: DVec3, velocity: DVec3, mu: f64, dt: f64) -> (DVec3, DVec3) {
+  let r0 = pos.length();
+  if r0 <= 0. || mu <= 0. {
+    return (pos + velocity * dt, velocity);
+  }
+  let sqrt_mu = mu.sqrt();
+  let sigma = pos.dot(velocity) / sqrt_mu;
+  // inverse of the semi-major axis, negative for hyperbolas
+  let alpha = 2. / r0 - velocity.length_squared() / mu;
+
+  // solved for the universal anomaly with Laguerre's method, which unlike
+  // Newton's doesn't run off on long steps of eccentric orbits
+  let kepler = |x: f64| {
+    let z = alpha * x * x;
+    let (c, s) = stumpff(z);
+    let f = sigma * x * x * c + (1. - alpha * r0) * x * x * x * s + r0 * x - sqrt_mu * dt;
+    let df = sigma * x * (1. - z * s) + (1. - alpha * r0) * x * x * c + r0;
+    let ddf = sigma * (1. - z * c) + (1. - alpha * r0) * x * (1. - z * s);
+    (f, df, ddf)
+  };
+  const N: f64 = 5.;
+  let mut x = sqrt_mu * dt / r0;
+  for _ in 0..MAX_ITERATIONS {
+    let (f, df, ddf) = kepler(x);
+    let root = ((N - 1.) * (N - 1.) * df * df - N * (N - 1.) * f * ddf)
+      .abs()
+      .sqrt();
+    let step = N * f / (df + df.signum() * root);
+    x -= step;
+    if step.abs() <= TOLERANCE * x.abs().max(1.) {
+      break;
+    }
+  }
+
+  let z = alpha * x * x;
+  let (c, s) = stumpff(z);
+  let f = 1. - x * x / r0 * c;
+  let g = dt - x * x * x / sqrt_mu * s;
+  let new_pos = pos * f + velocity * g;
+  let r = new_pos.length();
+  let df = sqrt_mu / (r * r0) * (z * x * s - x);
+  let dg = 1. - x * x / r * c;
+  (new_pos, pos * df + velocity * dg)
+}
+
+/// Closest distance the relative orbit of `pos` and `velocity` comes to.
+pub fn periapsis(pos: DVec3, velocity: DVec3, mu: f64) -> f64 {
+  let r = pos.length();
+  let h = pos.cross(velocity).length_squared();
+  let e =
+    ((pos * (velocity.length_squared() - mu / r) - velocity * pos.dot(velocity)) / mu).length();
+  h / mu / (1. + e)
+}
+
+/// Time one trip around a bound orbit takes, infinite for unbound ones.
+pub fn period(pos: DVec3, velocity: DVec3, mu: f64) -> f64 {
+  let alpha = 2. / pos.length() - velocity.length_squared() / mu;
+  if alpha <= 0. {
+    return f64::INFINITY;
+  }
+  core::f64::consts::TAU * (1. / (alpha * alpha * alpha * mu)).sqrt()
+}
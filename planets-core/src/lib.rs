@@ -0,0 +1,48 @@
+//! The physics of planets without windowing or drawing: gravity,
+//! integration, collisions, radiation and energy bookkeeping for any body
+//! type that implements [`Body`]. Only glam and `alloc` collections are
+//! used, so the same code runs in headless tools, WASM workers and bindings.
+//!
+//! Everything is in three dimensions; flat systems keep z at zero.
+
+extern crate alloc;
+
+mod accumulator;
+pub mod collision;
+pub mod energy;
+pub mod gravity;
+pub mod hooks;
+pub mod integrate;
+pub mod kepler;
+pub mod potential;
+pub mod radiation;
+
+pub use accumulator::Accumulator;
+pub use collision::{Impact, Merge};
+pub use glam::Vec3;
+pub use hooks::{Hooks, SimEvent};
+pub use potential::Potential;
+
+/// A body as far as the physics is concerned. Apps implement it for their
+/// own body type, which is moved and merged as a whole.
+pub trait Body {
+  /// Stable identity, unlike the index which shifts as bodies merge.
+  fn id(&self) -> u32;
+  fn mass(&self) -> f32;
+  fn pos(&self) -> Vec3;
+  fn velocity(&self) -> Vec3;
+  /// Distance from the center at which another body touches it.
+  fn radius(&self) -> f32;
+  /// Whether it pulls and is pulled, collides and counts towards the
+  /// energy. Inactive bodies only coast.
+  fn active(&self) -> bool {
+    true
+  }
+  /// Whether its light pushes on dust, see [`radiation`].
+  fn luminous(&self) -> bool {
+    false
+  }
+  fn set_mass(&mut self, mass: f32);
+  fn set_pos(&mut self, pos: Vec3);
+  fn set_velocity(&mut self, velocity: Vec3);
+}
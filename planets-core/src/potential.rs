@@ -0,0 +1,132 @@
+//! Static background potentials centered on the origin, standing in for
+//! a dark matter halo or a galactic disk that would take far too many
+//! bodies to simulate.
+
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+use glam::{Vec3, Vec3Swizzles};
+
+use crate::Body;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Potential {
+  /// Logarithmic halo: circular orbits go round at `speed` whatever their
+  /// size, once well outside the `core` radius. This is the flat rotation
+  /// curve of real galaxies.
+  Halo { speed: f32, core: f32 },
+  /// Kuzmin disk of `mass` in the x-y plane, `scale` setting how quickly
+  /// its density falls off with radius.
+  Disk { mass: f32, scale: f32 },
+}
+
+impl Potential {
+  pub fn acceleration(&self, pos: Vec3, g: f32) -> Vec3 {
+    match *self {
+      Potential::Halo { speed, core } => {
+        -speed * speed * pos / (pos.length_squared() + core * core)
+      }
+      Potential::Disk { mass, scale } => {
+        let height = scale + pos.z.abs();
+        let d2 = pos.xy().length_squared() + height * height;
+        let pull = g * mass / (d2 * d2.sqrt());
+        // the disk is infinitely thin, so in it there is no pull across it
+        let across = if pos.z == 0. {
+          0.
+        } else {
+          height.copysign(pos.z)
+        };
+        -pull * pos.xy().extend(across)
+      }
+    }
+  }
+}
+
+/// Accelerates every active body by the potential for `dt`.
+pub fn kick<B: Body>(bodies: &mut [B], potential: &Potential, g: f32, dt: f32) {
+  for body in bodies.iter_mut().filter(|b| b.active()) {
+    let velocity = body.velocity() + potential.acceleration(body.pos(), g) * dt;
+    body.set_velocity(velocity);
+  }
+}
+
+/// Written as `halo <speed> <core>` or `disk <mass> <scale>`.
+impl fmt::Display for Potential {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Potential::Halo { speed, core } => write!(f, "halo {} {}", speed, core),
+      Potential::Disk { mass, scale } => write!(f, "disk {} {}", mass, scale),
+    }
+  }
+}
+
+impl FromStr for Potential {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let words = Vec::from_iter(s.split_whitespace());
+    let number = |s: &str| {
+      s.parse::<f32>()
+        .ok()
+        .filter(|n| n.is_finite() && *n >= 0.)
+        .ok_or_else(|| format!("invalid number `{}`", s))
+    };
+    // a zero core or scale makes the pull at the origin 0 / 0
+    let length = |s: &str| match number(s)? {
+      n if n > 0. => Ok(n),
+      _ => Err(format!("`{}` must be above 0", s)),
+    };
+    match words[..] {
+      ["halo", speed, core] => Ok(Potential::Halo {
+        speed: number(speed)?,
+        core: length(core)?,
+      }),
+      ["disk", mass, scale] => Ok(Potential::Disk {
+        mass: number(mass)?,
+        scale: length(scale)?,
+      }),
+      _ => Err("expected `halo <speed> <core>` or `disk <mass> <scale>`".to_owned()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse() {
+    assert_eq!(
+      "halo 0.5 100".parse(),
+      Ok(Potential::Halo {
+        speed: 0.5,
+        core: 100.
+      })
+    );
+    assert_eq!(
+      "disk 2000 50".parse(),
+      Ok(Potential::Disk {
+        mass: 2000.,
+        scale: 50.
+      })
+    );
+  }
+
+  #[test]
+  fn zero_core_or_scale() {
+    assert!("halo 0.5 0".parse::<Potential>().is_err());
+    assert!("disk 2000 0".parse::<Potential>().is_err());
+    assert!("disk 0 50".parse::<Potential>().is_ok());
+  }
+
+  #[test]
+  fn malformed() {
+    assert!("halo 0.5".parse::<Potential>().is_err());
+    assert!("halo -1 10".parse::<Potential>().is_err());
+    assert!("disk inf 10".parse::<Potential>().is_err());
+    assert!("ring 1 2".parse::<Potential>().is_err());
+  }
+}
@@ -0,0 +1,68 @@
+//! Starlight pushing on small bodies: radiation pressure that cancels part
+//! of their star's pull, and Poynting–Robertson drag that slowly spirals
+//! them in.
+
+use alloc::vec::Vec;
+use glam::Vec3;
+
+use crate::Body;
+
+/// Radiation pressure over gravity for a body of exactly the dust mass.
+const EDGE_BETA: f32 = 0.2;
+/// Most radiation pressure over gravity, for the finest dust.
+const MAX_BETA: f32 = 2.;
+
+/// Which bodies count as dust, and how strongly light drags on them.
+#[derive(Debug, Clone, Copy)]
+pub struct Radiation {
+  /// Bodies lighter than this feel the light, 0 for none.
+  pub dust_mass: f32,
+  /// Sets the drag, which grows as light slows down.
+  pub light_speed: f32,
+}
+
+impl Radiation {
+  /// Radiation pressure over gravity: a grain's cross-section falls more
+  /// slowly than its mass, so lighter dust is pushed harder. Above 0.5 dust
+  /// shed from a circular orbit is blown out of the system.
+  pub fn beta(&self, mass: f32) -> f32 {
+    if mass <= 0. || mass >= self.dust_mass {
+      return 0.;
+    }
+    (EDGE_BETA * (self.dust_mass / mass).cbrt()).min(MAX_BETA)
+  }
+}
+
+/// Accelerates dust by the light of every luminous body for `dt`.
+pub fn kick<B: Body>(bodies: &mut [B], g: f32, dt: f32, radiation: &Radiation) {
+  let sources = Vec::from_iter(
+    bodies
+      .iter()
+      .filter(|b| b.active() && b.luminous())
+      .map(|b| (b.pos(), b.velocity(), b.mass())),
+  );
+  if sources.is_empty() {
+    return;
+  }
+  for body in bodies.iter_mut() {
+    let beta = radiation.beta(body.mass());
+    if beta == 0. || !body.active() || body.luminous() {
+      continue;
+    }
+    let mut push = Vec3::ZERO;
+    for &(pos, velocity, mass) in &sources {
+      let offset = body.pos() - pos;
+      let r2 = offset.length_squared();
+      if r2 == 0. {
+        continue;
+      }
+      let out = offset / r2.sqrt();
+      let relative = body.velocity() - velocity;
+      let c = radiation.light_speed;
+      // Burns, Lamy & Soter (1979): the push is along the light, less the
+      // share of it the body runs away from, and the drag against its motion
+      push += beta * g * mass / r2 * ((1. - relative.dot(out) / c) * out - relative / c);
+    }
+    body.set_velocity(body.velocity() + push * dt);
+  }
+}
@@ -0,0 +1,45 @@
+/* C interface to the planets physics, built as the `planets` cdylib by the
+ * planets-ffi crate. */
+
+#ifndef PLANETS_H
+#define PLANETS_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct PlanetsBody {
+  uint32_t id;
+  float mass;
+  /* Distance from the center at which another body touches it. */
+  float radius;
+  float x, y, z;
+  float vx, vy, vz;
+} PlanetsBody;
+
+/* Opaque simulation handle. */
+typedef struct PlanetsSim PlanetsSim;
+
+/* A simulation of `count` bodies copied from `bodies`, pulling each other
+ * with gravitational constant `g`. `bodies` may be NULL when `count` is 0. */
+PlanetsSim *planets_sim_new(float g, const PlanetsBody *bodies, size_t count);
+
+/* Advances the simulation by `dt` and merges bodies that touch, the
+ * heavier keeping its id. */
+void planets_sim_step(PlanetsSim *sim, float dt);
+
+/* Copies up to `capacity` bodies into `out` and returns how many there are
+ * in all, so a short buffer can be grown and the call repeated. */
+size_t planets_sim_bodies(const PlanetsSim *sim, PlanetsBody *out, size_t capacity);
+
+/* Frees a simulation. NULL is ignored. */
+void planets_sim_free(PlanetsSim *sim);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
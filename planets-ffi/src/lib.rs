@@ -0,0 +1,130 @@
+//! A C ABI over `planets-core`, declared in `planets.h`: a simulation
+//! behind an opaque handle that is created from an array of bodies,
+//! stepped, read back and freed.
+
+use planets_core::{collision, integrate, Body, Vec3};
+use std::slice;
+
+/// A body as C sees it, with the same layout as `PlanetsBody`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PlanetsBody {
+  pub id: u32,
+  pub mass: f32,
+  /// Distance from the center at which another body touches it.
+  pub radius: f32,
+  pub x: f32,
+  pub y: f32,
+  pub z: f32,
+  pub vx: f32,
+  pub vy: f32,
+  pub vz: f32,
+}
+
+impl Body for PlanetsBody {
+  fn id(&self) -> u32 {
+    self.id
+  }
+
+  fn mass(&self) -> f32 {
+    self.mass
+  }
+
+  fn pos(&self) -> Vec3 {
+    Vec3::new(self.x, self.y, self.z)
+  }
+
+  fn velocity(&self) -> Vec3 {
+    Vec3::new(self.vx, self.vy, self.vz)
+  }
+
+  fn radius(&self) -> f32 {
+    self.radius
+  }
+
+  fn set_mass(&mut self, mass: f32) {
+    self.mass = mass;
+  }
+
+  fn set_pos(&mut self, pos: Vec3) {
+    (self.x, self.y, self.z) = pos.into();
+  }
+
+  fn set_velocity(&mut self, velocity: Vec3) {
+    (self.vx, self.vy, self.vz) = velocity.into();
+  }
+}
+
+/// The handle C holds, never looked into from that side.
+pub struct PlanetsSim {
+  bodies: Vec<PlanetsBody>,
+  g: f32,
+}
+
+/// A simulation of `count` bodies copied from `bodies`, pulling each other
+/// with gravitational constant `g`. Free it with `planets_sim_free`.
+///
+/// # Safety
+///
+/// `bodies` must point to `count` bodies, or may be null when `count` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn planets_sim_new(
+  g: f32,
+  bodies: *const PlanetsBody,
+  count: usize,
+) -> *mut PlanetsSim {
+  let bodies = match count {
+    0 => Vec::new(),
+    _ => slice::from_raw_parts(bodies, count).to_vec(),
+  };
+  Box::into_raw(Box::new(PlanetsSim { bodies, g }))
+}
+
+/// Advances the simulation by `dt` and merges bodies that touch, the
+/// heavier keeping its id.
+///
+/// # Safety
+///
+/// `sim` must come from `planets_sim_new` and not have been freed.
+#[no_mangle]
+pub unsafe extern "C" fn planets_sim_step(sim: *mut PlanetsSim, dt: f32) {
+  let sim = &mut *sim;
+  let pairs = integrate::close_pairs(&sim.bodies, sim.g, dt);
+  integrate::kick(&mut sim.bodies, &pairs, sim.g, dt, None);
+  integrate::drift(&mut sim.bodies, &pairs, sim.g, dt);
+  collision::merge_collisions(&mut sim.bodies);
+}
+
+/// Copies up to `capacity` bodies into `out` and returns how many there
+/// are in all, so a short buffer can be grown and the call repeated.
+///
+/// # Safety
+///
+/// `sim` must come from `planets_sim_new` and not have been freed, and
+/// `out` must have room for `capacity` bodies, or may be null when
+/// `capacity` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn planets_sim_bodies(
+  sim: *const PlanetsSim,
+  out: *mut PlanetsBody,
+  capacity: usize,
+) -> usize {
+  let sim = &*sim;
+  let copied = sim.bodies.len().min(capacity);
+  if copied > 0 {
+    slice::from_raw_parts_mut(out, copied).copy_from_slice(&sim.bodies[..copied]);
+  }
+  sim.bodies.len()
+}
+
+/// Frees a simulation. Null is ignored.
+///
+/// # Safety
+///
+/// `sim` must come from `planets_sim_new` and not have been freed already.
+#[no_mangle]
+pub unsafe extern "C" fn planets_sim_free(sim: *mut PlanetsSim) {
+  if !sim.is_null() {
+    drop(Box::from_raw(sim));
+  }
+}